@@ -25,6 +25,8 @@ impl BoundProcedure {
                 };
                 builtin.call(ctx, self.operands)
             }
+            Procedure::Record(record) => record.call(interpreter, self.range, self.operands),
+            Procedure::Derived(derived) => derived.call(interpreter, self.range, self.operands),
         }
     }
 }
@@ -23,21 +23,47 @@ pub struct BuiltinProcedure {
     pub name: InternedString,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub enum BuiltinProcedureFn {
     Nullary(fn(BuiltinProcedureContext) -> CallableResult),
     Unary(fn(BuiltinProcedureContext, &SourceValue) -> CallableResult),
     Binary(fn(BuiltinProcedureContext, &SourceValue, &SourceValue) -> CallableResult),
+    Ternary(
+        fn(BuiltinProcedureContext, &SourceValue, &SourceValue, &SourceValue) -> CallableResult,
+    ),
     NullaryVariadic(fn(BuiltinProcedureContext, &[SourceValue]) -> CallableResult),
     UnaryVariadic(fn(BuiltinProcedureContext, &SourceValue, &[SourceValue]) -> CallableResult),
 }
 
+impl BuiltinProcedureFn {
+    fn address(&self) -> usize {
+        match self {
+            BuiltinProcedureFn::Nullary(func) => *func as usize,
+            BuiltinProcedureFn::Unary(func) => *func as usize,
+            BuiltinProcedureFn::Binary(func) => *func as usize,
+            BuiltinProcedureFn::Ternary(func) => *func as usize,
+            BuiltinProcedureFn::NullaryVariadic(func) => *func as usize,
+            BuiltinProcedureFn::UnaryVariadic(func) => *func as usize,
+        }
+    }
+}
+
+/// Note that the same function isn't guaranteed to have the same address
+/// everywhere, nor different functions different addresses, so this is
+/// only as reliable as comparing function pointers can be.
+impl PartialEq for BuiltinProcedureFn {
+    fn eq(&self, other: &Self) -> bool {
+        self.address() == other.address()
+    }
+}
+
 impl BuiltinProcedure {
     pub fn is_valid_arity(&self, operands_len: usize) -> bool {
         match self.func {
             BuiltinProcedureFn::Nullary(_) => operands_len == 0,
             BuiltinProcedureFn::Unary(_) => operands_len == 1,
             BuiltinProcedureFn::Binary(_) => operands_len == 2,
+            BuiltinProcedureFn::Ternary(_) => operands_len == 3,
             BuiltinProcedureFn::NullaryVariadic(_) => true,
             BuiltinProcedureFn::UnaryVariadic(_) => operands_len >= 1,
         }
@@ -48,6 +74,9 @@ impl BuiltinProcedure {
             BuiltinProcedureFn::Nullary(func) => (func)(ctx),
             BuiltinProcedureFn::Unary(func) => (func)(ctx, &operands[0]),
             BuiltinProcedureFn::Binary(func) => (func)(ctx, &operands[0], &operands[1]),
+            BuiltinProcedureFn::Ternary(func) => {
+                (func)(ctx, &operands[0], &operands[1], &operands[2])
+            }
             BuiltinProcedureFn::NullaryVariadic(func) => (func)(ctx, &operands[..]),
             BuiltinProcedureFn::UnaryVariadic(func) => (func)(ctx, &operands[0], &operands[1..]),
         }
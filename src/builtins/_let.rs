@@ -67,7 +67,7 @@ fn parse_bindings(
 fn eval_body(ctx: &mut SpecialFormContext) -> Result<CallableSuccess, RuntimeError> {
     let body = Body::try_new(&ctx.operands[1..], ctx.range)?;
     ctx.interpreter
-        .eval_expressions_in_tail_context(body.as_ref())
+        .eval_body_with_internal_defines(body.as_ref())
 }
 
 fn named_let(mut ctx: SpecialFormContext, variable: &InternedString) -> CallableResult {
@@ -298,6 +298,45 @@ mod tests {
         assert!(interpreter.get("x").is_none());
     }
 
+    #[test]
+    fn let_body_supports_mutually_recursive_defines() {
+        test_eval_success(
+            "
+            (let ((n 11))
+              (define (my-even? n) (if (= n 0) #t (my-odd? (- n 1))))
+              (define (my-odd? n) (if (= n 0) #f (my-even? (- n 1))))
+              (list (my-even? n) (my-odd? n)))
+            ",
+            "(#f #t)",
+        );
+    }
+
+    #[test]
+    fn internal_defines_shadow_outer_bindings_throughout_body() {
+        test_eval_success(
+            "
+            (define x 'outer)
+            (define (f)
+              (set! x 'changed)
+              (define x 'inner)
+              x)
+            (list (f) x)
+            ",
+            "(inner outer)",
+        );
+        test_eval_success(
+            "
+            (define x 'outer)
+            (let* ((y 1))
+              (set! x 'changed)
+              (define x 'inner)
+              x)
+            x
+            ",
+            "outer",
+        );
+    }
+
     #[test]
     fn named_let_works() {
         test_eval_success(
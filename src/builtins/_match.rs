@@ -0,0 +1,180 @@
+use crate::{
+    builtins::Builtin,
+    callable::CallableResult,
+    compound_procedure::Body,
+    interpreter::{RuntimeError, RuntimeErrorType},
+    source_mapped::{SourceMappable, SourceMapped},
+    special_form::SpecialFormContext,
+    string_interner::InternedString,
+    value::{SourceValue, Value},
+};
+
+use super::eq::is_equal;
+
+pub fn get_builtins() -> super::Builtins {
+    vec![Builtin::SpecialForm("match", _match)]
+}
+
+type PatternBindings = Vec<(InternedString, SourceValue)>;
+
+/// Attempts to match the given value against the given pattern, adding any
+/// variables bound by the pattern to `bindings`.
+///
+/// Patterns can be:
+///
+/// * `_`, which matches anything.
+/// * A symbol, which matches anything and binds it to the symbol.
+/// * A number, string, boolean, or character, which matches an `equal?` value.
+/// * A quoted datum, which matches an `equal?` value.
+/// * A pair, which matches a pair whose car and cdr match the pattern's car
+///   and cdr, e.g. `(a b . rest)`.
+/// * The empty list, which matches the empty list.
+fn match_pattern(
+    pattern: &SourceValue,
+    value: &SourceValue,
+    bindings: &mut PatternBindings,
+) -> Result<bool, RuntimeError> {
+    match &pattern.0 {
+        Value::Symbol(symbol) => {
+            if symbol.as_ref() != "_" {
+                bindings.push((symbol.clone(), value.clone()));
+            }
+            Ok(true)
+        }
+        Value::EmptyList => Ok(matches!(value.0, Value::EmptyList)),
+        Value::Number(_) | Value::String(_) | Value::Boolean(_) | Value::Character(_) => {
+            is_equal(pattern, value)
+        }
+        Value::Pair(pattern_pair) => {
+            if let Some(datum) = try_get_quoted_datum(pattern) {
+                return is_equal(&datum, value);
+            }
+            let Value::Pair(value_pair) = &value.0 else {
+                return Ok(false);
+            };
+            Ok(
+                match_pattern(&pattern_pair.car(), &value_pair.car(), bindings)?
+                    && match_pattern(&pattern_pair.cdr(), &value_pair.cdr(), bindings)?,
+            )
+        }
+        _ => Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(pattern.1)),
+    }
+}
+
+/// If the given pattern is of the form `(quote datum)`, returns the datum.
+fn try_get_quoted_datum(pattern: &SourceValue) -> Option<SourceValue> {
+    let list = pattern.try_into_list()?.0;
+    match list.as_slice() {
+        [SourceMapped(Value::Symbol(symbol), _), datum] if symbol.as_ref() == "quote" => {
+            Some(datum.clone())
+        }
+        _ => None,
+    }
+}
+
+fn _match(ctx: SpecialFormContext) -> CallableResult {
+    let Some((expression, clauses)) = ctx.operands.split_first() else {
+        return Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(ctx.range));
+    };
+    let value = ctx.interpreter.eval_expression(expression)?;
+    for clause in clauses {
+        let Some(clause) = clause.try_into_list() else {
+            return Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(clause.1));
+        };
+        let Some((pattern, body)) = clause.0.split_first() else {
+            return Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(clause.1));
+        };
+        let body = Body::try_new(body, clause.1)?;
+        let mut bindings = vec![];
+        if !match_pattern(pattern, &value, &mut bindings)? {
+            continue;
+        }
+        ctx.interpreter.environment.push_inherited(clause.1);
+        for (variable, value) in bindings {
+            ctx.interpreter.environment.define(variable, value);
+        }
+        let result = ctx
+            .interpreter
+            .eval_body_with_internal_defines(body.as_ref())?;
+
+        // Note that the environment won't have been popped if an error occured above--this is
+        // so we can examine it afterwards, if needed. It's up to the caller to clean things
+        // up after an error.
+        ctx.interpreter.environment.pop();
+
+        return Ok(result);
+    }
+    Err(RuntimeErrorType::NoMatchingPattern.source_mapped(ctx.range))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        interpreter::RuntimeErrorType,
+        test_util::{test_eval_err, test_eval_success, test_eval_successes},
+    };
+
+    #[test]
+    fn match_destructures_lists() {
+        test_eval_success("(match '(1 2 3) ((a . rest) (list a rest)))", "(1 (2 3))");
+        test_eval_success("(match '(1 2 3) ((a b c) (+ a b c)))", "6");
+        test_eval_success("(match '(1 2 3) ((a b) 'two) ((a b c) 'three))", "three");
+        test_eval_success("(match '(1 (2 3)) ((a (b c)) (list c b a)))", "(3 2 1)");
+        test_eval_success("(match '() (() 'empty))", "empty");
+        test_eval_success("(match '(1) (() 'empty) ((x) x))", "1");
+    }
+
+    #[test]
+    fn match_supports_wildcards() {
+        let mut interpreter = test_eval_successes(&[("(match '(1 2 3) ((_ b _) b))", "2")]);
+        let underscore = interpreter.intern("_");
+        interpreter.eval_err(
+            "(match '(1 2) ((_ b) _))",
+            RuntimeErrorType::UnboundVariable(underscore),
+        );
+    }
+
+    #[test]
+    fn match_supports_literals() {
+        test_eval_success("(match 5 (4 'four) (5 'five))", "five");
+        test_eval_success(r#"(match "hi" ("hi" 'hi) (_ 'other))"#, "hi");
+        test_eval_success(r"(match #\a (#\a 'a) (_ 'other))", "a");
+        test_eval_success("(match #f (#t 'yes) (#f 'no))", "no");
+        test_eval_success(
+            "(match '(add 1 2) (('add a b) (+ a b)) (('sub a b) (- a b)))",
+            "3",
+        );
+        test_eval_success(
+            "(match '(sub 1 2) (('add a b) (+ a b)) (('sub a b) (- a b)))",
+            "-1",
+        );
+        test_eval_success("(match '(1 2) ('(1 2) 'yes))", "yes");
+    }
+
+    #[test]
+    fn match_bindings_are_scoped_to_clause() {
+        test_eval_success("(define a 5) (match 1 (a a)) a", "5");
+    }
+
+    #[test]
+    fn match_is_properly_tail_recursive() {
+        test_eval_success(
+            "
+            (define (len lst acc)
+              (match lst
+                (() acc)
+                ((_ . rest) (len rest (+ acc 1)))))
+            (len (list-tabulate 10000 identity) 0)
+            ",
+            "10000",
+        );
+    }
+
+    #[test]
+    fn match_raises_errors() {
+        test_eval_err("(match)", RuntimeErrorType::MalformedSpecialForm);
+        test_eval_err("(match 1 (2 'two))", RuntimeErrorType::NoMatchingPattern);
+        test_eval_err("(match 1 (1))", RuntimeErrorType::MalformedSpecialForm);
+        test_eval_err("(match 1 5)", RuntimeErrorType::MalformedSpecialForm);
+    }
+}
@@ -0,0 +1,131 @@
+use crate::{
+    builtin_procedure::{BuiltinProcedureContext, BuiltinProcedureFn},
+    builtins::Builtin,
+    callable::CallableResult,
+    value::{SourceValue, Value},
+};
+
+use super::util::optional_radix_arg;
+
+pub fn get_builtins() -> super::Builtins {
+    vec![
+        Builtin::Procedure("digit-value", BuiltinProcedureFn::Unary(digit_value)),
+        Builtin::Procedure(
+            "char->digit",
+            BuiltinProcedureFn::UnaryVariadic(char_to_digit),
+        ),
+        Builtin::Procedure("char-upcase", BuiltinProcedureFn::Unary(char_upcase)),
+        Builtin::Procedure("char-downcase", BuiltinProcedureFn::Unary(char_downcase)),
+        Builtin::Procedure("char-foldcase", BuiltinProcedureFn::Unary(char_foldcase)),
+    ]
+}
+
+/// Returns the numeric value of the given character in the given radix, or
+/// false if it isn't a digit in that radix.
+fn char_digit(char: &SourceValue, radix: u32) -> CallableResult {
+    Ok(match char.expect_character()?.to_digit(radix) {
+        Some(digit) => (digit as f64).into(),
+        None => false.into(),
+    })
+}
+
+fn digit_value(_ctx: BuiltinProcedureContext, char: &SourceValue) -> CallableResult {
+    char_digit(char, 10)
+}
+
+fn char_to_digit(
+    ctx: BuiltinProcedureContext,
+    char: &SourceValue,
+    rest: &[SourceValue],
+) -> CallableResult {
+    char_digit(char, optional_radix_arg(ctx.range, rest)?)
+}
+
+/// Returns the full case folding of the given character, which can consist of
+/// more than one character (e.g. `ß` folds to `ss`).
+///
+/// This is mostly the same as lowercasing, except for a few characters that
+/// have no single lowercase equivalent to compare against.
+pub fn fold_case(char: char) -> String {
+    match char {
+        'ß' | 'ẞ' => "ss".to_string(),
+        'ς' => "σ".to_string(),
+        _ => char.to_lowercase().collect(),
+    }
+}
+
+/// Maps the given character using the given full case mapping. Since a
+/// character procedure has to return a single character, characters whose
+/// mapping consists of more than one character are left unchanged.
+fn map_char<I: Iterator<Item = char>, F: FnOnce(char) -> I>(
+    char: &SourceValue,
+    func: F,
+) -> CallableResult {
+    let char = char.expect_character()?;
+    let mut mapped = func(char);
+    let result = match (mapped.next(), mapped.next()) {
+        (Some(mapped), None) => mapped,
+        _ => char,
+    };
+    Ok(Value::Character(result).into())
+}
+
+fn char_upcase(_ctx: BuiltinProcedureContext, char: &SourceValue) -> CallableResult {
+    map_char(char, char::to_uppercase)
+}
+
+fn char_downcase(_ctx: BuiltinProcedureContext, char: &SourceValue) -> CallableResult {
+    map_char(char, char::to_lowercase)
+}
+
+fn char_foldcase(_ctx: BuiltinProcedureContext, char: &SourceValue) -> CallableResult {
+    map_char(char, |char| {
+        fold_case(char).chars().collect::<Vec<_>>().into_iter()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        interpreter::RuntimeErrorType,
+        test_util::{test_eval_err, test_eval_success},
+    };
+
+    #[test]
+    fn digit_value_works() {
+        test_eval_success(r"(digit-value #\7)", "7");
+        test_eval_success(r"(digit-value #\0)", "0");
+        test_eval_success(r"(digit-value #\a)", "#f");
+        test_eval_err("(digit-value 7)", RuntimeErrorType::ExpectedCharacter);
+    }
+
+    #[test]
+    fn char_case_conversion_works() {
+        test_eval_success(r"(char-upcase #\a)", r"#\A");
+        test_eval_success(r"(char-upcase #\A)", r"#\A");
+        test_eval_success(r"(char-upcase #\1)", r"#\1");
+        test_eval_success(r"(char-upcase #\()", r"#\(");
+        test_eval_success(r"(list 1 (char-upcase #\a) #\1 2)", r"(1 #\A #\1 2)");
+        test_eval_success(r"(char-downcase #\Σ)", r"#\σ");
+        test_eval_success(r"(char-foldcase #\A)", r"#\a");
+        test_eval_success(r"(char-foldcase #\ς)", r"#\σ");
+        // These map to more than one character, so they're left alone.
+        test_eval_success(r"(char-upcase #\ß)", r"#\ß");
+        test_eval_success(r"(char-foldcase #\ß)", r"#\ß");
+        test_eval_err(r#"(char-upcase "a")"#, RuntimeErrorType::ExpectedCharacter);
+    }
+
+    #[test]
+    fn char_to_digit_works() {
+        test_eval_success(r"(char->digit #\7)", "7");
+        test_eval_success(r"(char->digit #\f 16)", "15");
+        test_eval_success(r"(char->digit #\F 16)", "15");
+        test_eval_success(r"(char->digit #\2 2)", "#f");
+        test_eval_err(r"(char->digit #\2 1)", RuntimeErrorType::InvalidRadix);
+        test_eval_err(r"(char->digit #\2 17)", RuntimeErrorType::InvalidRadix);
+        test_eval_err(
+            r"(char->digit #\2 2 2)",
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+    }
+}
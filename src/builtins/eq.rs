@@ -1,14 +1,29 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    mem::discriminant,
+    rc::Rc,
+};
+
 use crate::{
     builtin_procedure::{BuiltinProcedureContext, BuiltinProcedureFn},
     builtins::Builtin,
     callable::{Callable, CallableResult},
     interpreter::RuntimeError,
+    pair::PairComparisonSet,
     procedure::Procedure,
     value::{SourceValue, Value},
+    vector::VectorComparisonSet,
 };
 
 pub fn get_builtins() -> super::Builtins {
-    vec![Builtin::Procedure("eq?", BuiltinProcedureFn::Binary(eq))]
+    vec![
+        Builtin::Procedure("eq?", BuiltinProcedureFn::Binary(eq)),
+        Builtin::Procedure("eqv?", BuiltinProcedureFn::Binary(eqv)),
+        Builtin::Procedure("equal?", BuiltinProcedureFn::Binary(equal)),
+        Builtin::Procedure("boolean=?", BuiltinProcedureFn::NullaryVariadic(boolean_eq)),
+        Builtin::Procedure("symbol=?", BuiltinProcedureFn::NullaryVariadic(symbol_eq)),
+    ]
 }
 
 pub fn is_eq(a: &SourceValue, b: &SourceValue) -> Result<bool, RuntimeError> {
@@ -31,6 +46,14 @@ pub fn is_eq(a: &SourceValue, b: &SourceValue) -> Result<bool, RuntimeError> {
             Value::String(b) => a.points_at_same_memory_as(b),
             _ => false,
         },
+        Value::Character(a) => match b.0 {
+            Value::Character(b) => a == &b,
+            _ => false,
+        },
+        Value::Vector(a) => match &b.0 {
+            Value::Vector(b) => a.points_at_same_memory_as(b),
+            _ => false,
+        },
         Value::Callable(Callable::SpecialForm(a)) => match &b.0 {
             Value::Callable(Callable::SpecialForm(b)) => a.func == b.func,
             _ => false,
@@ -43,20 +66,210 @@ pub fn is_eq(a: &SourceValue, b: &SourceValue) -> Result<bool, RuntimeError> {
             Value::Callable(Callable::Procedure(Procedure::Compound(b))) => a.id() == b.id(),
             _ => false,
         },
+        Value::Callable(Callable::Procedure(Procedure::Record(a))) => match &b.0 {
+            Value::Callable(Callable::Procedure(Procedure::Record(b))) => a.is_same_procedure_as(b),
+            _ => false,
+        },
+        Value::Callable(Callable::Procedure(Procedure::Derived(a))) => match &b.0 {
+            Value::Callable(Callable::Procedure(Procedure::Derived(b))) => a.id() == b.id(),
+            _ => false,
+        },
         Value::Pair(a) => match &b.0 {
             Value::Pair(b) => a.points_at_same_memory_as(b),
             _ => false,
         },
+        Value::Record(a) => match &b.0 {
+            Value::Record(b) => a.points_at_same_memory_as(b),
+            _ => false,
+        },
+        Value::HashTable(a) => match &b.0 {
+            Value::HashTable(b) => a.points_at_same_memory_as(b),
+            _ => false,
+        },
+        Value::EofObject => matches!(b.0, Value::EofObject),
+        Value::Port(a) => match &b.0 {
+            Value::Port(b) => a.points_at_same_memory_as(b),
+            _ => false,
+        },
+        Value::Environment(a) => match &b.0 {
+            Value::Environment(b) => a.points_at_same_memory_as(b),
+            _ => false,
+        },
+        Value::MultipleValues(a) => match &b.0 {
+            Value::MultipleValues(b) => Rc::ptr_eq(a, b),
+            _ => false,
+        },
     })
 }
 
+/// Since our numbers are all floats, and `eq?` compares characters by value,
+/// there's no difference between `eqv?` and `eq?` in our implementation.
+pub fn is_eqv(a: &SourceValue, b: &SourceValue) -> Result<bool, RuntimeError> {
+    is_eq(a, b)
+}
+
+#[derive(Default)]
+struct EqualVisitedSet {
+    pairs: PairComparisonSet,
+    vectors: VectorComparisonSet,
+}
+
+/// Recursively compares pairs, vectors and strings, using `eqv?` for everything
+/// else.
+///
+/// This terminates on cyclic structures: if we encounter two pairs (or vectors)
+/// that we've already started comparing, we consider them equal, since any
+/// difference between them will be found by the comparison that's already in
+/// progress.
+///
+/// The same bookkeeping also means we never compare the same two pairs (or
+/// vectors) twice, which keeps structures with lots of shared substructure
+/// from taking exponential time to compare. This is sound because a
+/// comparison that finds a difference makes the whole `equal?` return false.
+pub fn is_equal(a: &SourceValue, b: &SourceValue) -> Result<bool, RuntimeError> {
+    let mut visited = EqualVisitedSet::default();
+    // We keep our own stack of comparisons left to make, rather than
+    // recursing, so deeply nested structures don't overflow the stack.
+    let mut pending = vec![(a.clone(), b.clone())];
+    while let Some((a, b)) = pending.pop() {
+        match (&a.0, &b.0) {
+            (Value::Pair(a_pair), Value::Pair(b_pair)) => {
+                if visited.pairs.add(a_pair, b_pair) {
+                    pending.push((a_pair.cdr(), b_pair.cdr()));
+                    pending.push((a_pair.car(), b_pair.car()));
+                }
+            }
+            (Value::String(a_string), Value::String(b_string)) => {
+                if a_string != b_string {
+                    return Ok(false);
+                }
+            }
+            (Value::Vector(a_vector), Value::Vector(b_vector)) => {
+                if visited.vectors.add(a_vector, b_vector) {
+                    let a_items = a_vector.items();
+                    let b_items = b_vector.items();
+                    if a_items.len() != b_items.len() {
+                        return Ok(false);
+                    }
+                    pending.extend(a_items.iter().cloned().zip(b_items.iter().cloned()).rev());
+                }
+            }
+            _ => {
+                if !is_eqv(&a, &b)? {
+                    return Ok(false);
+                }
+            }
+        }
+    }
+    Ok(true)
+}
+
+/// The maximum number of values that `equal_hash()` will look at.
+const MAX_EQUAL_HASH_VALUES: usize = 32;
+
+/// Returns a hash that's consistent with `equal?`, i.e. any two values that
+/// are `equal?` to each other have the same hash.
+///
+/// To terminate on cyclic structures (and to keep hashing large structures
+/// cheap), this only looks at the first `MAX_EQUAL_HASH_VALUES` values it
+/// encounters during a depth-first traversal. Since the traversal is
+/// determined solely by the structure of the value, and `equal?` values have
+/// the same structure, they will always visit the same values in the same
+/// order.
+pub fn equal_hash(value: &SourceValue) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    let mut remaining = MAX_EQUAL_HASH_VALUES;
+    equal_hash_recursive(value, &mut hasher, &mut remaining);
+    hasher.finish()
+}
+
+fn equal_hash_recursive(value: &SourceValue, hasher: &mut DefaultHasher, remaining: &mut usize) {
+    let mut value = value.clone();
+    // We iterate, rather than recurse, through the cdrs of lists.
+    while *remaining > 0 {
+        *remaining -= 1;
+        discriminant(&value.0).hash(hasher);
+        let next = match &value.0 {
+            Value::Pair(pair) => {
+                equal_hash_recursive(&pair.car(), hasher, remaining);
+                pair.cdr()
+            }
+            Value::Vector(vector) => {
+                for item in vector.items().iter() {
+                    equal_hash_recursive(item, hasher, remaining);
+                }
+                return;
+            }
+            Value::String(string) => {
+                string.to_string().hash(hasher);
+                return;
+            }
+            Value::Number(number) => {
+                // Make sure that 0 and -0, which are equal, have the same hash.
+                let number = if *number == 0.0 { 0.0 } else { *number };
+                number.to_bits().hash(hasher);
+                return;
+            }
+            Value::Symbol(symbol) => {
+                symbol.hash(hasher);
+                return;
+            }
+            Value::Boolean(boolean) => {
+                boolean.hash(hasher);
+                return;
+            }
+            Value::Character(char) => {
+                char.hash(hasher);
+                return;
+            }
+            // Everything else is compared by identity, so we just leave it
+            // with the hash of its type.
+            _ => return,
+        };
+        value = next;
+    }
+}
+
 fn eq(_ctx: BuiltinProcedureContext, a: &SourceValue, b: &SourceValue) -> CallableResult {
     Ok(is_eq(a, b)?.into())
 }
 
+fn eqv(_ctx: BuiltinProcedureContext, a: &SourceValue, b: &SourceValue) -> CallableResult {
+    Ok(is_eqv(a, b)?.into())
+}
+
+fn equal(_ctx: BuiltinProcedureContext, a: &SourceValue, b: &SourceValue) -> CallableResult {
+    Ok(is_equal(a, b)?.into())
+}
+
+/// Returns whether all the given values are equal to each other, after
+/// converting each of them with the given `expect_*` function.
+fn all_equal<T, F>(operands: &[SourceValue], expect: F) -> Result<bool, RuntimeError>
+where
+    T: PartialEq,
+    F: Fn(&SourceValue) -> Result<T, RuntimeError>,
+{
+    let mut values = Vec::with_capacity(operands.len());
+    for operand in operands {
+        values.push(expect(operand)?);
+    }
+    Ok(values.windows(2).all(|pair| pair[0] == pair[1]))
+}
+
+fn boolean_eq(_ctx: BuiltinProcedureContext, operands: &[SourceValue]) -> CallableResult {
+    Ok(all_equal(operands, SourceValue::expect_boolean)?.into())
+}
+
+fn symbol_eq(_ctx: BuiltinProcedureContext, operands: &[SourceValue]) -> CallableResult {
+    Ok(all_equal(operands, SourceValue::expect_identifier)?.into())
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::test_util::test_eval_success;
+    use crate::{
+        interpreter::RuntimeErrorType,
+        test_util::{test_eval_err, test_eval_success, test_eval_successes},
+    };
 
     #[test]
     fn eq_works() {
@@ -84,4 +297,105 @@ mod tests {
         test_eval_success(r#"(eq? "blarg" "blarg")"#, "#f");
         test_eval_success(r#"(define x "blarg") (eq? x x)"#, "#t");
     }
+
+    #[test]
+    fn eqv_works() {
+        // From R5RS section 6.1.
+        test_eval_success("(eqv? 'a 'a)", "#t");
+        test_eval_success("(eqv? 'a 'b)", "#f");
+        test_eval_success("(eqv? 2 2)", "#t");
+        test_eval_success("(eqv? '() '())", "#t");
+        test_eval_success("(eqv? 100000000 100000000)", "#t");
+        test_eval_success("(eqv? (cons 1 2) (cons 1 2))", "#f");
+        test_eval_success("(eqv? (lambda () 1) (lambda () 2))", "#f");
+        test_eval_success("(eqv? #f 'nil)", "#f");
+        test_eval_success("(let ((p (lambda (x) x))) (eqv? p p))", "#t");
+    }
+
+    #[test]
+    fn equal_works() {
+        // From R5RS section 6.1.
+        test_eval_success("(equal? 'a 'a)", "#t");
+        test_eval_success("(equal? '(a) '(a))", "#t");
+        test_eval_success("(equal? '(a (b) c) '(a (b) c))", "#t");
+        test_eval_success(r#"(equal? "abc" "abc")"#, "#t");
+        test_eval_success("(equal? 2 2)", "#t");
+
+        test_eval_success(r#"(equal? "abc" "abd")"#, "#f");
+        test_eval_success("(equal? '(a (b) c) '(a (b) d))", "#f");
+        test_eval_success("(equal? '(a b) '(a b c))", "#f");
+        test_eval_success("(equal? '(a . b) '(a . b))", "#t");
+    }
+
+    #[test]
+    fn boolean_eq_works() {
+        test_eval_success("(boolean=? #t #t)", "#t");
+        test_eval_success("(boolean=? #f #f #f)", "#t");
+        test_eval_success("(boolean=? #t #t #f)", "#f");
+        test_eval_success("(boolean=? #t)", "#t");
+        test_eval_err("(boolean=? #f '())", RuntimeErrorType::ExpectedBoolean);
+    }
+
+    #[test]
+    fn symbol_eq_works() {
+        test_eval_success("(symbol=? 'a 'a)", "#t");
+        test_eval_success("(symbol=? 'a 'a 'a)", "#t");
+        test_eval_success("(symbol=? 'a 'a 'b)", "#f");
+        test_eval_err(r#"(symbol=? 'a "a")"#, RuntimeErrorType::ExpectedIdentifier);
+    }
+
+    #[test]
+    fn equal_is_fast_on_shared_structures() {
+        // Each of these has 2^40 paths through it, so comparing them naively
+        // would never finish.
+        test_eval_successes(&[
+            (
+                "(define (nest n x) (if (= n 0) x (nest (- n 1) (cons x x))))",
+                "",
+            ),
+            ("(equal? (nest 40 '(1)) (nest 40 '(1)))", "#t"),
+            ("(equal? (nest 40 '(1)) (nest 40 '(2)))", "#f"),
+            ("(equal? (nest 40 '(1)) (nest 39 '(1)))", "#f"),
+        ]);
+    }
+
+    #[test]
+    fn equal_works_on_deeply_nested_lists() {
+        let interpreter = test_eval_successes(&[
+            (
+                "(define (nest n x) (if (= n 0) x (nest (- n 1) (list x))))",
+                "",
+            ),
+            ("(define a (nest 10000 '(1)))", ""),
+            ("(define b (nest 10000 '(1)))", ""),
+            ("(equal? a b)", "#t"),
+            ("(equal? a (list b))", "#f"),
+        ]);
+        // Dropping lists this deeply nested would itself overflow the stack.
+        std::mem::forget(interpreter);
+    }
+
+    #[test]
+    fn equal_terminates_on_cyclic_lists() {
+        test_eval_success(
+            "
+            (define x '(1 . 2))
+            (set-cdr! x x)
+            (define y '(1 . 2))
+            (set-cdr! y y)
+            (equal? x y)
+            ",
+            "#t",
+        );
+        test_eval_success(
+            "
+            (define x '(1 . 2))
+            (set-cdr! x x)
+            (define y '(2 . 2))
+            (set-cdr! y y)
+            (equal? x y)
+            ",
+            "#f",
+        );
+    }
 }
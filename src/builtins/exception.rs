@@ -0,0 +1,243 @@
+use crate::{
+    builtin_procedure::{BuiltinProcedureContext, BuiltinProcedureFn},
+    builtins::Builtin,
+    callable::CallableResult,
+    interpreter::{RuntimeError, RuntimeErrorType},
+    source_mapped::SourceMappable,
+    value::SourceValue,
+};
+
+/// Note that since we don't have continuations, handlers can't escape from
+/// the code that raised an exception. Instead, they're called at the point
+/// the exception was raised, which is all that `raise-continuable` needs.
+pub fn get_builtins() -> super::Builtins {
+    vec![
+        Builtin::Procedure(
+            "with-exception-handler",
+            BuiltinProcedureFn::Binary(with_exception_handler),
+        ),
+        Builtin::Procedure("raise", BuiltinProcedureFn::Unary(raise)),
+        Builtin::Procedure(
+            "raise-continuable",
+            BuiltinProcedureFn::Unary(raise_continuable),
+        ),
+        Builtin::Procedure("dynamic-wind", BuiltinProcedureFn::Ternary(dynamic_wind)),
+    ]
+}
+
+fn with_exception_handler(
+    ctx: BuiltinProcedureContext,
+    handler: &SourceValue,
+    thunk: &SourceValue,
+) -> CallableResult {
+    let handler = handler.expect_procedure()?;
+    let thunk = thunk.expect_procedure()?;
+    ctx.interpreter.exception_handlers.push(handler);
+    let result = ctx.interpreter.call_procedure(thunk, &[], ctx.range);
+    // Unlike the call stack, we want to unwind this even if an error
+    // occurred, since the error may be handled by an outer handler.
+    ctx.interpreter.exception_handlers.pop();
+    Ok(result?.into())
+}
+
+/// Since we don't have continuations, the only way to leave the dynamic
+/// extent of the thunk other than returning is via an error, so we make sure
+/// to call `after` when one propagates through us.
+fn dynamic_wind(
+    ctx: BuiltinProcedureContext,
+    before: &SourceValue,
+    thunk: &SourceValue,
+    after: &SourceValue,
+) -> CallableResult {
+    let before = before.expect_procedure()?;
+    let thunk = thunk.expect_procedure()?;
+    let after = after.expect_procedure()?;
+    ctx.interpreter.call_procedure(before, &[], ctx.range)?;
+    let depth = ctx.interpreter.stack_depth();
+    let result = ctx.interpreter.call_procedure(thunk, &[], ctx.range);
+    // An error leaves the thunk's frames on the stack, which could keep
+    // `after` from being called at all if the error was a stack overflow.
+    ctx.interpreter.truncate_stack(depth);
+    // If this fails, its error takes precedence over any error from the thunk.
+    ctx.interpreter.call_procedure(after, &[], ctx.range)?;
+    Ok(result?.into())
+}
+
+/// Calls the innermost exception handler with the given object, returning
+/// whatever the handler returns.
+///
+/// As in R7RS, the handler is called with the next-outermost handler
+/// installed, so that anything it raises goes to that one.
+fn call_current_handler(
+    ctx: &mut BuiltinProcedureContext,
+    obj: &SourceValue,
+) -> Result<SourceValue, RuntimeError> {
+    let Some(handler) = ctx.interpreter.exception_handlers.pop() else {
+        return Err(RuntimeErrorType::UncaughtException(obj.0.to_string()).source_mapped(ctx.range));
+    };
+    let result =
+        ctx.interpreter
+            .call_procedure(handler.clone(), std::slice::from_ref(obj), ctx.range);
+    ctx.interpreter.exception_handlers.push(handler);
+    result
+}
+
+fn raise(mut ctx: BuiltinProcedureContext, obj: &SourceValue) -> CallableResult {
+    call_current_handler(&mut ctx, obj)?;
+    Err(RuntimeErrorType::ExceptionHandlerReturned.source_mapped(ctx.range))
+}
+
+fn raise_continuable(mut ctx: BuiltinProcedureContext, obj: &SourceValue) -> CallableResult {
+    Ok(call_current_handler(&mut ctx, obj)?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        interpreter::{Interpreter, RuntimeErrorType},
+        test_util::{test_eval_err, test_eval_success, test_eval_successes, TestInterpreter},
+    };
+
+    #[test]
+    fn raise_continuable_returns_handler_result() {
+        // From R7RS section 6.11.
+        test_eval_success(
+            "
+            (with-exception-handler
+              (lambda (con)
+                (if (string? con) (display con) (display \"a warning has been issued\"))
+                42)
+              (lambda ()
+                (+ (raise-continuable \"should be a number\") 23)))
+            ",
+            "should be a number65",
+        );
+    }
+
+    #[test]
+    fn handlers_are_called_with_outer_handler_installed() {
+        test_eval_success(
+            "
+            (with-exception-handler
+              (lambda (x) (list 'outer x))
+              (lambda ()
+                (with-exception-handler
+                  (lambda (x) (list 'inner (raise-continuable x)))
+                  (lambda () (raise-continuable 1)))))
+            ",
+            "(inner (outer 1))",
+        );
+    }
+
+    #[test]
+    fn handlers_are_uninstalled_after_thunk_returns() {
+        test_eval_err(
+            "
+            (with-exception-handler (lambda (x) x) (lambda () 1))
+            (raise-continuable 'oops)
+            ",
+            RuntimeErrorType::UncaughtException("oops".to_string()),
+        );
+    }
+
+    #[test]
+    fn dynamic_wind_works() {
+        test_eval_success(
+            "
+            (dynamic-wind
+              (lambda () (display \"before \"))
+              (lambda () (display \"during \") 5)
+              (lambda () (display \"after \")))
+            ",
+            "before during after 5",
+        );
+        test_eval_err(
+            "(dynamic-wind 1 (lambda () 1) (lambda () 1))",
+            RuntimeErrorType::ExpectedProcedure,
+        );
+    }
+
+    #[test]
+    fn dynamic_wind_calls_after_when_errors_propagate() {
+        let mut interpreter = test_eval_successes(&[(
+            "
+            (define log '())
+            (define (note x) (set! log (cons x log)))
+            ",
+            "",
+        )]);
+        interpreter.eval_err(
+            "
+            (dynamic-wind
+              (lambda () (note 'outer-before))
+              (lambda ()
+                (dynamic-wind
+                  (lambda () (note 'inner-before))
+                  (lambda () (car '()))
+                  (lambda () (note 'inner-after))))
+              (lambda () (note 'outer-after)))
+            ",
+            RuntimeErrorType::ExpectedPair,
+        );
+        interpreter.eval_success("log", "(outer-after inner-after inner-before outer-before)");
+    }
+
+    #[test]
+    fn dynamic_wind_does_not_call_thunk_if_before_fails() {
+        let mut interpreter = test_eval_successes(&[("(define log '())", "")]);
+        interpreter.eval_err(
+            "
+            (dynamic-wind
+              (lambda () (car '()))
+              (lambda () (set! log (cons 'during log)))
+              (lambda () (set! log (cons 'after log))))
+            ",
+            RuntimeErrorType::ExpectedPair,
+        );
+        interpreter.eval_success("log", "()");
+    }
+
+    #[test]
+    fn dynamic_wind_calls_after_when_stack_overflows() {
+        let mut interpreter = Interpreter::new();
+        interpreter.max_stack_size = 20;
+        let mut interpreter = TestInterpreter::new(interpreter);
+        interpreter.eval_success(
+            "
+            (define log '())
+            (define (f) (+ 1 (f)))
+            ",
+            "",
+        );
+        interpreter.eval_err(
+            "
+            (dynamic-wind
+              (lambda () #f)
+              f
+              (lambda () (set! log (cons 'after log))))
+            ",
+            RuntimeErrorType::StackOverflow,
+        );
+        interpreter.eval_success("log", "(after)");
+    }
+
+    #[test]
+    fn raise_errors_if_handler_returns() {
+        test_eval_err(
+            "(with-exception-handler (lambda (x) x) (lambda () (raise 'oops)))",
+            RuntimeErrorType::ExceptionHandlerReturned,
+        );
+    }
+
+    #[test]
+    fn uncaught_exceptions_raise_errors() {
+        test_eval_err(
+            "(raise '(1 2))",
+            RuntimeErrorType::UncaughtException("(1 2)".to_string()),
+        );
+        test_eval_err(
+            "(raise-continuable 5)",
+            RuntimeErrorType::UncaughtException("5".to_string()),
+        );
+    }
+}
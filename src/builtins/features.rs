@@ -0,0 +1,137 @@
+use crate::{
+    builtin_procedure::{BuiltinProcedureContext, BuiltinProcedureFn},
+    builtins::Builtin,
+    callable::CallableResult,
+    interpreter::{RuntimeError, RuntimeErrorType},
+    source_mapped::{SourceMappable, SourceMapped},
+    special_form::SpecialFormContext,
+    value::{SourceValue, Value},
+};
+
+use super::is_else;
+
+pub fn get_builtins() -> super::Builtins {
+    vec![
+        Builtin::Procedure("features", BuiltinProcedureFn::Nullary(features)),
+        Builtin::SpecialForm("cond-expand", cond_expand),
+    ]
+}
+
+/// The features supported by the interpreter, as reported by `features` and
+/// tested by `cond-expand`.
+const FEATURES: &[&str] = &["r5rs", "tail-calls", "ascheme"];
+
+fn features(ctx: BuiltinProcedureContext) -> CallableResult {
+    let symbols = FEATURES
+        .iter()
+        .map(|feature| Value::Symbol(ctx.interpreter.string_interner.intern(feature)).into())
+        .collect();
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(symbols)
+        .source_mapped(ctx.range)
+        .into())
+}
+
+/// Returns whether the given feature requirement is satisfied. A requirement
+/// is either a feature identifier or an `and`, `or`, or `not` of other
+/// requirements.
+fn is_requirement_met(requirement: &SourceValue) -> Result<bool, RuntimeError> {
+    if let Value::Symbol(symbol) = &requirement.0 {
+        return Ok(FEATURES.contains(&symbol.as_ref()));
+    }
+    let malformed = || RuntimeErrorType::MalformedSpecialForm.source_mapped(requirement.1);
+    let list = requirement.try_into_list().ok_or_else(malformed)?.0;
+    let Some((SourceMapped(Value::Symbol(operator), _), operands)) = list.split_first() else {
+        return Err(malformed());
+    };
+    match operator.as_ref() {
+        "and" => {
+            for operand in operands {
+                if !is_requirement_met(operand)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        "or" => {
+            for operand in operands {
+                if is_requirement_met(operand)? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+        "not" => match operands {
+            [operand] => Ok(!is_requirement_met(operand)?),
+            _ => Err(malformed()),
+        },
+        _ => Err(malformed()),
+    }
+}
+
+fn cond_expand(ctx: SpecialFormContext) -> CallableResult {
+    let last_index = ctx.operands.len().saturating_sub(1);
+    for (i, clause) in ctx.operands.iter().enumerate() {
+        let Some(clause) = clause.try_into_list() else {
+            return Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(clause.1));
+        };
+        let Some((requirement, body)) = clause.0.split_first() else {
+            return Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(clause.1));
+        };
+        let is_else_clause = is_else(requirement);
+        if is_else_clause && i != last_index {
+            return Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(clause.1));
+        }
+        if is_else_clause || is_requirement_met(requirement)? {
+            return ctx.interpreter.eval_expressions_in_tail_context(body);
+        }
+    }
+    ctx.undefined()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        interpreter::RuntimeErrorType,
+        test_util::{test_eval_err, test_eval_success},
+    };
+
+    #[test]
+    fn features_works() {
+        test_eval_success("(memq 'tail-calls (features))", "(tail-calls ascheme)");
+        test_eval_success("(memq 'r5rs (features))", "(r5rs tail-calls ascheme)");
+    }
+
+    #[test]
+    fn cond_expand_works() {
+        test_eval_success("(cond-expand (tail-calls 1) (else 2))", "1");
+        test_eval_success("(cond-expand (blarg 1) (else 2))", "2");
+        test_eval_success("(cond-expand (blarg 1))", "");
+        test_eval_success("(cond-expand ((and r5rs tail-calls) 1) (else 2))", "1");
+        test_eval_success("(cond-expand ((and r5rs blarg) 1) (else 2))", "2");
+        test_eval_success("(cond-expand ((or blarg r5rs) 1) (else 2))", "1");
+        test_eval_success("(cond-expand ((not blarg) 1) (else 2))", "1");
+        test_eval_success("(cond-expand ((and) 1))", "1");
+        test_eval_success("(cond-expand (r5rs (define x 5))) x", "5");
+    }
+
+    #[test]
+    fn cond_expand_raises_errors() {
+        test_eval_err("(cond-expand 5)", RuntimeErrorType::MalformedSpecialForm);
+        test_eval_err("(cond-expand ())", RuntimeErrorType::MalformedSpecialForm);
+        test_eval_err(
+            "(cond-expand ((nand r5rs) 1))",
+            RuntimeErrorType::MalformedSpecialForm,
+        );
+        test_eval_err(
+            "(cond-expand ((not r5rs blarg) 1))",
+            RuntimeErrorType::MalformedSpecialForm,
+        );
+        test_eval_err(
+            "(cond-expand (else 1) (r5rs 2))",
+            RuntimeErrorType::MalformedSpecialForm,
+        );
+    }
+}
@@ -0,0 +1,375 @@
+use crate::{
+    builtin_procedure::{BuiltinProcedureContext, BuiltinProcedureFn},
+    builtins::Builtin,
+    callable::CallableResult,
+    hash_table::HashTable,
+    interpreter::{RuntimeError, RuntimeErrorType},
+    source_mapped::SourceMappable,
+    value::{SourceValue, Value},
+};
+
+use super::{
+    eq::{equal_hash, is_equal},
+    util::expect_elements,
+};
+
+/// Note that all hash tables currently compare their keys via `equal?`.
+pub fn get_builtins() -> super::Builtins {
+    vec![
+        Builtin::Procedure(
+            "make-hash-table",
+            BuiltinProcedureFn::Nullary(make_hash_table),
+        ),
+        Builtin::Procedure("hash-table?", BuiltinProcedureFn::Unary(hash_table)),
+        Builtin::Procedure(
+            "hash-table-set!",
+            BuiltinProcedureFn::Ternary(hash_table_set),
+        ),
+        Builtin::Procedure(
+            "hash-table-ref/default",
+            BuiltinProcedureFn::Ternary(hash_table_ref_default),
+        ),
+        Builtin::Procedure(
+            "hash-table-contains?",
+            BuiltinProcedureFn::Binary(hash_table_contains),
+        ),
+        Builtin::Procedure(
+            "hash-table-delete!",
+            BuiltinProcedureFn::Binary(hash_table_delete),
+        ),
+        Builtin::Procedure(
+            "hash-table-size",
+            BuiltinProcedureFn::Unary(hash_table_size),
+        ),
+        Builtin::Procedure(
+            "hash-table-update!",
+            BuiltinProcedureFn::UnaryVariadic(hash_table_update),
+        ),
+        Builtin::Procedure(
+            "hash-table-update!/default",
+            BuiltinProcedureFn::UnaryVariadic(hash_table_update_default),
+        ),
+        Builtin::Procedure(
+            "hash-table->alist",
+            BuiltinProcedureFn::Unary(hash_table_to_alist),
+        ),
+        Builtin::Procedure(
+            "alist->hash-table",
+            BuiltinProcedureFn::Unary(alist_to_hash_table),
+        ),
+        Builtin::Procedure("equal-hash", BuiltinProcedureFn::Unary(equal_hash_builtin)),
+    ]
+}
+
+fn make_hash_table(ctx: BuiltinProcedureContext) -> CallableResult {
+    let hash_table = ctx.interpreter.hash_table_manager.hash_table();
+    Ok(Value::HashTable(hash_table).source_mapped(ctx.range).into())
+}
+
+fn hash_table(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(matches!(value.0, Value::HashTable(_)).into())
+}
+
+fn hash_table_set(
+    ctx: BuiltinProcedureContext,
+    table: &SourceValue,
+    key: &SourceValue,
+    value: &SourceValue,
+) -> CallableResult {
+    set(&table.expect_hash_table()?, key, value.clone())?;
+    ctx.undefined()
+}
+
+fn hash_table_ref_default(
+    _ctx: BuiltinProcedureContext,
+    table: &SourceValue,
+    key: &SourceValue,
+    default: &SourceValue,
+) -> CallableResult {
+    let value = get(&table.expect_hash_table()?, key)?;
+    Ok(value.unwrap_or_else(|| default.clone()).into())
+}
+
+fn hash_table_contains(
+    _ctx: BuiltinProcedureContext,
+    table: &SourceValue,
+    key: &SourceValue,
+) -> CallableResult {
+    let value = get(&table.expect_hash_table()?, key)?;
+    Ok(value.is_some().into())
+}
+
+fn hash_table_delete(
+    ctx: BuiltinProcedureContext,
+    table: &SourceValue,
+    key: &SourceValue,
+) -> CallableResult {
+    table
+        .expect_hash_table()?
+        .delete(equal_hash(key), |other| is_equal(key, other))?;
+    ctx.undefined()
+}
+
+fn hash_table_size(_ctx: BuiltinProcedureContext, table: &SourceValue) -> CallableResult {
+    Ok((table.expect_hash_table()?.len() as f64).into())
+}
+
+fn get(table: &HashTable, key: &SourceValue) -> Result<Option<SourceValue>, RuntimeError> {
+    table.get(equal_hash(key), |other| is_equal(key, other))
+}
+
+fn set(table: &HashTable, key: &SourceValue, value: SourceValue) -> Result<(), RuntimeError> {
+    table.set(
+        equal_hash(key),
+        |other| is_equal(key, other),
+        key.clone(),
+        value,
+    )
+}
+
+/// Sets the value for the given key to the result of calling the given
+/// procedure with its current value. If the key isn't present, the procedure
+/// is called with whatever `get_default` returns.
+fn update<F>(
+    ctx: BuiltinProcedureContext,
+    table: &SourceValue,
+    key: &SourceValue,
+    proc: &SourceValue,
+    get_default: F,
+) -> CallableResult
+where
+    F: FnOnce(&mut BuiltinProcedureContext) -> Result<SourceValue, RuntimeError>,
+{
+    let mut ctx = ctx;
+    let table = table.expect_hash_table()?;
+    let procedure = proc.expect_procedure()?;
+    let value = match get(&table, key)? {
+        Some(value) => value,
+        None => get_default(&mut ctx)?,
+    };
+    let new_value = ctx
+        .interpreter
+        .call_procedure(procedure, &[value], ctx.range)?;
+    set(&table, key, new_value)?;
+    ctx.undefined()
+}
+
+/// Takes a key, a procedure, and an optional thunk that returns the value to
+/// use if the key isn't present. If there's no thunk, a missing key is an
+/// error.
+fn hash_table_update(
+    ctx: BuiltinProcedureContext,
+    table: &SourceValue,
+    rest: &[SourceValue],
+) -> CallableResult {
+    match rest {
+        [key, proc] => update(ctx, table, key, proc, |ctx| {
+            Err(RuntimeErrorType::KeyNotFound.source_mapped(ctx.range))
+        }),
+        [key, proc, thunk] => {
+            let thunk = thunk.expect_procedure()?;
+            update(ctx, table, key, proc, |ctx| {
+                ctx.interpreter.call_procedure(thunk, &[], ctx.range)
+            })
+        }
+        _ => Err(RuntimeErrorType::WrongNumberOfArguments.source_mapped(ctx.range)),
+    }
+}
+
+/// Takes a key, a procedure, and the value to use if the key isn't present.
+fn hash_table_update_default(
+    ctx: BuiltinProcedureContext,
+    table: &SourceValue,
+    rest: &[SourceValue],
+) -> CallableResult {
+    match rest {
+        [key, proc, default] => update(ctx, table, key, proc, |_ctx| Ok(default.clone())),
+        _ => Err(RuntimeErrorType::WrongNumberOfArguments.source_mapped(ctx.range)),
+    }
+}
+
+fn hash_table_to_alist(ctx: BuiltinProcedureContext, table: &SourceValue) -> CallableResult {
+    let pairs = table
+        .expect_hash_table()?
+        .entries()
+        .into_iter()
+        .map(|(key, value)| {
+            Value::Pair(ctx.interpreter.pair_manager.pair(key, value)).source_mapped(ctx.range)
+        })
+        .collect();
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(pairs)
+        .source_mapped(ctx.range)
+        .into())
+}
+
+/// If a key appears more than once, its first association takes precedence.
+fn alist_to_hash_table(ctx: BuiltinProcedureContext, alist: &SourceValue) -> CallableResult {
+    let pairs = expect_elements(&alist.expect_list()?, "pair", SourceValue::expect_pair)?;
+    let table = ctx.interpreter.hash_table_manager.hash_table();
+    for pair in pairs.iter().rev() {
+        set(&table, &pair.car(), pair.cdr())?;
+    }
+    Ok(Value::HashTable(table).source_mapped(ctx.range).into())
+}
+
+fn equal_hash_builtin(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    // Our numbers are floats, so make sure the hash fits in one without
+    // losing precision.
+    Ok(((equal_hash(value) & ((1 << f64::MANTISSA_DIGITS) - 1)) as f64).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        interpreter::RuntimeErrorType,
+        test_util::{test_eval_err, test_eval_success, test_eval_successes},
+    };
+
+    #[test]
+    fn hash_tables_work() {
+        test_eval_successes(&[
+            ("(define h (make-hash-table))", ""),
+            ("h", "#<hash-table 0>"),
+            ("(hash-table? h)", "#t"),
+            ("(hash-table? '())", "#f"),
+            ("(hash-table-set! h 'a 1)", ""),
+            ("(hash-table-ref/default h 'a 0)", "1"),
+            ("(hash-table-ref/default h 'b 0)", "0"),
+            ("(hash-table-contains? h 'a)", "#t"),
+            ("(hash-table-contains? h 'b)", "#f"),
+            ("(hash-table-set! h 'a 2)", ""),
+            ("(hash-table-ref/default h 'a 0)", "2"),
+            ("(hash-table-size h)", "1"),
+            ("(hash-table-delete! h 'a)", ""),
+            ("(hash-table-contains? h 'a)", "#f"),
+            ("(hash-table-size h)", "0"),
+        ]);
+    }
+
+    #[test]
+    fn hash_tables_support_structured_keys() {
+        test_eval_successes(&[
+            ("(define h (make-hash-table))", ""),
+            ("(hash-table-set! h (list 1 \"two\" #\\3) 'list)", ""),
+            ("(hash-table-set! h (list->vector '(1 2)) 'vector)", ""),
+            ("(hash-table-set! h \"hi\" 'string)", ""),
+            (
+                "(hash-table-ref/default h (list 1 \"two\" #\\3) #f)",
+                "list",
+            ),
+            ("(hash-table-ref/default h (list 1 \"two\" #\\4) #f)", "#f"),
+            (
+                "(hash-table-ref/default h (list->vector '(1 2)) #f)",
+                "vector",
+            ),
+            (
+                "(hash-table-ref/default h (list->string '(#\\h #\\i)) #f)",
+                "string",
+            ),
+            ("(hash-table-size h)", "3"),
+        ]);
+    }
+
+    #[test]
+    fn hash_table_update_works() {
+        test_eval_successes(&[
+            ("(define h (make-hash-table))", ""),
+            ("(hash-table-set! h 'a 1)", ""),
+            ("(hash-table-update! h 'a (lambda (x) (+ x 10)))", ""),
+            ("(hash-table-ref/default h 'a #f)", "11"),
+            (
+                "(hash-table-update! h 'a (lambda (x) (+ x 10)) (lambda () 0))",
+                "",
+            ),
+            ("(hash-table-ref/default h 'a #f)", "21"),
+            (
+                "(hash-table-update! h 'b (lambda (x) (+ x 10)) (lambda () 0))",
+                "",
+            ),
+            ("(hash-table-ref/default h 'b #f)", "10"),
+            (
+                "(hash-table-update!/default h 'c (lambda (x) (cons 1 x)) '())",
+                "",
+            ),
+            (
+                "(hash-table-update!/default h 'c (lambda (x) (cons 2 x)) '())",
+                "",
+            ),
+            ("(hash-table-ref/default h 'c #f)", "(2 1)"),
+        ]);
+    }
+
+    #[test]
+    fn hash_table_update_without_default_requires_key() {
+        test_eval_err(
+            "(hash-table-update! (make-hash-table) 'a (lambda (x) x))",
+            RuntimeErrorType::KeyNotFound,
+        );
+    }
+
+    #[test]
+    fn hash_table_alist_conversion_works() {
+        test_eval_successes(&[
+            (
+                "(define h (alist->hash-table '((a . 1) (b . 2) (a . 3))))",
+                "",
+            ),
+            ("(hash-table-size h)", "2"),
+            ("(hash-table-ref/default h 'a #f)", "1"),
+            ("(hash-table-ref/default h 'b #f)", "2"),
+            (
+                "(hash-table->alist (alist->hash-table '((a . 1))))",
+                "((a . 1))",
+            ),
+            ("(hash-table->alist (make-hash-table))", "()"),
+            ("(define h2 (alist->hash-table (hash-table->alist h)))", ""),
+            ("(hash-table-ref/default h2 'a #f)", "1"),
+            ("(hash-table-ref/default h2 'b #f)", "2"),
+        ]);
+        test_eval_err(
+            "(alist->hash-table '((a . 1) b))",
+            RuntimeErrorType::WrongElementType {
+                index: 1,
+                expected: "pair",
+                actual: "symbol",
+            },
+        );
+    }
+
+    #[test]
+    fn equal_hash_is_consistent_with_equal() {
+        test_eval_success(
+            "(= (equal-hash '(1 (2 3))) (equal-hash (list 1 (list 2 3))))",
+            "#t",
+        );
+        test_eval_success("(= (equal-hash 0) (equal-hash -0))", "#t");
+        test_eval_success("(= (equal-hash \"a\") (equal-hash \"b\"))", "#f");
+    }
+
+    #[test]
+    fn equal_hash_terminates_on_cyclic_structures() {
+        test_eval_successes(&[
+            ("(define x (list 1 2))", ""),
+            ("(set-cdr! (cdr x) x)", ""),
+            ("(define h (make-hash-table))", ""),
+            ("(hash-table-set! h x 'cyclic)", ""),
+            ("(hash-table-ref/default h x #f)", "cyclic"),
+        ]);
+    }
+
+    #[test]
+    fn hash_tables_are_garbage_collected() {
+        test_eval_success(
+            "(define h (make-hash-table)) (hash-table-set! h 'self h) (define h 0) (gc)",
+            "1",
+        );
+    }
+
+    #[test]
+    fn non_hash_tables_raise_errors() {
+        test_eval_err("(hash-table-size '())", RuntimeErrorType::ExpectedHashTable);
+    }
+}
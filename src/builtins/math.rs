@@ -3,11 +3,15 @@ use crate::{
     builtins::Builtin,
     callable::CallableResult,
     interpreter::{RuntimeError, RuntimeErrorType},
+    mutable_string::MutableString,
     source_mapped::SourceMappable,
-    value::SourceValue,
+    value::{SourceValue, Value},
 };
 
-use super::util::number_args;
+use super::{
+    util::{number_args, optional_radix_arg},
+    values::make_values,
+};
 
 pub fn get_builtins() -> super::Builtins {
     vec![
@@ -16,15 +20,185 @@ pub fn get_builtins() -> super::Builtins {
         Builtin::Procedure("*", BuiltinProcedureFn::NullaryVariadic(multiply)),
         Builtin::Procedure("/", BuiltinProcedureFn::UnaryVariadic(divide)),
         Builtin::Procedure("sqrt", BuiltinProcedureFn::Unary(sqrt)),
+        Builtin::Procedure(
+            "exact-integer-sqrt",
+            BuiltinProcedureFn::Unary(exact_integer_sqrt),
+        ),
+        Builtin::Procedure("square", BuiltinProcedureFn::Unary(square)),
+        Builtin::Procedure("exact", BuiltinProcedureFn::Unary(exact)),
+        Builtin::Procedure("inexact->exact", BuiltinProcedureFn::Unary(exact)),
+        Builtin::Procedure("inexact", BuiltinProcedureFn::Unary(inexact)),
+        Builtin::Procedure("exact->inexact", BuiltinProcedureFn::Unary(inexact)),
         Builtin::Procedure("remainder", BuiltinProcedureFn::Binary(remainder)),
+        Builtin::Procedure("quotient", BuiltinProcedureFn::Binary(quotient)),
+        Builtin::Procedure("modulo", BuiltinProcedureFn::Binary(modulo)),
+        // R7RS names for the above. Since they're registered with the same
+        // functions, they're `eq?` to the originals.
+        Builtin::Procedure("truncate-remainder", BuiltinProcedureFn::Binary(remainder)),
+        Builtin::Procedure("truncate-quotient", BuiltinProcedureFn::Binary(quotient)),
+        Builtin::Procedure("floor-remainder", BuiltinProcedureFn::Binary(modulo)),
+        Builtin::Procedure("floor-quotient", BuiltinProcedureFn::Binary(floor_quotient)),
+        Builtin::Procedure("floor/", BuiltinProcedureFn::Binary(floor_divide)),
+        Builtin::Procedure("truncate/", BuiltinProcedureFn::Binary(truncate_divide)),
+        Builtin::Procedure("floor", BuiltinProcedureFn::Unary(floor)),
+        Builtin::Procedure("ceiling", BuiltinProcedureFn::Unary(ceiling)),
+        Builtin::Procedure("truncate", BuiltinProcedureFn::Unary(truncate)),
+        Builtin::Procedure("round", BuiltinProcedureFn::Unary(round)),
+        Builtin::Procedure("max", BuiltinProcedureFn::UnaryVariadic(max)),
+        Builtin::Procedure("min", BuiltinProcedureFn::UnaryVariadic(min)),
+        Builtin::Procedure("positive?", BuiltinProcedureFn::Unary(positive)),
+        Builtin::Procedure("negative?", BuiltinProcedureFn::Unary(negative)),
+        Builtin::Procedure("odd?", BuiltinProcedureFn::Unary(odd)),
+        Builtin::Procedure("even?", BuiltinProcedureFn::Unary(even)),
+        Builtin::Procedure("nan?", BuiltinProcedureFn::Unary(nan)),
+        Builtin::Procedure("infinite?", BuiltinProcedureFn::Unary(infinite)),
+        Builtin::Procedure("finite?", BuiltinProcedureFn::Unary(finite)),
+        Builtin::Procedure(
+            "string->number",
+            BuiltinProcedureFn::UnaryVariadic(string_to_number),
+        ),
+        Builtin::Procedure(
+            "number->string",
+            BuiltinProcedureFn::UnaryVariadic(number_to_string),
+        ),
     ]
 }
 
+/// Parses the given string as a number in the given radix, returning `None`
+/// if it isn't one. Only radix 10 supports fractional numbers, and as in
+/// R5RS, surrounding whitespace isn't allowed.
+fn parse_number(string: &str, radix: u32) -> Option<f64> {
+    if radix == 10 {
+        // Rust accepts things like "inf" and "NaN", which we don't want.
+        if !string
+            .chars()
+            .all(|char| char.is_ascii_digit() || "+-.eE".contains(char))
+        {
+            return None;
+        }
+        string.parse::<f64>().ok()
+    } else {
+        i64::from_str_radix(string, radix)
+            .ok()
+            .map(|number| number as f64)
+    }
+}
+
+fn string_to_number(
+    ctx: BuiltinProcedureContext,
+    string: &SourceValue,
+    rest: &[SourceValue],
+) -> CallableResult {
+    let string = string.expect_string()?.to_string();
+    let radix = optional_radix_arg(ctx.range, rest)?;
+    Ok(match parse_number(&string, radix) {
+        Some(number) => number.into(),
+        None => false.into(),
+    })
+}
+
+/// Formats the given integer, whose magnitude must fit in a `u64`, in the
+/// given radix, using lowercase letters for digits above 9.
+fn format_integer(number: f64, radix: u32) -> String {
+    let mut magnitude = number.abs() as u64;
+    let mut digits = vec![];
+    loop {
+        digits.push(char::from_digit((magnitude % radix as u64) as u32, radix).unwrap());
+        magnitude /= radix as u64;
+        if magnitude == 0 {
+            break;
+        }
+    }
+    if number < 0.0 {
+        digits.push('-');
+    }
+    digits.into_iter().rev().collect()
+}
+
+/// The most decimal places `number->string` will show. No float has any
+/// nonzero digits past this point, since the smallest one is 2^-1074.
+const MAX_PRECISION: usize = 1074;
+
+/// Takes an optional radix and, if the radix is 10, an optional number of
+/// decimal places to show. Otherwise, the number is formatted the same way
+/// it's displayed.
+fn number_to_string(
+    ctx: BuiltinProcedureContext,
+    number: &SourceValue,
+    rest: &[SourceValue],
+) -> CallableResult {
+    let (radix_arg, precision) = match rest {
+        [radix, precision] => (
+            std::slice::from_ref(radix),
+            Some(precision.expect_size(MAX_PRECISION)?),
+        ),
+        _ => (rest, None),
+    };
+    let radix = optional_radix_arg(ctx.range, radix_arg)?;
+    let string = match (radix, precision) {
+        (10, None) => Value::Number(number.expect_number()?).to_string(),
+        (10, Some(precision)) => format!("{:.*}", precision, number.expect_number()?),
+        (_, None) => format_integer(number.expect_exact_integer()?, radix),
+        (_, Some(_)) => return Err(RuntimeErrorType::InvalidRadix.source_mapped(rest[0].1)),
+    };
+    Ok(Value::String(MutableString::new(string))
+        .source_mapped(ctx.range)
+        .into())
+}
+
 fn sqrt(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
     let number = value.expect_number()?;
     Ok(number.sqrt().into())
 }
 
+/// Since we don't distinguish exact from inexact numbers yet, and don't
+/// support rationals, this only accepts numbers that are already whole.
+fn exact(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(value.expect_integer()?.into())
+}
+
+fn inexact(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(value.expect_number()?.into())
+}
+
+/// Returns the largest integer whose square is no greater than `n`.
+///
+/// This uses Newton's method with integer arithmetic, since a float square
+/// root can be off by one for large numbers.
+fn integer_sqrt(n: u64) -> u64 {
+    if n < 2 {
+        return n;
+    }
+    // Start with a power of two that's at least as large as the root.
+    let mut x = 1u64 << (64 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// Returns the integer square root of the given non-negative integer, along
+/// with the remainder, as two values.
+fn exact_integer_sqrt(ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    value.expect_non_negative_integer()?;
+    let n = value.expect_exact_integer()? as u64;
+    let root = integer_sqrt(n);
+    let remainder = n - root * root;
+    Ok(
+        make_values(vec![(root as f64).into(), (remainder as f64).into()])
+            .source_mapped(ctx.range)
+            .into(),
+    )
+}
+
+fn square(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    let number = value.expect_number()?;
+    Ok((number * number).into())
+}
+
 fn add(_ctx: BuiltinProcedureContext, operands: &[SourceValue]) -> CallableResult {
     let mut result = 0.0;
     for number in number_args(operands)? {
@@ -58,6 +232,13 @@ fn multiply(_ctx: BuiltinProcedureContext, operands: &[SourceValue]) -> Callable
     Ok(result.into())
 }
 
+/// All our numbers are floats, so most arithmetic follows IEEE 754 semantics
+/// and can quietly produce infinities or NaN (e.g. `(sqrt -1)`), which can be
+/// detected with `nan?`, `infinite?`, and `finite?`.
+///
+/// Division by zero is the exception: since we can't tell an exact `0` from
+/// an inexact `0.0`, we treat every zero divisor as the integer case and
+/// raise an error, which is what beginners are most likely to expect.
 fn divide(
     ctx: BuiltinProcedureContext,
     first: &SourceValue,
@@ -90,13 +271,146 @@ fn remainder(_ctx: BuiltinProcedureContext, a: &SourceValue, b: &SourceValue) ->
     Ok((a.expect_number()? % b.expect_number()?).into())
 }
 
+/// Returns the integer operands of a division operator, raising an error if
+/// the divisor is zero.
+fn integer_division_args(a: &SourceValue, b: &SourceValue) -> Result<(f64, f64), RuntimeError> {
+    let a_number = a.expect_integer()?;
+    let b_number = b.expect_integer()?;
+    if b_number == 0.0 {
+        return Err(RuntimeErrorType::DivisionByZero.source_mapped(b.1));
+    }
+    Ok((a_number, b_number))
+}
+
+/// Returns the quotient and remainder of dividing `a` by `b`, where the
+/// quotient is rounded towards negative infinity.
+fn floor_division(a: f64, b: f64) -> (f64, f64) {
+    let quotient = (a / b).floor();
+    (quotient, a - b * quotient)
+}
+
+/// Returns the quotient and remainder of dividing `a` by `b`, where the
+/// quotient is rounded towards zero.
+fn truncate_division(a: f64, b: f64) -> (f64, f64) {
+    ((a / b).trunc(), a % b)
+}
+
+fn quotient(_ctx: BuiltinProcedureContext, a: &SourceValue, b: &SourceValue) -> CallableResult {
+    let (a, b) = integer_division_args(a, b)?;
+    Ok(truncate_division(a, b).0.into())
+}
+
+fn floor_quotient(
+    _ctx: BuiltinProcedureContext,
+    a: &SourceValue,
+    b: &SourceValue,
+) -> CallableResult {
+    let (a, b) = integer_division_args(a, b)?;
+    Ok(floor_division(a, b).0.into())
+}
+
+fn modulo(_ctx: BuiltinProcedureContext, a: &SourceValue, b: &SourceValue) -> CallableResult {
+    let (a, b) = integer_division_args(a, b)?;
+    Ok(floor_division(a, b).1.into())
+}
+
+/// Returns the quotient and remainder as two values.
+fn division_values(
+    ctx: BuiltinProcedureContext,
+    (quotient, remainder): (f64, f64),
+) -> CallableResult {
+    Ok(make_values(vec![quotient.into(), remainder.into()])
+        .source_mapped(ctx.range)
+        .into())
+}
+
+fn floor_divide(ctx: BuiltinProcedureContext, a: &SourceValue, b: &SourceValue) -> CallableResult {
+    let (a, b) = integer_division_args(a, b)?;
+    division_values(ctx, floor_division(a, b))
+}
+
+fn truncate_divide(
+    ctx: BuiltinProcedureContext,
+    a: &SourceValue,
+    b: &SourceValue,
+) -> CallableResult {
+    let (a, b) = integer_division_args(a, b)?;
+    division_values(ctx, truncate_division(a, b))
+}
+
+fn floor(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(value.expect_number()?.floor().into())
+}
+
+fn ceiling(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(value.expect_number()?.ceil().into())
+}
+
+fn truncate(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(value.expect_number()?.trunc().into())
+}
+
+/// From R5RS 6.2.5:
+///
+/// > `Round` rounds to even when `x` is halfway between two integers.
+fn round(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(value.expect_number()?.round_ties_even().into())
+}
+
+fn max(_ctx: BuiltinProcedureContext, first: &SourceValue, rest: &[SourceValue]) -> CallableResult {
+    let mut result = first.expect_number()?;
+    for number in number_args(rest)? {
+        result = result.max(number);
+    }
+    Ok(result.into())
+}
+
+fn min(_ctx: BuiltinProcedureContext, first: &SourceValue, rest: &[SourceValue]) -> CallableResult {
+    let mut result = first.expect_number()?;
+    for number in number_args(rest)? {
+        result = result.min(number);
+    }
+    Ok(result.into())
+}
+
+fn positive(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok((value.expect_number()? > 0.0).into())
+}
+
+fn negative(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok((value.expect_number()? < 0.0).into())
+}
+
+fn odd(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok((value.expect_integer()? % 2.0 != 0.0).into())
+}
+
+fn even(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok((value.expect_integer()? % 2.0 == 0.0).into())
+}
+
+fn nan(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(value.expect_number()?.is_nan().into())
+}
+
+fn infinite(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(value.expect_number()?.is_infinite().into())
+}
+
+fn finite(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(value.expect_number()?.is_finite().into())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
         interpreter::RuntimeErrorType,
-        test_util::{test_eval_err, test_eval_success},
+        test_util::{test_eval_err, test_eval_success, test_eval_successes},
+        value::MAX_EXACT_INTEGER,
     };
 
+    use super::integer_sqrt;
+
     #[test]
     fn basic_arithmetic_works() {
         // This is how try.scheme.org works, at least.
@@ -128,8 +442,302 @@ mod tests {
         test_eval_success("(remainder -13 -4)", "-1");
     }
 
+    #[test]
+    fn rounding_works() {
+        // From R5RS 6.2.5.
+        test_eval_success("(floor -4.3)", "-5");
+        test_eval_success("(ceiling -4.3)", "-4");
+        test_eval_success("(truncate -4.3)", "-4");
+        test_eval_success("(round -4.3)", "-4");
+
+        test_eval_success("(floor 3.5)", "3");
+        test_eval_success("(ceiling 3.5)", "4");
+        test_eval_success("(truncate 3.5)", "3");
+        test_eval_success("(round 3.5)", "4");
+
+        test_eval_success("(round 2.5)", "2");
+        test_eval_success("(round 7)", "7");
+        test_eval_success("(integer? (round 2.7))", "#t");
+    }
+
+    #[test]
+    fn max_and_min_work() {
+        test_eval_success("(max 1)", "1");
+        test_eval_success("(max 1 5 3)", "5");
+        test_eval_success("(min 1)", "1");
+        test_eval_success("(min 4 -2 3)", "-2");
+        test_eval_success("(apply max '(1 5 3))", "5");
+        test_eval_success("(apply min '(4 -2 3))", "-2");
+        test_eval_err("(max 1 \"2\")", RuntimeErrorType::ExpectedNumber);
+    }
+
+    #[test]
+    fn sign_predicates_work() {
+        test_eval_success("(positive? 1)", "#t");
+        test_eval_success("(positive? 0)", "#f");
+        test_eval_success("(positive? -0.5)", "#f");
+        test_eval_success("(negative? -0.5)", "#t");
+        test_eval_success("(negative? 0)", "#f");
+        test_eval_success("(negative? 1)", "#f");
+        test_eval_err("(positive? \"1\")", RuntimeErrorType::ExpectedNumber);
+    }
+
+    #[test]
+    fn parity_predicates_work() {
+        test_eval_success("(even? 0)", "#t");
+        test_eval_success("(even? 2)", "#t");
+        test_eval_success("(even? -2)", "#t");
+        test_eval_success("(even? 3)", "#f");
+        test_eval_success("(odd? 3)", "#t");
+        test_eval_success("(odd? -3)", "#t");
+        test_eval_success("(odd? 0)", "#f");
+        test_eval_err("(even? 2.5)", RuntimeErrorType::ExpectedInteger);
+        test_eval_err("(odd? 2.5)", RuntimeErrorType::ExpectedInteger);
+    }
+
+    #[test]
+    fn exact_and_inexact_conversions_work() {
+        test_eval_success("(exact 2)", "2");
+        test_eval_success("(exact 2.0)", "2");
+        test_eval_success("(inexact->exact -3)", "-3");
+        test_eval_success("(inexact 2.5)", "2.5");
+        test_eval_success("(exact->inexact 2)", "2");
+        test_eval_err("(exact 2.5)", RuntimeErrorType::ExpectedInteger);
+        test_eval_err("(exact 'a)", RuntimeErrorType::ExpectedInteger);
+        test_eval_err("(inexact 'a)", RuntimeErrorType::ExpectedNumber);
+    }
+
+    #[test]
+    fn nan_works() {
+        test_eval_success("(nan? (sqrt -1))", "#t");
+        test_eval_success(
+            r#"(nan? (- (string->number "1e400") (string->number "1e400")))"#,
+            "#t",
+        );
+        test_eval_success("(nan? 1.5)", "#f");
+        test_eval_success(r#"(nan? (string->number "1e400"))"#, "#f");
+        test_eval_err("(nan? 'a)", RuntimeErrorType::ExpectedNumber);
+    }
+
+    #[test]
+    fn infinite_works() {
+        test_eval_success(r#"(infinite? (string->number "1e400"))"#, "#t");
+        test_eval_success(r#"(infinite? (string->number "-1e400"))"#, "#t");
+        test_eval_success("(infinite? 1234.5)", "#f");
+        test_eval_success("(infinite? (sqrt -1))", "#f");
+        test_eval_err("(infinite? 'a)", RuntimeErrorType::ExpectedNumber);
+    }
+
+    #[test]
+    fn finite_works() {
+        test_eval_success("(finite? 0)", "#t");
+        test_eval_success("(finite? -1.5)", "#t");
+        test_eval_success(r#"(finite? (string->number "1e400"))"#, "#f");
+        test_eval_success("(finite? (sqrt -1))", "#f");
+        test_eval_err("(finite? 'a)", RuntimeErrorType::ExpectedNumber);
+    }
+
     #[test]
     fn division_by_zero_raises_err() {
         test_eval_err("(/ 5 0)", RuntimeErrorType::DivisionByZero);
+        test_eval_err("(/ 5.0 0.0)", RuntimeErrorType::DivisionByZero);
+    }
+
+    #[test]
+    fn string_to_number_works() {
+        test_eval_success(r#"(string->number "100")"#, "100");
+        test_eval_success(r#"(string->number "-1.5")"#, "-1.5");
+        test_eval_success(r#"(string->number "ff" 16)"#, "255");
+        test_eval_success(r#"(string->number "-101" 2)"#, "-5");
+        test_eval_success(r#"(string->number "1.5" 16)"#, "#f");
+        test_eval_success(r#"(string->number "xyz")"#, "#f");
+        test_eval_success(r#"(string->number "inf")"#, "#f");
+        test_eval_success(r#"(string->number "")"#, "#f");
+        test_eval_success(r#"(string->number " 5 ")"#, "#f");
+        test_eval_success(r#"(string->number "5\n")"#, "#f");
+        test_eval_success(r#"(string->number " ff" 16)"#, "#f");
+        test_eval_err(r#"(string->number "1" 37)"#, RuntimeErrorType::InvalidRadix);
+        test_eval_err("(string->number 1)", RuntimeErrorType::ExpectedString);
+    }
+
+    #[test]
+    fn numbers_display_as_shortest_round_trip() {
+        test_eval_success("(+ 0.1 0.2)", "0.30000000000000004");
+        test_eval_success("0.1", "0.1");
+        test_eval_success("(/ 1 3)", "0.3333333333333333");
+        test_eval_success(
+            "(= (+ 0.1 0.2) (string->number (number->string (+ 0.1 0.2))))",
+            "#t",
+        );
+    }
+
+    #[test]
+    fn whole_numbers_display_without_decimal_point() {
+        test_eval_success("(* 1.5 2)", "3");
+        test_eval_success("(inexact 3)", "3");
+        test_eval_success("-0.0", "-0");
+        test_eval_success("(+ 2.5 0.25)", "2.75");
+    }
+
+    #[test]
+    fn number_to_string_works() {
+        test_eval_success("(number->string 255)", r#""255""#);
+        test_eval_success("(number->string 1.5)", r#""1.5""#);
+        test_eval_success("(number->string 255 16)", r#""ff""#);
+        test_eval_success("(number->string -5 2)", r#""-101""#);
+        test_eval_success("(number->string 0 8)", r#""0""#);
+        test_eval_success("(number->string 3.14159 10 2)", r#""3.14""#);
+        test_eval_success("(number->string 2 10 3)", r#""2.000""#);
+        test_eval_success("(string->number (number->string 1 10 1074))", "1");
+        test_eval_err(
+            "(number->string 1.5 10 70000)",
+            RuntimeErrorType::SizeTooLarge {
+                size: 70000.0,
+                max: 1074,
+            },
+        );
+        test_eval_success(r#"(string->number (number->string 1234 16) 16)"#, "1234");
+        test_eval_err("(number->string 1.5 16)", RuntimeErrorType::ExpectedInteger);
+        test_eval_success(
+            "(number->string -9007199254740992 16)",
+            r#""-20000000000000""#,
+        );
+        test_eval_err(
+            "(number->string (* 2 9007199254740992) 16)",
+            RuntimeErrorType::IntegerTooLarge {
+                integer: 18014398509481984.0,
+                max: MAX_EXACT_INTEGER,
+            },
+        );
+        test_eval_err("(number->string 1.5 16 2)", RuntimeErrorType::InvalidRadix);
+        test_eval_err("(number->string 1 1)", RuntimeErrorType::InvalidRadix);
+        test_eval_err("(number->string \"1\")", RuntimeErrorType::ExpectedNumber);
+    }
+
+    #[test]
+    fn quotient_and_modulo_work() {
+        // From R5RS 6.2.5.
+        test_eval_success("(modulo 13 4)", "1");
+        test_eval_success("(modulo -13 4)", "3");
+        test_eval_success("(modulo 13 -4)", "-3");
+        test_eval_success("(modulo -13 -4)", "-1");
+        test_eval_success("(quotient 13 4)", "3");
+        test_eval_success("(quotient -13 4)", "-3");
+        test_eval_err("(quotient 1 0)", RuntimeErrorType::DivisionByZero);
+        test_eval_err("(modulo 1.5 1)", RuntimeErrorType::ExpectedInteger);
+    }
+
+    #[test]
+    fn r7rs_division_names_work() {
+        test_eval_success("(truncate-quotient -13 4)", "-3");
+        test_eval_success("(truncate-remainder -13 4)", "-1");
+        test_eval_success("(floor-quotient -13 4)", "-4");
+        test_eval_success("(floor-remainder -13 4)", "3");
+        test_eval_err("(floor-quotient 1 0)", RuntimeErrorType::DivisionByZero);
+    }
+
+    #[test]
+    fn r7rs_division_names_are_aliases() {
+        test_eval_success("(eq? quotient truncate-quotient)", "#t");
+        test_eval_success("(eq? remainder truncate-remainder)", "#t");
+        test_eval_success("(eq? modulo floor-remainder)", "#t");
+        test_eval_success("(eq? quotient floor-quotient)", "#f");
+        test_eval_success("(eq? quotient remainder)", "#f");
+    }
+
+    #[test]
+    fn square_works() {
+        test_eval_success("(square 5)", "25");
+        test_eval_success("(square -1.5)", "2.25");
+        test_eval_err("(square 'a)", RuntimeErrorType::ExpectedNumber);
+    }
+
+    #[test]
+    fn exact_integer_sqrt_works() {
+        test_eval_success(
+            "(call-with-values (lambda () (exact-integer-sqrt 17)) list)",
+            "(4 1)",
+        );
+        test_eval_success(
+            "(call-with-values (lambda () (exact-integer-sqrt 16)) list)",
+            "(4 0)",
+        );
+        test_eval_success(
+            "(call-with-values (lambda () (exact-integer-sqrt 0)) list)",
+            "(0 0)",
+        );
+        test_eval_success(
+            "(call-with-values (lambda () (exact-integer-sqrt 1)) list)",
+            "(1 0)",
+        );
+        // The float square root of this rounds up to 67108865.
+        test_eval_success(
+            "(call-with-values (lambda () (exact-integer-sqrt 4503599761588224)) list)",
+            "(67108864 134217728)",
+        );
+        test_eval_err(
+            "(exact-integer-sqrt -1)",
+            RuntimeErrorType::ExpectedNonNegativeInteger,
+        );
+        test_eval_err(
+            "(exact-integer-sqrt 2.5)",
+            RuntimeErrorType::ExpectedNonNegativeInteger,
+        );
+        test_eval_success(
+            "(call-with-values (lambda () (exact-integer-sqrt 9007199254740992)) list)",
+            "(94906265 118490767)",
+        );
+        test_eval_err(
+            "(exact-integer-sqrt 100000000000000000000)",
+            RuntimeErrorType::IntegerTooLarge {
+                integer: 1e20,
+                max: MAX_EXACT_INTEGER,
+            },
+        );
+    }
+
+    #[test]
+    fn integer_sqrt_works_on_large_numbers() {
+        assert_eq!(integer_sqrt(u64::MAX), u32::MAX as u64);
+        assert_eq!(integer_sqrt((1 << 62) - 1), (1 << 31) - 1);
+        assert_eq!(integer_sqrt(1 << 62), 1 << 31);
+    }
+
+    #[test]
+    fn division_operators_returning_two_values_work() {
+        // From R7RS 6.2.6.
+        test_eval_successes(&[
+            ("(call-with-values (lambda () (floor/ 5 2)) list)", "(2 1)"),
+            (
+                "(call-with-values (lambda () (floor/ -5 2)) list)",
+                "(-3 1)",
+            ),
+            (
+                "(call-with-values (lambda () (floor/ 5 -2)) list)",
+                "(-3 -1)",
+            ),
+            (
+                "(call-with-values (lambda () (floor/ -5 -2)) list)",
+                "(2 -1)",
+            ),
+            (
+                "(call-with-values (lambda () (truncate/ 5 2)) list)",
+                "(2 1)",
+            ),
+            (
+                "(call-with-values (lambda () (truncate/ -5 2)) list)",
+                "(-2 -1)",
+            ),
+            (
+                "(call-with-values (lambda () (truncate/ 5 -2)) list)",
+                "(-2 1)",
+            ),
+            (
+                "(call-with-values (lambda () (truncate/ -5 -2)) list)",
+                "(2 -1)",
+            ),
+        ]);
+        test_eval_err("(floor/ 1 0)", RuntimeErrorType::DivisionByZero);
+        test_eval_err("(truncate/ 1 0)", RuntimeErrorType::DivisionByZero);
     }
 }
@@ -1,33 +1,59 @@
+use std::collections::HashSet;
+
 use crate::{
     builtin_procedure::{BuiltinProcedure, BuiltinProcedureContext, BuiltinProcedureFn},
     callable::{Callable, CallableResult, CallableSuccess, TailCallContext},
     compound_procedure::{Body, CompoundProcedure, Signature},
-    environment::Environment,
+    environment::{CapturedLexicalScope, Environment},
     interpreter::RuntimeErrorType,
+    mutable_string::MutableString,
     procedure::Procedure,
     source_mapped::{SourceMappable, SourceMapped},
     special_form::{SpecialForm, SpecialFormContext, SpecialFormFn},
-    string_interner::StringInterner,
+    string_interner::{InternedString, StringInterner},
     value::{SourceValue, Value},
 };
 
 mod _let;
+mod _match;
+mod char;
 mod eq;
+mod exception;
+mod features;
+mod hash_table;
 mod library;
 mod logic;
 mod math;
 mod non_standard;
 mod ord;
 mod pair;
+mod port;
+mod predicates;
+mod procedure;
+mod record;
+mod sort;
+mod string;
+mod symbol;
 mod util;
+mod values;
+mod vector;
 
 pub use library::add_library_source;
 
-pub fn populate_environment(environment: &mut Environment, interner: &mut StringInterner) {
-    for builtin in get_builtins() {
+/// Defines all builtins in the given environment, returning their names. In
+/// strict R5RS mode, our non-standard builtins (e.g. `gc` and `test-eq`) are
+/// left out.
+pub fn populate_environment(
+    environment: &mut Environment,
+    interner: &mut StringInterner,
+    strict_r5rs: bool,
+) -> HashSet<InternedString> {
+    let mut names = HashSet::new();
+    for builtin in get_builtins(strict_r5rs) {
         match builtin {
             Builtin::SpecialForm(name, func) => {
                 let name = interner.intern(name);
+                names.insert(name.clone());
                 environment.define(
                     name.clone(),
                     Value::Callable(Callable::SpecialForm(SpecialForm { func, name })).into(),
@@ -35,6 +61,7 @@ pub fn populate_environment(environment: &mut Environment, interner: &mut String
             }
             Builtin::Procedure(name, func) => {
                 let name = interner.intern(name);
+                names.insert(name.clone());
                 environment.define(
                     name.clone(),
                     Value::Callable(Callable::Procedure(Procedure::Builtin(BuiltinProcedure {
@@ -46,9 +73,7 @@ pub fn populate_environment(environment: &mut Environment, interner: &mut String
             }
         }
     }
-    // TODO: Technically 'else' is just part of how the 'cond' special form is evaluated,
-    // but just aliasing it to 'true' is easier for now.
-    environment.define(interner.intern("else"), Value::Boolean(true).into());
+    names
 }
 
 pub enum Builtin {
@@ -58,14 +83,26 @@ pub enum Builtin {
 
 pub type Builtins = Vec<Builtin>;
 
-fn get_builtins() -> Builtins {
+fn get_builtins(strict_r5rs: bool) -> Builtins {
     let mut builtins: Builtins = vec![
         Builtin::SpecialForm("define", define),
         Builtin::SpecialForm("lambda", lambda),
         Builtin::Procedure("apply", BuiltinProcedureFn::Binary(apply)),
+        Builtin::Procedure("eval", BuiltinProcedureFn::UnaryVariadic(eval)),
+        Builtin::Procedure(
+            "interaction-environment",
+            BuiltinProcedureFn::Nullary(interaction_environment),
+        ),
         Builtin::SpecialForm("quote", quote),
+        Builtin::SpecialForm("unquote", stray_unquote),
+        Builtin::SpecialForm("unquote-splicing", stray_unquote),
         Builtin::SpecialForm("begin", begin),
         Builtin::Procedure("display", BuiltinProcedureFn::Unary(display)),
+        Builtin::Procedure("write", BuiltinProcedureFn::Unary(write)),
+        Builtin::Procedure(
+            "with-output-to-string",
+            BuiltinProcedureFn::Unary(with_output_to_string),
+        ),
         Builtin::SpecialForm("if", _if),
         Builtin::SpecialForm("cond", cond),
         Builtin::SpecialForm("set!", set),
@@ -74,9 +111,25 @@ fn get_builtins() -> Builtins {
     builtins.extend(eq::get_builtins());
     builtins.extend(ord::get_builtins());
     builtins.extend(logic::get_builtins());
-    builtins.extend(non_standard::get_builtins());
+    if !strict_r5rs {
+        builtins.extend(non_standard::get_builtins());
+    }
     builtins.extend(_let::get_builtins());
+    builtins.extend(_match::get_builtins());
     builtins.extend(pair::get_builtins());
+    builtins.extend(predicates::get_builtins());
+    builtins.extend(record::get_builtins());
+    builtins.extend(string::get_builtins());
+    builtins.extend(symbol::get_builtins());
+    builtins.extend(char::get_builtins());
+    builtins.extend(vector::get_builtins());
+    builtins.extend(sort::get_builtins());
+    builtins.extend(hash_table::get_builtins());
+    builtins.extend(values::get_builtins());
+    builtins.extend(exception::get_builtins());
+    builtins.extend(features::get_builtins());
+    builtins.extend(procedure::get_builtins());
+    builtins.extend(port::get_builtins());
     builtins
 }
 
@@ -104,17 +157,38 @@ fn cond(ctx: SpecialFormContext) -> CallableResult {
         return Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(ctx.range));
     }
 
-    for clause in ctx.operands.iter() {
+    let last_index = ctx.operands.len() - 1;
+    for (i, clause) in ctx.operands.iter().enumerate() {
         let SourceMapped(Value::Pair(pair), range) = clause else {
             return Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(clause.1));
         };
         let Some(clause) = pair.try_as_rc_list() else {
             return Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(*range));
         };
-        let test = ctx.interpreter.eval_expression(&clause[0])?.0;
-        if test.as_bool() {
+        if is_else(&clause[0]) {
+            // This is a clause of the form `(else expression...)`, which is only allowed last.
+            if i != last_index || clause.len() == 1 {
+                return Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(*range));
+            }
+            return ctx
+                .interpreter
+                .eval_expressions_in_tail_context(&clause[1..]);
+        }
+        let test = ctx.interpreter.eval_expression(&clause[0])?;
+        if test.0.as_bool() {
             if clause.len() == 1 {
-                return Ok(test.into());
+                return Ok(test.0.into());
+            }
+            if is_cond_arrow(&clause[1]) {
+                // This is a clause of the form `(test => receiver)`.
+                if clause.len() != 3 {
+                    return Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(*range));
+                }
+                let receiver = ctx.interpreter.eval_expression(&clause[2])?;
+                let procedure = receiver.expect_procedure()?;
+                return Ok(CallableSuccess::TailCall(TailCallContext {
+                    bound_procedure: procedure.bind(*range, &[test])?,
+                }));
             }
             return ctx
                 .interpreter
@@ -125,6 +199,16 @@ fn cond(ctx: SpecialFormContext) -> CallableResult {
     ctx.undefined()
 }
 
+fn is_cond_arrow(value: &SourceValue) -> bool {
+    matches!(&value.0, Value::Symbol(symbol) if symbol.as_ref() == "=>")
+}
+
+/// Note that `else` is recognized syntactically, so it works even if the
+/// user has bound `else` to something.
+pub(super) fn is_else(value: &SourceValue) -> bool {
+    matches!(&value.0, Value::Symbol(symbol) if symbol.as_ref() == "else")
+}
+
 // TODO: According to R5RS section 5.2, definitions are only allowed at the top level
 // of a program file, and at the beginning of a body. Currently we support it anywhere.
 fn define(ctx: SpecialFormContext) -> CallableResult {
@@ -138,6 +222,9 @@ fn define(ctx: SpecialFormContext) -> CallableResult {
                     compound.name = Some(name.clone());
                 }
             }
+            if ctx.interpreter.environment.is_at_global_scope() {
+                ctx.interpreter.maybe_warn_about_builtin_redefinition(name);
+            }
             ctx.interpreter.environment.define(name.clone(), value);
             ctx.undefined()
         }
@@ -152,6 +239,9 @@ fn define(ctx: SpecialFormContext) -> CallableResult {
                 ctx.interpreter.environment.capture_lexical_scope(),
             );
             proc.name = Some(name.clone());
+            if ctx.interpreter.environment.is_at_global_scope() {
+                ctx.interpreter.maybe_warn_about_builtin_redefinition(&name);
+            }
             ctx.interpreter.environment.define(
                 name,
                 Value::Callable(Callable::Procedure(Procedure::Compound(proc)))
@@ -178,6 +268,8 @@ fn lambda(ctx: SpecialFormContext) -> CallableResult {
     Ok(Value::Callable(Callable::Procedure(Procedure::Compound(proc))).into())
 }
 
+/// Note that rather than calling the procedure directly, this returns a tail call,
+/// so `apply` in a tail context doesn't grow the call stack.
 fn apply(ctx: BuiltinProcedureContext, func: &SourceValue, args: &SourceValue) -> CallableResult {
     let procedure = func.expect_procedure()?;
     let args = args.expect_list()?;
@@ -186,6 +278,35 @@ fn apply(ctx: BuiltinProcedureContext, func: &SourceValue, args: &SourceValue) -
     }))
 }
 
+/// Evaluates the given expression in the given environment, which defaults to
+/// the interaction environment. None of the lexical scopes active at the
+/// point of the call are visible to the expression.
+fn eval(
+    ctx: BuiltinProcedureContext,
+    expression: &SourceValue,
+    rest: &[SourceValue],
+) -> CallableResult {
+    let environment = match rest {
+        [] => CapturedLexicalScope::global(),
+        [environment] => environment.expect_environment()?,
+        _ => return Err(RuntimeErrorType::WrongNumberOfArguments.source_mapped(ctx.range)),
+    };
+    ctx.interpreter.environment.push_isolated(environment);
+    let result = ctx.interpreter.eval_expression(expression)?;
+
+    // Note that the environment won't have been restored if an error occured above--as with
+    // lexical scopes, it's up to the caller to clean things up after an error.
+    ctx.interpreter.environment.pop_isolated();
+
+    Ok(result.into())
+}
+
+fn interaction_environment(ctx: BuiltinProcedureContext) -> CallableResult {
+    Ok(Value::Environment(CapturedLexicalScope::global())
+        .source_mapped(ctx.range)
+        .into())
+}
+
 fn quote(ctx: SpecialFormContext) -> CallableResult {
     if ctx.operands.len() == 1 {
         Ok(ctx.operands[0].clone().into())
@@ -194,6 +315,13 @@ fn quote(ctx: SpecialFormContext) -> CallableResult {
     }
 }
 
+/// We don't support quasiquote yet, but `unquote` and `unquote-splicing` are
+/// only meaningful inside one, so we'd rather raise a clear error than have
+/// them treated as ordinary (unbound) combinations.
+fn stray_unquote(ctx: SpecialFormContext) -> CallableResult {
+    Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(ctx.range))
+}
+
 fn begin(ctx: SpecialFormContext) -> CallableResult {
     ctx.interpreter
         .eval_expressions_in_tail_context(&ctx.operands)
@@ -203,10 +331,14 @@ fn set(ctx: SpecialFormContext) -> CallableResult {
     ctx.ensure_operands_len(2)?;
     let identifier = ctx.operands[0].expect_identifier()?;
     let value = ctx.interpreter.eval_expression(&ctx.operands[1])?;
-    if let Err(err) = ctx.interpreter.environment.change(&identifier, value) {
-        Err(err.source_mapped(ctx.operands[0].1))
-    } else {
-        ctx.undefined()
+    if ctx.interpreter.environment.resolves_to_global(&identifier) {
+        ctx.interpreter
+            .maybe_warn_about_builtin_redefinition(&identifier);
+    }
+    match ctx.interpreter.environment.change(&identifier, value) {
+        Ok(previous) if ctx.interpreter.set_returns_previous_value => Ok(previous.into()),
+        Ok(_) => ctx.undefined(),
+        Err(err) => Err(err.source_mapped(ctx.operands[0].1)),
     }
 }
 
@@ -215,10 +347,28 @@ fn display(ctx: BuiltinProcedureContext, arg: &SourceValue) -> CallableResult {
     ctx.undefined()
 }
 
+fn write(ctx: BuiltinProcedureContext, arg: &SourceValue) -> CallableResult {
+    let repr = ctx.interpreter.repr_value(arg);
+    ctx.interpreter.printer.print(repr);
+    ctx.undefined()
+}
+
+fn with_output_to_string(ctx: BuiltinProcedureContext, thunk: &SourceValue) -> CallableResult {
+    let procedure = thunk.expect_procedure()?;
+    ctx.interpreter.printer.begin_capture();
+    let result = ctx.interpreter.call_procedure(procedure, &[], ctx.range);
+    // Note that we want to stop capturing even if the thunk raised an error.
+    let output = ctx.interpreter.printer.end_capture();
+    result?;
+    Ok(Value::String(MutableString::new(output))
+        .source_mapped(ctx.range)
+        .into())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        interpreter::RuntimeErrorType,
+        interpreter::{Interpreter, RuntimeErrorType},
         test_util::{test_eval_err, test_eval_success, test_eval_successes},
     };
 
@@ -241,6 +391,16 @@ mod tests {
         test_eval_success("'blarg", "blarg");
     }
 
+    #[test]
+    fn unquote_outside_quasiquote_raises_error() {
+        test_eval_err("(unquote 5)", RuntimeErrorType::MalformedSpecialForm);
+        test_eval_err(
+            "(unquote-splicing '(1 2))",
+            RuntimeErrorType::MalformedSpecialForm,
+        );
+        test_eval_success("'(1 2 (unquote 5))", "(1 2 (unquote 5))");
+    }
+
     #[test]
     fn cond_works() {
         test_eval_success("(cond (1))", "1");
@@ -251,6 +411,50 @@ mod tests {
         test_eval_success("(cond (1) (lolol))", "1");
     }
 
+    #[test]
+    fn cond_else_is_syntactic() {
+        test_eval_success("(define else #f) (cond (else 1))", "1");
+        test_eval_success("(let ((else #f)) (cond (#f 1) (else 2 3)))", "3");
+        let mut interpreter = test_eval_successes(&[]);
+        let else_ = interpreter.intern("else");
+        interpreter.eval_err("else", RuntimeErrorType::UnboundVariable(else_));
+        test_eval_err(
+            "(cond (else 1) (#t 2))",
+            RuntimeErrorType::MalformedSpecialForm,
+        );
+        test_eval_err("(cond (else))", RuntimeErrorType::MalformedSpecialForm);
+    }
+
+    #[test]
+    fn cond_arrow_clauses_work() {
+        // Adapted from R5RS 4.2.1.
+        test_eval_success(
+            "(cond ((assv 'b '((a 1) (b 2))) => (lambda (x) (car (cdr x)))) (else #f))",
+            "2",
+        );
+        test_eval_success("(cond ((assv 2 '((1 . a) (2 . b))) => cdr))", "b");
+        test_eval_success("(cond (#f => lolol) (else 5))", "5");
+        test_eval_err("(cond (1 => 5))", RuntimeErrorType::ExpectedProcedure);
+        test_eval_err("(cond (1 =>))", RuntimeErrorType::MalformedSpecialForm);
+        test_eval_err(
+            "(cond (1 => car cdr))",
+            RuntimeErrorType::MalformedSpecialForm,
+        );
+    }
+
+    #[test]
+    fn cond_arrow_clauses_are_properly_tail_recursive() {
+        test_eval_success(
+            "
+            (define (count-down n)
+              (cond ((= n 0) 'done)
+                    (n => (lambda (n) (count-down (- n 1))))))
+            (count-down 10000)
+            ",
+            "done",
+        );
+    }
+
     #[test]
     fn variable_definitions_work() {
         test_eval_success("(define x 3) x", "3");
@@ -350,6 +554,34 @@ mod tests {
         test_eval_success("(define x 1) (set! x (+ x 1)) x", "2");
     }
 
+    #[test]
+    fn set_distinguishes_unbound_from_inaccessible_variables() {
+        let mut interpreter = test_eval_successes(&[(
+            "
+            (define (make-counter)
+              (define n 0)
+              (lambda () (set! n (+ n 1)) n))
+            (define counter (make-counter))
+            ",
+            "",
+        )]);
+        let n = interpreter.intern("n");
+        let boop = interpreter.intern("boop");
+        interpreter.eval_err("(set! n 5)", RuntimeErrorType::InaccessibleVariable(n));
+        interpreter.eval_err("(set! boop 5)", RuntimeErrorType::UnboundVariable(boop));
+    }
+
+    #[test]
+    fn set_can_return_previous_value() {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_returns_previous_value = true;
+        let source_id = interpreter
+            .source_mapper
+            .add("<code>".into(), "(define x 1) (set! x 2)".into());
+        let value = interpreter.evaluate(source_id).unwrap();
+        assert_eq!(value.to_string(), "1");
+    }
+
     #[test]
     fn set_works_in_closures() {
         test_eval_successes(&[
@@ -375,6 +607,29 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn closures_in_same_scope_share_mutations() {
+        test_eval_successes(&[
+            (
+                "
+                (define counter
+                  (let ((n 0))
+                    (list
+                      (lambda () (set! n (+ n 1)) n)
+                      (lambda () n))))
+                (define increment (car counter))
+                (define get (car (cdr counter)))
+                ",
+                "",
+            ),
+            ("(get)", "0"),
+            ("(increment)", "1"),
+            ("(get)", "1"),
+            ("(apply increment '())", "2"),
+            ("(get)", "2"),
+        ]);
+    }
+
     #[test]
     fn if_works() {
         test_eval_success("(if #t 1)", "1");
@@ -419,6 +674,40 @@ mod tests {
         test_eval_success(r#"(display 1)"#, "1");
     }
 
+    #[test]
+    fn write_works() {
+        test_eval_success(r#"(write "boop")"#, r#""boop""#);
+        test_eval_success(r#"(write '("boop"))"#, r#"("boop")"#);
+        test_eval_success(r#"(write car)"#, "#<builtin procedure car>");
+    }
+
+    #[test]
+    fn with_output_to_string_works() {
+        test_eval_success(
+            r#"(with-output-to-string (lambda () (display "boop") (display 1)))"#,
+            r#""boop1""#,
+        );
+        test_eval_success(r#"(with-output-to-string (lambda () 1))"#, r#""""#);
+        test_eval_success(
+            r#"
+            (with-output-to-string (lambda ()
+              (display (with-output-to-string (lambda () (display "inner"))))
+              (display " outer")))
+            "#,
+            r#""inner outer""#,
+        );
+    }
+
+    #[test]
+    fn with_output_to_string_stops_capturing_on_error() {
+        let mut interpreter = test_eval_success(r#"(define (boom) (display "a") (car 1))"#, "");
+        interpreter.eval_err(
+            "(with-output-to-string boom)",
+            RuntimeErrorType::ExpectedPair,
+        );
+        interpreter.eval_success(r#"(display "b")"#, "b");
+    }
+
     #[test]
     fn begin_works() {
         test_eval_success("(begin)", "");
@@ -427,6 +716,53 @@ mod tests {
         test_eval_success("(begin (+ 1 2))", "3");
     }
 
+    #[test]
+    fn eval_works() {
+        test_eval_success("(eval '(+ 1 2) (interaction-environment))", "3");
+        test_eval_success("(eval '(+ 1 2))", "3");
+        test_eval_success("(eval 5 (interaction-environment))", "5");
+        test_eval_success("(interaction-environment)", "#<environment>");
+        test_eval_success(
+            "(eq? (interaction-environment) (interaction-environment))",
+            "#t",
+        );
+    }
+
+    #[test]
+    fn eval_ignores_lexical_scopes() {
+        test_eval_success(
+            "(define x 1) (let ((x 2)) (eval 'x (interaction-environment)))",
+            "1",
+        );
+        test_eval_success(
+            "(let ((y 2)) (eval '(define z 5) (interaction-environment)) y) z",
+            "5",
+        );
+    }
+
+    #[test]
+    fn environments_survive_gc() {
+        test_eval_success(
+            "(define e (interaction-environment)) (gc) (eval '(+ 1 2) e)",
+            "3",
+        );
+    }
+
+    #[test]
+    fn eval_raises_errors() {
+        test_eval_err("(eval 1 2)", RuntimeErrorType::ExpectedEnvironment);
+        test_eval_err(
+            "(eval 1 (interaction-environment) 2)",
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+        let mut interpreter = test_eval_successes(&[]);
+        let x = interpreter.intern("x");
+        interpreter.eval_err(
+            "(let ((x 2)) (eval 'x (interaction-environment)))",
+            RuntimeErrorType::UnboundVariable(x),
+        );
+    }
+
     #[test]
     fn apply_works() {
         // From R5RS 6.4.
@@ -447,4 +783,68 @@ mod tests {
         test_eval_success("(apply + '())", "0");
         test_eval_success("(apply (lambda (x) x) '((1)))", "(1)");
     }
+
+    #[test]
+    fn apply_checks_arity_against_whole_list() {
+        test_eval_err(
+            "(apply (lambda (x) x) '(1 2))",
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+        test_eval_err(
+            "(apply (lambda (x y) x) '(1))",
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+
+        let mut interpreter = Interpreter::new();
+        let code = "(define big (list-tabulate 1000 identity)) (apply car big)";
+        let source_id = interpreter.source_mapper.add("<code>".into(), code.into());
+        let err = interpreter.evaluate(source_id).unwrap_err();
+        assert_eq!(err.0, RuntimeErrorType::WrongNumberOfArguments);
+        // The error should point at the whole `apply` combination.
+        assert_eq!(
+            interpreter.source_mapper.get_source_text(&err.1),
+            Some("(apply car big)")
+        );
+    }
+
+    #[test]
+    fn apply_rejects_special_forms_with_clear_error() {
+        let mut interpreter = test_eval_successes(&[]);
+        let if_name = interpreter.intern("if");
+        interpreter.eval_err(
+            "(apply if '(#t 1 2))",
+            RuntimeErrorType::SpecialFormIsNotProcedure(if_name.clone()),
+        );
+        interpreter.eval_err(
+            "(map if '(#t))",
+            RuntimeErrorType::SpecialFormIsNotProcedure(if_name),
+        );
+        interpreter.eval_err("(apply 1 '())", RuntimeErrorType::ExpectedProcedure);
+    }
+
+    #[test]
+    fn apply_is_properly_tail_recursive() {
+        test_eval_success(
+            "
+            (define (count-down n)
+              (if (= n 0)
+                'done
+                (apply count-down (list (- n 1)))))
+            (count-down 10000)
+            ",
+            "done",
+        );
+
+        // This is similar to how continuation-passing style code might trampoline through `apply`.
+        test_eval_success(
+            "
+            (define (count-down n k)
+              (if (= n 0)
+                (k 'done)
+                (apply count-down (list (- n 1) (lambda (v) (apply k (list v)))))))
+            (count-down 1000 (lambda (v) v))
+            ",
+            "done",
+        );
+    }
 }
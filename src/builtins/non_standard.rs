@@ -1,4 +1,4 @@
-use std::backtrace::Backtrace;
+use std::{backtrace::Backtrace, time::Instant};
 
 use colored::Colorize;
 
@@ -9,7 +9,7 @@ use crate::{
     interpreter::RuntimeErrorType,
     source_mapped::SourceMappable,
     special_form::SpecialFormContext,
-    value::SourceValue,
+    value::{SourceValue, Value},
 };
 
 use super::eq::is_eq;
@@ -23,11 +23,24 @@ pub fn get_builtins() -> super::Builtins {
         Builtin::Procedure("stats", BuiltinProcedureFn::Nullary(stats)),
         Builtin::Procedure("gc", BuiltinProcedureFn::Nullary(gc)),
         Builtin::Procedure("gc-verbose", BuiltinProcedureFn::Nullary(gc_verbose)),
+        Builtin::Procedure("gc-roots", BuiltinProcedureFn::Nullary(gc_roots)),
         Builtin::SpecialForm("test-eq", test_eq),
         Builtin::SpecialForm("test-repr", test_repr),
-        Builtin::Procedure("assert", BuiltinProcedureFn::Unary(assert)),
+        Builtin::SpecialForm("assert", assert),
         Builtin::SpecialForm("print-and-eval", print_and_eval),
         Builtin::SpecialForm("track-stats", track_stats),
+        Builtin::SpecialForm("profile", profile),
+        Builtin::Procedure(
+            "get-call-stats",
+            BuiltinProcedureFn::Nullary(get_call_stats),
+        ),
+        Builtin::SpecialForm("time", time),
+        Builtin::Procedure("gensym", BuiltinProcedureFn::Nullary(gensym)),
+        Builtin::Procedure("apropos", BuiltinProcedureFn::Unary(apropos)),
+        Builtin::Procedure(
+            "environment->alist",
+            BuiltinProcedureFn::NullaryVariadic(environment_to_alist),
+        ),
     ]
 }
 
@@ -46,6 +59,90 @@ fn gc_verbose(ctx: BuiltinProcedureContext) -> CallableResult {
     Ok((objs_found_in_cycles as f64).into())
 }
 
+/// Prints what's currently keeping objects from being garbage collected,
+/// aside from global variables, and returns a list containing the number of
+/// rooted values and the number of active lexical scopes.
+fn gc_roots(ctx: BuiltinProcedureContext) -> CallableResult {
+    let counts = ctx.interpreter.rooted_value_counts();
+    let total: usize = counts.iter().map(|(_, count)| count).sum();
+    let scopes = ctx.interpreter.environment.lexical_scope_depth();
+    ctx.interpreter
+        .printer
+        .println(format!("Rooted values: {total}"));
+    for (type_name, count) in counts {
+        ctx.interpreter
+            .printer
+            .println(format!("  {type_name}: {count}"));
+    }
+    ctx.interpreter
+        .printer
+        .println(format!("Active lexical scopes: {scopes}"));
+    let result = vec![(total as f64).into(), (scopes as f64).into()];
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(result)
+        .source_mapped(ctx.range)
+        .into())
+}
+
+/// Returns a new symbol that's guaranteed not to collide with any other
+/// symbol, including any that the user could type, since its name contains a
+/// `#` (which the tokenizer doesn't allow in identifiers).
+fn gensym(ctx: BuiltinProcedureContext) -> CallableResult {
+    let name = format!("g#{}", ctx.interpreter.new_id());
+    let symbol = ctx.interpreter.string_interner.intern(name);
+    Ok(Value::Symbol(symbol).source_mapped(ctx.range).into())
+}
+
+/// Returns a sorted list of the names of all global bindings that contain the
+/// given string.
+fn apropos(ctx: BuiltinProcedureContext, query: &SourceValue) -> CallableResult {
+    let query = query.expect_string()?.to_string();
+    let mut names = ctx.interpreter.environment.find_global_contains(&query);
+    names.sort();
+    let symbols = names
+        .into_iter()
+        .map(|name| Value::Symbol(ctx.interpreter.string_interner.intern(name)).into())
+        .collect();
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(symbols)
+        .source_mapped(ctx.range)
+        .into())
+}
+
+/// Returns the global bindings as an alist sorted by name. Bindings created by
+/// the interpreter itself are left out unless the optional argument is true.
+fn environment_to_alist(ctx: BuiltinProcedureContext, operands: &[SourceValue]) -> CallableResult {
+    let include_builtins = match operands {
+        [] => false,
+        [flag] => flag.0.as_bool(),
+        _ => return Err(RuntimeErrorType::WrongNumberOfArguments.source_mapped(ctx.range)),
+    };
+    let bindings: Vec<_> = ctx
+        .interpreter
+        .environment
+        .globals()
+        .into_iter()
+        .filter(|(name, _)| include_builtins || !ctx.interpreter.is_builtin_name(name))
+        .collect();
+    let pairs = bindings
+        .into_iter()
+        .map(|(name, value)| {
+            let name = Value::Symbol(name).source_mapped(ctx.range);
+            Value::Pair(ctx.interpreter.pair_manager.pair(name, value)).source_mapped(ctx.range)
+        })
+        .collect();
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(pairs)
+        .source_mapped(ctx.range)
+        .into())
+}
+
 fn print_and_eval(ctx: SpecialFormContext) -> CallableResult {
     for (i, operand) in ctx.operands.iter().enumerate() {
         let operand_repr = operand.to_string();
@@ -62,9 +159,15 @@ fn print_and_eval(ctx: SpecialFormContext) -> CallableResult {
     ctx.undefined()
 }
 
-fn assert(ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+fn assert(mut ctx: SpecialFormContext) -> CallableResult {
+    let value = ctx.eval_unary()?;
     if !value.0.as_bool() {
-        Err(RuntimeErrorType::AssertionFailure.source_mapped(ctx.range))
+        let operand = &ctx.operands[0];
+        let expression = match ctx.interpreter.source_mapper.get_source_text(&operand.1) {
+            Some(text) => text.to_string(),
+            None => operand.to_string(),
+        };
+        Err(RuntimeErrorType::AssertionFailure(expression).source_mapped(ctx.range))
     } else {
         ctx.undefined()
     }
@@ -122,24 +225,74 @@ fn rust_backtrace(ctx: BuiltinProcedureContext) -> CallableResult {
     ctx.undefined()
 }
 
-fn track_stats(mut ctx: SpecialFormContext) -> CallableResult {
+fn track_stats(ctx: SpecialFormContext) -> CallableResult {
+    eval_and_show_stats(ctx, false)
+}
+
+/// Like `track-stats`, but also shows how much time was spent in each callable.
+fn profile(ctx: SpecialFormContext) -> CallableResult {
+    eval_and_show_stats(ctx, true)
+}
+
+fn eval_and_show_stats(mut ctx: SpecialFormContext, profiling: bool) -> CallableResult {
     ctx.ensure_operands_len(1)?;
     let repr = ctx.operands[0].to_string();
-    ctx.interpreter.start_tracking_stats();
+    ctx.interpreter.start_tracking_stats(profiling);
     let result = ctx.eval_unary();
     println!("Statistics for evaluation of {}\n", repr.blue());
     if let Some(stats) = ctx.interpreter.take_tracked_stats() {
         ctx.interpreter.printer.println(stats.as_table());
+        ctx.interpreter.last_tracked_stats = Some(stats);
     }
     result?;
     ctx.undefined()
 }
 
+/// Returns the call counts from the most recent `track-stats` as a list of
+/// `(name calls tail-calls)` lists.
+fn get_call_stats(ctx: BuiltinProcedureContext) -> CallableResult {
+    let counts = match &ctx.interpreter.last_tracked_stats {
+        Some(stats) => stats.call_counts(),
+        None => vec![],
+    };
+    let entries = counts
+        .into_iter()
+        .map(|(name, calls, tail_calls)| {
+            let entry = vec![
+                Value::Symbol(name).into(),
+                (calls as f64).into(),
+                (tail_calls as f64).into(),
+            ];
+            ctx.interpreter
+                .pair_manager
+                .vec_to_list(entry)
+                .source_mapped(ctx.range)
+        })
+        .collect();
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(entries)
+        .source_mapped(ctx.range)
+        .into())
+}
+
+/// Evaluates its operand, prints how long it took, and returns its value.
+fn time(mut ctx: SpecialFormContext) -> CallableResult {
+    let start = Instant::now();
+    let value = ctx.eval_unary()?;
+    let elapsed = start.elapsed();
+    ctx.interpreter
+        .printer
+        .println(format!("; {:.1}ms", elapsed.as_secs_f64() * 1000.0));
+    Ok(value.into())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        interpreter::RuntimeErrorType,
-        test_util::{test_eval_err, test_eval_success},
+        interpreter::{Interpreter, RuntimeErrorType},
+        test_util::{test_eval_err, test_eval_success, test_eval_successes},
     };
 
     #[test]
@@ -150,6 +303,165 @@ mod tests {
 
     #[test]
     fn assert_errors_when_operand_is_false() {
-        test_eval_err("(assert #f)", RuntimeErrorType::AssertionFailure);
+        test_eval_err(
+            "(assert #f)",
+            RuntimeErrorType::AssertionFailure("#f".to_string()),
+        );
+    }
+
+    #[test]
+    fn assert_failures_include_expression() {
+        test_eval_err(
+            "(define x 4) (assert (=   x 5))",
+            RuntimeErrorType::AssertionFailure("(=   x 5)".to_string()),
+        );
+    }
+
+    #[test]
+    fn gensym_works() {
+        test_eval_successes(&[
+            ("(define a (gensym))", ""),
+            ("(eq? a a)", "#t"),
+            ("(eq? a (gensym))", "#f"),
+            ("(eq? (gensym) (gensym))", "#f"),
+        ]);
+    }
+
+    #[test]
+    fn gc_roots_works_at_top_level() {
+        test_eval_success(
+            "(gc-roots)",
+            "Rooted values: 1\n  pair: 1\nActive lexical scopes: 0\n(1 0)",
+        );
+        // Only the expression being evaluated and the ones after it are rooted.
+        test_eval_success(
+            "(define x 1) (car (gc-roots)) 5",
+            "Rooted values: 2\n  number: 1\n  pair: 1\nActive lexical scopes: 0\n5",
+        );
+    }
+
+    #[test]
+    fn gc_roots_works_mid_computation() {
+        test_eval_success(
+            "
+            (define roots #f)
+            (define (recurse n)
+              (if (= n 0)
+                (set! roots (gc-roots))
+                (let ((m (- n 1))) (recurse m) n)))
+            (with-output-to-string (lambda () (recurse 3)))
+            roots
+            ",
+            "(2 7)",
+        );
+    }
+
+    #[test]
+    fn apropos_works() {
+        test_eval_successes(&[
+            (
+                r#"(define (has? name query) (if (find (lambda (x) (eq? x name)) (apropos query)) #t #f))"#,
+                "",
+            ),
+            (r#"(has? 'string-upcase "str")"#, "#t"),
+            (r#"(has? 'list->string "str")"#, "#t"),
+            (r#"(has? 'car "str")"#, "#f"),
+        ]);
+        test_eval_success(r#"(apropos "this-does-not-exist")"#, "()");
+        test_eval_success(
+            r#"(define my-very-unusual-name 1) (apropos "very-unusual")"#,
+            "(my-very-unusual-name)",
+        );
+        test_eval_err("(apropos 'str)", RuntimeErrorType::ExpectedString);
+    }
+
+    #[test]
+    fn environment_to_alist_works() {
+        test_eval_success("(environment->alist)", "()");
+        test_eval_success(
+            "(define b \"hi\") (define a 1) (environment->alist)",
+            "((a . 1) (b . \"hi\"))",
+        );
+        test_eval_success(
+            "(define (f x) x) (set! f 5) (environment->alist)",
+            "((f . 5))",
+        );
+        test_eval_success("(assq 'car (environment->alist))", "#f");
+        test_eval_success("(eq? (cdr (assq 'car (environment->alist #t))) car)", "#t");
+        test_eval_success("(assq 'zero? (environment->alist))", "#f");
+        test_eval_err(
+            "(environment->alist #t #t)",
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+    }
+
+    #[test]
+    fn get_call_stats_works() {
+        let mut interpreter = Interpreter::new();
+        interpreter.printer.disable_autoflush = true;
+        let mut eval = |code: &str| {
+            let source_id = interpreter.source_mapper.add("<code>".into(), code.into());
+            interpreter.evaluate(source_id).unwrap().to_string()
+        };
+        assert_eq!(eval("(get-call-stats)"), "()");
+        eval(
+            "
+            (define (count-down n) (if (= n 0) 'done (count-down (- n 1))))
+            (define (f n) (if (= n 0) 0 (+ 1 (f (- n 1)))))
+            (track-stats (begin (f 3) (count-down 5)))
+            ",
+        );
+        assert_eq!(eval("(assq 'f (get-call-stats))"), "(f 4 0)");
+        // Note that even the initial call to `count-down` is a tail call, since it's
+        // the last expression in the `begin`.
+        assert_eq!(
+            eval("(assq 'count-down (get-call-stats))"),
+            "(count-down 0 6)"
+        );
+    }
+
+    #[test]
+    fn profile_accumulates_time() {
+        let mut interpreter = Interpreter::new();
+        interpreter.printer.disable_autoflush = true;
+        let mut profile_time_spent = |iterations: usize| {
+            let code = format!(
+                "
+                (define (work n) (if (= n 0) 0 (+ 1 (work (- n 1)))))
+                (define (loop i) (if (> i 0) (begin (work 50) (loop (- i 1)))))
+                (profile (loop {iterations}))
+                "
+            );
+            let source_id = interpreter.source_mapper.add("<code>".into(), code);
+            interpreter.evaluate(source_id).unwrap();
+            let stats = interpreter.last_tracked_stats.as_ref().unwrap();
+            stats.time_spent("work").unwrap()
+        };
+        let short_time = profile_time_spent(5);
+        let long_time = profile_time_spent(100);
+        assert!(short_time.as_nanos() > 0);
+        assert!(long_time > short_time, "{long_time:?} <= {short_time:?}");
+    }
+
+    #[test]
+    fn time_works() {
+        let mut interpreter = Interpreter::new();
+        interpreter.printer.disable_autoflush = true;
+        let source_id = interpreter
+            .source_mapper
+            .add("<code>".into(), "(time (list 1 (+ 1 1)))".into());
+        let value = interpreter.evaluate(source_id).unwrap();
+        assert_eq!(value.to_string(), "(1 2)");
+        let output = interpreter.printer.take_buffered_output();
+        assert!(
+            output.starts_with("; ") && output.ends_with("ms\n"),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn time_requires_one_operand() {
+        test_eval_err("(time)", RuntimeErrorType::WrongNumberOfArguments);
+        test_eval_err("(time 1 2)", RuntimeErrorType::WrongNumberOfArguments);
     }
 }
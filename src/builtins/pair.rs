@@ -1,12 +1,22 @@
+use std::collections::HashMap;
+
 use crate::{
     builtin_procedure::{BuiltinProcedureContext, BuiltinProcedureFn},
     builtins::Builtin,
     callable::CallableResult,
+    interpreter::{RuntimeError, RuntimeErrorType},
+    pair::PairVisitedSet,
     source_mapped::SourceMappable,
     value::{SourceValue, Value},
 };
 
-use super::Builtins;
+use super::{
+    eq::{equal_hash, is_eq, is_equal, is_eqv},
+    values::make_values,
+    Builtins,
+};
+
+type Comparator = fn(&SourceValue, &SourceValue) -> Result<bool, RuntimeError>;
 
 pub fn get_builtins() -> Builtins {
     vec![
@@ -16,7 +26,43 @@ pub fn get_builtins() -> Builtins {
         Builtin::Procedure("car", BuiltinProcedureFn::Unary(car)),
         Builtin::Procedure("cdr", BuiltinProcedureFn::Unary(cdr)),
         Builtin::Procedure("list", BuiltinProcedureFn::NullaryVariadic(list)),
+        Builtin::Procedure("cons*", BuiltinProcedureFn::UnaryVariadic(cons_star)),
+        Builtin::Procedure("list-tabulate", BuiltinProcedureFn::Binary(list_tabulate)),
+        Builtin::Procedure("iota", BuiltinProcedureFn::UnaryVariadic(iota)),
         Builtin::Procedure("pair?", BuiltinProcedureFn::Unary(pair)),
+        Builtin::Procedure("memq", BuiltinProcedureFn::Binary(memq)),
+        Builtin::Procedure("memv", BuiltinProcedureFn::Binary(memv)),
+        Builtin::Procedure("member", BuiltinProcedureFn::Binary(member)),
+        Builtin::Procedure("assq", BuiltinProcedureFn::Binary(assq)),
+        Builtin::Procedure("assv", BuiltinProcedureFn::Binary(assv)),
+        Builtin::Procedure("assoc", BuiltinProcedureFn::UnaryVariadic(assoc)),
+        Builtin::Procedure("list-ref", BuiltinProcedureFn::Binary(list_ref)),
+        Builtin::Procedure("list-tail", BuiltinProcedureFn::Binary(list_tail)),
+        Builtin::Procedure("list-set!", BuiltinProcedureFn::Ternary(list_set)),
+        Builtin::Procedure("last", BuiltinProcedureFn::Unary(last)),
+        Builtin::Procedure("last-pair", BuiltinProcedureFn::Unary(last_pair)),
+        Builtin::Procedure("take", BuiltinProcedureFn::Binary(take)),
+        Builtin::Procedure("drop", BuiltinProcedureFn::Binary(list_tail)),
+        Builtin::Procedure("find", BuiltinProcedureFn::Binary(find)),
+        Builtin::Procedure("list-index", BuiltinProcedureFn::Binary(list_index)),
+        Builtin::Procedure("remove", BuiltinProcedureFn::Binary(remove)),
+        Builtin::Procedure("partition", BuiltinProcedureFn::Binary(partition)),
+        Builtin::Procedure("delete", BuiltinProcedureFn::Binary(delete)),
+        Builtin::Procedure(
+            "delete-duplicates",
+            BuiltinProcedureFn::Unary(delete_duplicates),
+        ),
+        Builtin::Procedure("reduce", BuiltinProcedureFn::Ternary(reduce)),
+        Builtin::Procedure("map", BuiltinProcedureFn::UnaryVariadic(map)),
+        Builtin::Procedure("for-each", BuiltinProcedureFn::UnaryVariadic(for_each)),
+        Builtin::Procedure("append-map", BuiltinProcedureFn::UnaryVariadic(append_map)),
+        Builtin::Procedure("count", BuiltinProcedureFn::UnaryVariadic(count)),
+        Builtin::Procedure("zip", BuiltinProcedureFn::NullaryVariadic(zip)),
+        Builtin::Procedure("unzip1", BuiltinProcedureFn::Unary(unzip1)),
+        Builtin::Procedure("unzip2", BuiltinProcedureFn::Unary(unzip2)),
+        Builtin::Procedure("flatten", BuiltinProcedureFn::Unary(flatten)),
+        Builtin::Procedure("every", BuiltinProcedureFn::UnaryVariadic(every)),
+        Builtin::Procedure("any", BuiltinProcedureFn::UnaryVariadic(any)),
     ]
 }
 
@@ -62,13 +108,630 @@ fn list(ctx: BuiltinProcedureContext, operands: &[SourceValue]) -> CallableResul
         .into())
 }
 
+/// Like `list`, but the last operand becomes the final cdr.
+fn cons_star(
+    ctx: BuiltinProcedureContext,
+    first: &SourceValue,
+    rest: &[SourceValue],
+) -> CallableResult {
+    let Some((last, middle)) = rest.split_last() else {
+        return Ok(first.clone().into());
+    };
+    let mut initial_values = Vec::with_capacity(rest.len());
+    initial_values.push(first.clone());
+    initial_values.extend_from_slice(middle);
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_pair(initial_values, last.clone())
+        .source_mapped(ctx.range)
+        .into())
+}
+
+fn list_tabulate(
+    ctx: BuiltinProcedureContext,
+    n: &SourceValue,
+    func: &SourceValue,
+) -> CallableResult {
+    let n = n.expect_non_negative_integer()?;
+    let procedure = func.expect_procedure()?;
+    // We don't preallocate, since `n` can be absurdly large.
+    let mut results = vec![];
+    for i in 0..n {
+        ctx.interpreter.check_interrupt(ctx.range)?;
+        results.push(ctx.interpreter.call_procedure(
+            procedure.clone(),
+            &[(i as f64).into()],
+            ctx.range,
+        )?);
+    }
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(results)
+        .source_mapped(ctx.range)
+        .into())
+}
+
+/// SRFI-1's `(iota count [start [step]])`. Each element is computed as
+/// `start + i * step`, rather than by repeated addition, so rounding errors
+/// don't accumulate.
+fn iota(ctx: BuiltinProcedureContext, count: &SourceValue, rest: &[SourceValue]) -> CallableResult {
+    let count = count.expect_non_negative_integer()?;
+    let (start, step) = match rest {
+        [] => (0.0, 1.0),
+        [start] => (start.expect_number()?, 1.0),
+        [start, step] => (start.expect_number()?, step.expect_number()?),
+        _ => return Err(RuntimeErrorType::WrongNumberOfArguments.source_mapped(ctx.range)),
+    };
+    // We don't preallocate, since `count` can be absurdly large, in which
+    // case the user can at least interrupt us.
+    let mut items = vec![];
+    for i in 0..count {
+        ctx.interpreter.check_interrupt(ctx.range)?;
+        items.push((start + i as f64 * step).into());
+    }
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(items)
+        .source_mapped(ctx.range)
+        .into())
+}
+
 fn pair(_ctx: BuiltinProcedureContext, operand: &SourceValue) -> CallableResult {
     Ok(matches!(operand.0, Value::Pair(_)).into())
 }
 
+/// Returns the first sublist of the given list whose car satisfies the given
+/// predicate, or `None` if no element satisfies it.
+///
+/// Raises an error if the list is improper or cyclic.
+fn search_list<F>(list: &SourceValue, mut predicate: F) -> Result<Option<SourceValue>, RuntimeError>
+where
+    F: FnMut(&SourceValue) -> Result<bool, RuntimeError>,
+{
+    let mut visited = PairVisitedSet::default();
+    let mut current = list.clone();
+    loop {
+        let next = match &current.0 {
+            Value::EmptyList => return Ok(None),
+            Value::Pair(pair) => {
+                if !visited.add(pair) {
+                    return Err(RuntimeErrorType::ExpectedList.source_mapped(list.1));
+                }
+                if predicate(&pair.car())? {
+                    return Ok(Some(current));
+                }
+                pair.cdr()
+            }
+            _ => return Err(RuntimeErrorType::ExpectedList.source_mapped(list.1)),
+        };
+        current = next;
+    }
+}
+
+fn find_member(obj: &SourceValue, list: &SourceValue, comparator: Comparator) -> CallableResult {
+    match search_list(list, |item| comparator(obj, item))? {
+        Some(sublist) => Ok(sublist.into()),
+        None => Ok(false.into()),
+    }
+}
+
+fn find_association<F>(obj: &SourceValue, alist: &SourceValue, mut comparator: F) -> CallableResult
+where
+    F: FnMut(&SourceValue, &SourceValue) -> Result<bool, RuntimeError>,
+{
+    match search_list(alist, |item| comparator(obj, &item.expect_pair()?.car()))? {
+        Some(sublist) => Ok(sublist.expect_pair()?.car().into()),
+        None => Ok(false.into()),
+    }
+}
+
+fn memq(_ctx: BuiltinProcedureContext, obj: &SourceValue, list: &SourceValue) -> CallableResult {
+    find_member(obj, list, is_eq)
+}
+
+fn memv(_ctx: BuiltinProcedureContext, obj: &SourceValue, list: &SourceValue) -> CallableResult {
+    find_member(obj, list, is_eqv)
+}
+
+fn member(_ctx: BuiltinProcedureContext, obj: &SourceValue, list: &SourceValue) -> CallableResult {
+    find_member(obj, list, is_equal)
+}
+
+fn assq(_ctx: BuiltinProcedureContext, obj: &SourceValue, alist: &SourceValue) -> CallableResult {
+    find_association(obj, alist, is_eq)
+}
+
+fn assv(_ctx: BuiltinProcedureContext, obj: &SourceValue, alist: &SourceValue) -> CallableResult {
+    find_association(obj, alist, is_eqv)
+}
+
+/// Implements `(assoc obj alist [compare])`, where `compare` is called as
+/// `(compare obj key)` and defaults to `equal?`.
+fn assoc(ctx: BuiltinProcedureContext, obj: &SourceValue, rest: &[SourceValue]) -> CallableResult {
+    match rest {
+        [alist] => find_association(obj, alist, is_equal),
+        [alist, compare] => {
+            let compare = compare.expect_procedure()?;
+            find_association(obj, alist, |obj, key| {
+                ctx.interpreter.check_interrupt(ctx.range)?;
+                let result = ctx.interpreter.call_procedure(
+                    compare.clone(),
+                    &[obj.clone(), key.clone()],
+                    ctx.range,
+                )?;
+                Ok(result.0.as_bool())
+            })
+        }
+        _ => Err(RuntimeErrorType::WrongNumberOfArguments.source_mapped(ctx.range)),
+    }
+}
+
+fn list_ref(_ctx: BuiltinProcedureContext, list: &SourceValue, k: &SourceValue) -> CallableResult {
+    let items = list.expect_list()?;
+    let index = k.expect_index(items.len())?;
+    Ok(items[index].clone().into())
+}
+
+fn list_tail(_ctx: BuiltinProcedureContext, list: &SourceValue, k: &SourceValue) -> CallableResult {
+    let index = k.expect_index_or_end(list.expect_list()?.len())?;
+    let mut tail = list.clone();
+    for _ in 0..index {
+        tail = tail.expect_pair()?.cdr();
+    }
+    Ok(tail.into())
+}
+
+fn list_set(
+    ctx: BuiltinProcedureContext,
+    list: &SourceValue,
+    k: &SourceValue,
+    value: &SourceValue,
+) -> CallableResult {
+    let index = k.expect_index(list.expect_list()?.len())?;
+    let mut pair = list.expect_pair()?;
+    for _ in 0..index {
+        pair = pair.cdr().expect_pair()?;
+    }
+    pair.set_car(value.clone());
+    ctx.undefined()
+}
+
+fn last(_ctx: BuiltinProcedureContext, list: &SourceValue) -> CallableResult {
+    let items = list.expect_list()?;
+    let Some(item) = items.last() else {
+        return Err(RuntimeErrorType::ExpectedPair.source_mapped(list.1));
+    };
+    Ok(item.clone().into())
+}
+
+/// Returns the final pair of a non-empty proper list itself, rather than a
+/// copy, so it can be mutated.
+fn last_pair(_ctx: BuiltinProcedureContext, list: &SourceValue) -> CallableResult {
+    let length = list.expect_list()?.len();
+    let mut pair = list.expect_pair()?;
+    for _ in 1..length {
+        pair = pair.cdr().expect_pair()?;
+    }
+    Ok(Value::Pair(pair).source_mapped(list.1).into())
+}
+
+/// Returns a fresh list of the first `k` elements of the list. Its
+/// counterpart, `drop`, is just `list-tail`, which shares structure with
+/// the original list rather than copying it.
+fn take(ctx: BuiltinProcedureContext, list: &SourceValue, k: &SourceValue) -> CallableResult {
+    let items = list.expect_list()?;
+    let count = k.expect_index_or_end(items.len())?;
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(items[..count].to_vec())
+        .source_mapped(ctx.range)
+        .into())
+}
+
+/// Returns the index and value of the first element of the list that satisfies
+/// the given predicate.
+fn find_index(
+    ctx: &mut BuiltinProcedureContext,
+    pred: &SourceValue,
+    list: &SourceValue,
+) -> Result<Option<(usize, SourceValue)>, RuntimeError> {
+    let procedure = pred.expect_procedure()?;
+    for (i, item) in list.expect_list()?.iter().enumerate() {
+        ctx.interpreter.check_interrupt(ctx.range)?;
+        let result = ctx.interpreter.call_procedure(
+            procedure.clone(),
+            std::slice::from_ref(item),
+            ctx.range,
+        )?;
+        if result.0.as_bool() {
+            return Ok(Some((i, item.clone())));
+        }
+    }
+    Ok(None)
+}
+
+fn find(
+    mut ctx: BuiltinProcedureContext,
+    pred: &SourceValue,
+    list: &SourceValue,
+) -> CallableResult {
+    Ok(match find_index(&mut ctx, pred, list)? {
+        Some((_, item)) => item.into(),
+        None => false.into(),
+    })
+}
+
+fn list_index(
+    mut ctx: BuiltinProcedureContext,
+    pred: &SourceValue,
+    list: &SourceValue,
+) -> CallableResult {
+    Ok(match find_index(&mut ctx, pred, list)? {
+        Some((i, _)) => (i as f64).into(),
+        None => false.into(),
+    })
+}
+
+fn remove(ctx: BuiltinProcedureContext, pred: &SourceValue, list: &SourceValue) -> CallableResult {
+    let procedure = pred.expect_procedure()?;
+    let mut results = vec![];
+    for item in list.expect_list()?.iter() {
+        ctx.interpreter.check_interrupt(ctx.range)?;
+        let result = ctx.interpreter.call_procedure(
+            procedure.clone(),
+            std::slice::from_ref(item),
+            ctx.range,
+        )?;
+        if !result.0.as_bool() {
+            results.push(item.clone());
+        }
+    }
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(results)
+        .source_mapped(ctx.range)
+        .into())
+}
+
+/// Returns two values: a list of the elements that satisfy the predicate, and
+/// a list of the ones that don't.
+fn partition(
+    ctx: BuiltinProcedureContext,
+    pred: &SourceValue,
+    list: &SourceValue,
+) -> CallableResult {
+    let procedure = pred.expect_procedure()?;
+    let mut matches = vec![];
+    let mut non_matches = vec![];
+    for item in list.expect_list()?.iter() {
+        ctx.interpreter.check_interrupt(ctx.range)?;
+        let result = ctx.interpreter.call_procedure(
+            procedure.clone(),
+            std::slice::from_ref(item),
+            ctx.range,
+        )?;
+        if result.0.as_bool() {
+            matches.push(item.clone());
+        } else {
+            non_matches.push(item.clone());
+        }
+    }
+    let pair_manager = &mut ctx.interpreter.pair_manager;
+    let matches = pair_manager.vec_to_list(matches).source_mapped(ctx.range);
+    let non_matches = pair_manager
+        .vec_to_list(non_matches)
+        .source_mapped(ctx.range);
+    Ok(make_values(vec![matches, non_matches])
+        .source_mapped(ctx.range)
+        .into())
+}
+
+fn delete(ctx: BuiltinProcedureContext, obj: &SourceValue, list: &SourceValue) -> CallableResult {
+    let mut results = vec![];
+    for item in list.expect_list()?.iter() {
+        if !is_equal(obj, item)? {
+            results.push(item.clone());
+        }
+    }
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(results)
+        .source_mapped(ctx.range)
+        .into())
+}
+
+/// Removes all but the first occurrence of `equal?` elements. Elements are
+/// bucketed by their hash, so this doesn't need to compare every pair of
+/// elements.
+fn delete_duplicates(ctx: BuiltinProcedureContext, list: &SourceValue) -> CallableResult {
+    let mut seen: HashMap<u64, Vec<SourceValue>> = HashMap::new();
+    let mut results = vec![];
+    'items: for item in list.expect_list()?.iter() {
+        let bucket = seen.entry(equal_hash(item)).or_default();
+        for seen_item in bucket.iter() {
+            if is_equal(seen_item, item)? {
+                continue 'items;
+            }
+        }
+        bucket.push(item.clone());
+        results.push(item.clone());
+    }
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(results)
+        .source_mapped(ctx.range)
+        .into())
+}
+
+/// Implements SRFI-1's `(reduce f ridentity list)`.
+///
+/// If the list is empty, `ridentity` is returned; if it has one element, that
+/// element is returned. Otherwise, `f` is called with each subsequent element
+/// as its *first* argument and the accumulated result as its *second*, so e.g.
+/// `(reduce - 0 '(1 2 3))` is `(- 3 (- 2 1))`.
+fn reduce(
+    ctx: BuiltinProcedureContext,
+    func: &SourceValue,
+    ridentity: &SourceValue,
+    list: &SourceValue,
+) -> CallableResult {
+    let procedure = func.expect_procedure()?;
+    let items = list.expect_list()?;
+    let Some((first, rest)) = items.split_first() else {
+        return Ok(ridentity.clone().into());
+    };
+    let mut result = first.clone();
+    for item in rest {
+        result = ctx.interpreter.call_procedure(
+            procedure.clone(),
+            &[item.clone(), result],
+            ctx.range,
+        )?;
+    }
+    Ok(result.into())
+}
+
+/// Given one or more lists, returns a vec containing the arguments to pass to a
+/// procedure for each of their elements, stopping at the end of the shortest
+/// list.
+///
+/// Being given no lists at all is an error, since there's nothing to iterate
+/// over.
+fn zip_lists(
+    ctx: &BuiltinProcedureContext,
+    lists: &[SourceValue],
+) -> Result<Vec<Vec<SourceValue>>, RuntimeError> {
+    if lists.is_empty() {
+        return Err(RuntimeErrorType::WrongNumberOfArguments.source_mapped(ctx.range));
+    }
+    let lists = lists
+        .iter()
+        .map(|list| list.expect_list())
+        .collect::<Result<Vec<_>, _>>()?;
+    let len = lists.iter().map(|list| list.len()).min().unwrap_or(0);
+    Ok((0..len)
+        .map(|i| lists.iter().map(|list| list[i].clone()).collect())
+        .collect())
+}
+
+fn map(ctx: BuiltinProcedureContext, func: &SourceValue, lists: &[SourceValue]) -> CallableResult {
+    let procedure = func.expect_procedure()?;
+    let args_list = zip_lists(&ctx, lists)?;
+    let mut results = Vec::with_capacity(args_list.len());
+    for args in args_list {
+        ctx.interpreter.check_interrupt(ctx.range)?;
+        results.push(
+            ctx.interpreter
+                .call_procedure(procedure.clone(), &args, ctx.range)?,
+        );
+    }
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(results)
+        .source_mapped(ctx.range)
+        .into())
+}
+
+fn for_each(
+    ctx: BuiltinProcedureContext,
+    func: &SourceValue,
+    lists: &[SourceValue],
+) -> CallableResult {
+    let procedure = func.expect_procedure()?;
+    for args in zip_lists(&ctx, lists)? {
+        ctx.interpreter.check_interrupt(ctx.range)?;
+        ctx.interpreter
+            .call_procedure(procedure.clone(), &args, ctx.range)?;
+    }
+    ctx.undefined()
+}
+
+fn append_map(
+    ctx: BuiltinProcedureContext,
+    func: &SourceValue,
+    lists: &[SourceValue],
+) -> CallableResult {
+    let procedure = func.expect_procedure()?;
+    let mut results = vec![];
+    for args in zip_lists(&ctx, lists)? {
+        ctx.interpreter.check_interrupt(ctx.range)?;
+        let result = ctx
+            .interpreter
+            .call_procedure(procedure.clone(), &args, ctx.range)?;
+        results.extend(result.expect_list()?.iter().cloned());
+    }
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(results)
+        .source_mapped(ctx.range)
+        .into())
+}
+
+fn count(
+    ctx: BuiltinProcedureContext,
+    pred: &SourceValue,
+    lists: &[SourceValue],
+) -> CallableResult {
+    let procedure = pred.expect_procedure()?;
+    let mut count = 0;
+    for args in zip_lists(&ctx, lists)? {
+        ctx.interpreter.check_interrupt(ctx.range)?;
+        let result = ctx
+            .interpreter
+            .call_procedure(procedure.clone(), &args, ctx.range)?;
+        if result.0.as_bool() {
+            count += 1;
+        }
+    }
+    Ok((count as f64).into())
+}
+
+fn zip(ctx: BuiltinProcedureContext, lists: &[SourceValue]) -> CallableResult {
+    let tuples = zip_lists(&ctx, lists)?
+        .into_iter()
+        .map(|tuple| {
+            ctx.interpreter
+                .pair_manager
+                .vec_to_list(tuple)
+                .source_mapped(ctx.range)
+        })
+        .collect();
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(tuples)
+        .source_mapped(ctx.range)
+        .into())
+}
+
+/// Returns a vec of lists, where the nth list contains the nth element of
+/// each of the given list's elements, which must all be lists with at least
+/// `count` elements.
+fn unzip(
+    ctx: &mut BuiltinProcedureContext,
+    list: &SourceValue,
+    count: usize,
+) -> Result<Vec<SourceValue>, RuntimeError> {
+    let mut columns = vec![vec![]; count];
+    for tuple in list.expect_list()?.iter() {
+        let mut rest = tuple.clone();
+        for column in columns.iter_mut() {
+            let pair = rest.expect_pair()?;
+            column.push(pair.car());
+            rest = pair.cdr();
+        }
+    }
+    Ok(columns
+        .into_iter()
+        .map(|column| {
+            ctx.interpreter
+                .pair_manager
+                .vec_to_list(column)
+                .source_mapped(ctx.range)
+        })
+        .collect())
+}
+
+fn unzip1(mut ctx: BuiltinProcedureContext, list: &SourceValue) -> CallableResult {
+    let mut columns = unzip(&mut ctx, list, 1)?;
+    Ok(columns.remove(0).into())
+}
+
+fn unzip2(mut ctx: BuiltinProcedureContext, list: &SourceValue) -> CallableResult {
+    let columns = unzip(&mut ctx, list, 2)?;
+    Ok(make_values(columns).source_mapped(ctx.range).into())
+}
+
+/// Recursively flattens nested lists into a single list.
+///
+/// Note that `expect_list()` uses `Pair::get_type()`, which looks for cycles
+/// through the cars of a list as well as its cdrs, so any cyclic structure
+/// is rejected before we start descending into it.
+fn flatten(ctx: BuiltinProcedureContext, list: &SourceValue) -> CallableResult {
+    list.expect_list()?;
+    let mut results = vec![];
+    // Each entry is the remainder of a list we're partway through. We keep
+    // our own stack of these, rather than recursing, so deeply nested lists
+    // don't overflow the call stack.
+    let mut pending = vec![list.clone()];
+    while let Some(rest) = pending.pop() {
+        match &rest.0 {
+            Value::Pair(pair) => {
+                pending.push(pair.cdr());
+                let item = pair.car();
+                match item.0 {
+                    Value::Pair(_) | Value::EmptyList => pending.push(item),
+                    _ => results.push(item),
+                }
+            }
+            Value::EmptyList => {}
+            _ => return Err(RuntimeErrorType::ExpectedList.source_mapped(rest.1)),
+        }
+    }
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(results)
+        .source_mapped(ctx.range)
+        .into())
+}
+
+/// Returns `#f` as soon as the predicate returns false for some elements,
+/// otherwise returns the predicate's last result (or `#t` if the lists are
+/// empty).
+fn every(
+    ctx: BuiltinProcedureContext,
+    pred: &SourceValue,
+    lists: &[SourceValue],
+) -> CallableResult {
+    let procedure = pred.expect_procedure()?;
+    let mut result: SourceValue = Value::Boolean(true).into();
+    for args in zip_lists(&ctx, lists)? {
+        ctx.interpreter.check_interrupt(ctx.range)?;
+        result = ctx
+            .interpreter
+            .call_procedure(procedure.clone(), &args, ctx.range)?;
+        if !result.0.as_bool() {
+            break;
+        }
+    }
+    Ok(result.into())
+}
+
+/// Returns the first true result of the predicate, or `#f` if there isn't one.
+fn any(ctx: BuiltinProcedureContext, pred: &SourceValue, lists: &[SourceValue]) -> CallableResult {
+    let procedure = pred.expect_procedure()?;
+    for args in zip_lists(&ctx, lists)? {
+        ctx.interpreter.check_interrupt(ctx.range)?;
+        let result = ctx
+            .interpreter
+            .call_procedure(procedure.clone(), &args, ctx.range)?;
+        if result.0.as_bool() {
+            return Ok(result.into());
+        }
+    }
+    Ok(false.into())
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::test_util::test_eval_success;
+    use std::sync::mpsc::channel;
+
+    use crate::{
+        builtin_procedure::BuiltinProcedureContext,
+        interpreter::{Interpreter, RuntimeErrorType},
+        test_util::{test_eval_err, test_eval_success, test_eval_successes},
+        value::Value,
+    };
 
     #[test]
     fn set_car_works() {
@@ -110,4 +773,618 @@ mod tests {
         test_eval_success("(pair? '(1 . 2))", "#t");
         test_eval_success("(pair? '(1 2))", "#t");
     }
+
+    #[test]
+    fn memq_works() {
+        // From R5RS section 6.3.2.
+        test_eval_success("(memq 'a '(a b c))", "(a b c)");
+        test_eval_success("(memq 'b '(a b c))", "(b c)");
+        test_eval_success("(memq 'a '(b c d))", "#f");
+        test_eval_success("(memq (list 'a) '(b (a) c))", "#f");
+    }
+
+    #[test]
+    fn memv_works() {
+        // From R5RS section 6.3.2.
+        test_eval_success("(memv 101 '(100 101 102))", "(101 102)");
+
+        // R5RS says that `memq` on numbers is unspecified (it happens to work in our
+        // implementation), whereas `memv` is guaranteed to find them.
+        test_eval_success("(memv 2 '(1 2 3))", "(2 3)");
+        test_eval_success("(memv 2.0 '(1 2 3))", "(2 3)");
+        test_eval_success("(memv 4 '(1 2 3))", "#f");
+        test_eval_success("(memv '(2) '(1 (2) 3))", "#f");
+    }
+
+    #[test]
+    fn member_works() {
+        // From R5RS section 6.3.2.
+        test_eval_success("(member (list 'a) '(b (a) c))", "((a) c)");
+        test_eval_success("(member \"b\" '(\"a\" \"b\"))", "(\"b\")");
+        test_eval_success("(member 'z '())", "#f");
+    }
+
+    #[test]
+    fn member_errors_on_improper_and_cyclic_lists() {
+        test_eval_err("(memq 'z '(a . b))", RuntimeErrorType::ExpectedList);
+        test_eval_err(
+            "(define x '(a b)) (set-cdr! (cdr x) x) (memq 'z x)",
+            RuntimeErrorType::ExpectedList,
+        );
+    }
+
+    #[test]
+    fn assq_works() {
+        // From R5RS section 6.3.2.
+        test_eval_success("(assq 'a '((a 1) (b 2) (c 3)))", "(a 1)");
+        test_eval_success("(assq 'b '((a 1) (b 2) (c 3)))", "(b 2)");
+        test_eval_success("(assq 'd '((a 1) (b 2) (c 3)))", "#f");
+        test_eval_success("(assq (list 'a) '(((a)) ((b)) ((c))))", "#f");
+    }
+
+    #[test]
+    fn assv_works() {
+        // From R5RS section 6.3.2.
+        test_eval_success("(assv 5 '((2 3) (5 7) (11 13)))", "(5 7)");
+        test_eval_success("(assv 6 '((2 3) (5 7) (11 13)))", "#f");
+    }
+
+    #[test]
+    fn assoc_works() {
+        // From R5RS section 6.3.2.
+        test_eval_success("(assoc (list 'a) '(((a)) ((b)) ((c))))", "((a))");
+    }
+
+    #[test]
+    fn assoc_works_with_custom_comparators() {
+        test_eval_successes(&[
+            ("(define (close? a b) (< (abs (- a b)) 0.01))", ""),
+            (
+                "(assoc 2.001 '((1 one) (2 two) (3 three)) close?)",
+                "(2 two)",
+            ),
+            ("(assoc 2.5 '((1 one) (2 two) (3 three)) close?)", "#f"),
+            ("(assoc 2.0 '((1 one) (2 two)) =)", "(2 two)"),
+            ("(apply assoc (list 3 '((1 one) (3 three)) =))", "(3 three)"),
+        ]);
+        test_eval_success(
+            "(assoc 1 '((2 two) (1 one)) (lambda (obj key) (list obj key)))",
+            "(2 two)",
+        );
+        test_eval_err(
+            "(assoc 1 '((1 one)) 5)",
+            RuntimeErrorType::ExpectedProcedure,
+        );
+        test_eval_err(
+            "(assoc 1 '((1 one)) = 5)",
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+        test_eval_err("(assoc 1)", RuntimeErrorType::WrongNumberOfArguments);
+    }
+
+    #[test]
+    fn assoc_errors_on_non_pair_elements() {
+        test_eval_err("(assq 'b '((a 1) b))", RuntimeErrorType::ExpectedPair);
+    }
+
+    #[test]
+    fn reduce_works() {
+        test_eval_success("(reduce + 0 '())", "0");
+        test_eval_success("(reduce + 0 '(5))", "5");
+        test_eval_success("(reduce + 0 '(1 2 3))", "6");
+        test_eval_success("(reduce - 0 '(1 2 3))", "2");
+        test_eval_success("(reduce list 0 '(1 2 3))", "(3 (2 1))");
+        test_eval_success("(reduce max 0 '(3 9 2))", "9");
+    }
+
+    #[test]
+    fn reduce_does_not_call_procedure_for_empty_or_singleton_lists() {
+        test_eval_success("(reduce (lambda (a b) (car a)) 0 '())", "0");
+        test_eval_success("(reduce (lambda (a b) (car a)) 0 '(1))", "1");
+        test_eval_err(
+            "(reduce (lambda (a b) (car a)) 0 '(1 2))",
+            RuntimeErrorType::ExpectedPair,
+        );
+        test_eval_err("(reduce 1 0 '(1 2))", RuntimeErrorType::ExpectedProcedure);
+    }
+
+    #[test]
+    fn cons_star_works() {
+        test_eval_success("(cons* 1)", "1");
+        test_eval_success("(cons* '(1 2))", "(1 2)");
+        test_eval_success("(cons* 1 2)", "(1 . 2)");
+        test_eval_success("(cons* 1 2 3)", "(1 2 . 3)");
+        test_eval_success("(cons* 1 2 '(3 4))", "(1 2 3 4)");
+        test_eval_success("(cons* 1 '())", "(1)");
+    }
+
+    #[test]
+    fn iota_works() {
+        test_eval_success("(iota 3)", "(0 1 2)");
+        test_eval_success("(iota 0)", "()");
+        test_eval_success("(iota 3 1)", "(1 2 3)");
+        test_eval_success("(iota 3 10 -1)", "(10 9 8)");
+        test_eval_success("(iota 3 0 0.1)", "(0 0.1 0.2)");
+        test_eval_success("(apply iota '(2 5))", "(5 6)");
+        test_eval_err("(iota -1)", RuntimeErrorType::ExpectedNonNegativeInteger);
+        test_eval_err("(iota 2 'a)", RuntimeErrorType::ExpectedNumber);
+        test_eval_err("(iota 1 2 3 4)", RuntimeErrorType::WrongNumberOfArguments);
+    }
+
+    #[test]
+    fn list_tabulate_works() {
+        test_eval_success("(list-tabulate 0 (lambda (i) i))", "()");
+        test_eval_success("(list-tabulate 4 (lambda (i) (* i i)))", "(0 1 4 9)");
+        test_eval_err(
+            "(list-tabulate -1 (lambda (i) i))",
+            RuntimeErrorType::ExpectedNonNegativeInteger,
+        );
+        test_eval_err(
+            "(list-tabulate 1.5 (lambda (i) i))",
+            RuntimeErrorType::ExpectedNonNegativeInteger,
+        );
+        test_eval_err("(list-tabulate 1 1)", RuntimeErrorType::ExpectedProcedure);
+    }
+
+    #[test]
+    fn list_tabulate_does_not_preallocate_huge_lists() {
+        test_eval_err(
+            "(list-tabulate (* 1000000 1000000 1000000) (lambda (i) (if (= i 2) (raise 'stop) i)))",
+            RuntimeErrorType::UncaughtException("stop".to_string()),
+        );
+    }
+
+    #[test]
+    fn find_works() {
+        test_eval_success("(find even? '(1 3 4 5 6))", "4");
+        test_eval_success("(find even? '(1 3 5))", "#f");
+        test_eval_success("(find even? '())", "#f");
+        test_eval_success(
+            "(define n 0) (find (lambda (x) (set! n (+ n 1)) (even? x)) '(1 2 3 4)) n",
+            "2",
+        );
+        test_eval_err("(find 1 '(1))", RuntimeErrorType::ExpectedProcedure);
+    }
+
+    #[test]
+    fn list_index_works() {
+        test_eval_success("(list-index even? '(1 3 4 5 6))", "2");
+        test_eval_success("(list-index even? '(1 3 5))", "#f");
+        test_eval_success("(list-index even? '())", "#f");
+        test_eval_err(
+            "(list-index even? '(1 . 2))",
+            RuntimeErrorType::ExpectedList,
+        );
+    }
+
+    #[test]
+    fn remove_works() {
+        test_eval_success("(remove even? '(1 2 3 4 5))", "(1 3 5)");
+        test_eval_success("(remove even? '(2 4))", "()");
+        test_eval_success("(remove even? '())", "()");
+        test_eval_err("(remove even? 5)", RuntimeErrorType::ExpectedList);
+    }
+
+    #[test]
+    fn partition_works() {
+        test_eval_success(
+            "(receive (evens odds) (partition even? '(1 2 3 4 5)) (list evens odds))",
+            "((2 4) (1 3 5))",
+        );
+        test_eval_success(
+            "(call-with-values (lambda () (partition even? '())) list)",
+            "(() ())",
+        );
+        test_eval_success(
+            "(define x (list 2 4)) (receive (evens odds) (partition even? x) (eq? evens x))",
+            "#f",
+        );
+        test_eval_err("(partition even? 5)", RuntimeErrorType::ExpectedList);
+    }
+
+    #[test]
+    fn delete_works() {
+        test_eval_success("(delete 2 '(1 2 3 2))", "(1 3)");
+        test_eval_success("(delete '(1) '((1) (2) (1)))", "((2))");
+        test_eval_success(r#"(delete "a" '("a" "b"))"#, r#"("b")"#);
+        test_eval_success("(delete 5 '(1 2))", "(1 2)");
+    }
+
+    #[test]
+    fn delete_duplicates_works() {
+        test_eval_success("(delete-duplicates '(1 2 1 3 2 4))", "(1 2 3 4)");
+        test_eval_success(
+            r#"(delete-duplicates '((1) "a" (1) "a" b b))"#,
+            r#"((1) "a" b)"#,
+        );
+        test_eval_success("(delete-duplicates '())", "()");
+        test_eval_success(
+            "(delete-duplicates (list-tabulate 1000 (lambda (i) (remainder i 10))))",
+            "(0 1 2 3 4 5 6 7 8 9)",
+        );
+    }
+
+    #[test]
+    fn list_ref_works() {
+        test_eval_success("(list-ref '(1 2 3) 0)", "1");
+        test_eval_success("(list-ref '(1 2 3) 2)", "3");
+        test_eval_err("(list-ref '(1 2 3) 1.5)", RuntimeErrorType::ExpectedInteger);
+        test_eval_err("(list-ref '(1 . 2) 0)", RuntimeErrorType::ExpectedList);
+    }
+
+    #[test]
+    fn list_ref_errors_on_out_of_range_indices() {
+        test_eval_err(
+            "(list-ref '(1 2 3) 3)",
+            RuntimeErrorType::IndexOutOfRange {
+                index: 3.0,
+                length: 3,
+            },
+        );
+        test_eval_err(
+            "(list-ref '(1 2 3) -1)",
+            RuntimeErrorType::IndexOutOfRange {
+                index: -1.0,
+                length: 3,
+            },
+        );
+        test_eval_err(
+            "(list-ref '() 0)",
+            RuntimeErrorType::IndexOutOfRange {
+                index: 0.0,
+                length: 0,
+            },
+        );
+    }
+
+    #[test]
+    fn last_works() {
+        test_eval_success("(last '(1 2 3))", "3");
+        test_eval_success("(last '(1))", "1");
+        test_eval_err("(last '())", RuntimeErrorType::ExpectedPair);
+        test_eval_err("(last '(1 . 2))", RuntimeErrorType::ExpectedList);
+        test_eval_err(
+            "(define x (list 1 2)) (set-cdr! (cdr x) x) (last x)",
+            RuntimeErrorType::ExpectedList,
+        );
+    }
+
+    #[test]
+    fn last_pair_works() {
+        test_eval_success("(last-pair '(1 2 3))", "(3)");
+        test_eval_success("(last-pair '(1))", "(1)");
+        test_eval_success("(define x (list 1 2)) (eq? (last-pair x) (cdr x))", "#t");
+        test_eval_success(
+            "(define x (list 1 2)) (set-cdr! (last-pair x) '(3)) x",
+            "(1 2 3)",
+        );
+        test_eval_err("(last-pair '())", RuntimeErrorType::ExpectedPair);
+        test_eval_err("(last-pair '(1 . 2))", RuntimeErrorType::ExpectedList);
+    }
+
+    #[test]
+    fn list_tail_works() {
+        test_eval_success("(list-tail '(1 2 3) 0)", "(1 2 3)");
+        test_eval_success("(list-tail '(1 2 3) 2)", "(3)");
+        test_eval_success("(list-tail '(1 2 3) 3)", "()");
+        test_eval_success("(define x '(1 2 3)) (eq? (list-tail x 1) (cdr x))", "#t");
+        test_eval_err(
+            "(list-tail '(1 2 3) 0.5)",
+            RuntimeErrorType::ExpectedInteger,
+        );
+    }
+
+    #[test]
+    fn list_tail_errors_on_out_of_range_indices() {
+        test_eval_err(
+            "(list-tail '(1 2 3) 4)",
+            RuntimeErrorType::IndexOutOfRange {
+                index: 4.0,
+                length: 3,
+            },
+        );
+        test_eval_err(
+            "(list-tail '(1 2 3) -1)",
+            RuntimeErrorType::IndexOutOfRange {
+                index: -1.0,
+                length: 3,
+            },
+        );
+    }
+
+    #[test]
+    fn list_set_works() {
+        test_eval_success("(define x (list 1 2 3)) (list-set! x 0 'a) x", "(a 2 3)");
+        test_eval_success("(define x (list 1 2 3)) (list-set! x 2 'c) x", "(1 2 c)");
+        test_eval_success(
+            "(define x (list 1 2 3)) (define y (cdr x)) (list-set! x 1 'b) y",
+            "(b 3)",
+        );
+        test_eval_err("(list-set! '(1 . 2) 0 'a)", RuntimeErrorType::ExpectedList);
+    }
+
+    #[test]
+    fn list_set_errors_on_out_of_range_indices() {
+        test_eval_err(
+            "(list-set! (list 1 2 3) 3 'a)",
+            RuntimeErrorType::IndexOutOfRange {
+                index: 3.0,
+                length: 3,
+            },
+        );
+        test_eval_err(
+            "(list-set! (list 1 2 3) -1 'a)",
+            RuntimeErrorType::IndexOutOfRange {
+                index: -1.0,
+                length: 3,
+            },
+        );
+        test_eval_err(
+            "(list-set! '() 0 'a)",
+            RuntimeErrorType::IndexOutOfRange {
+                index: 0.0,
+                length: 0,
+            },
+        );
+    }
+
+    #[test]
+    fn take_and_drop_work() {
+        test_eval_success("(take '(1 2 3) 2)", "(1 2)");
+        test_eval_success("(take '(1 2 3) 3)", "(1 2 3)");
+        test_eval_success("(take '(1 2 3) 0)", "()");
+        test_eval_success("(define x '(1 2 3)) (eq? (take x 3) x)", "#f");
+        test_eval_success("(drop '(1 2 3) 2)", "(3)");
+        test_eval_success("(drop '(1 2 3) 3)", "()");
+        test_eval_success("(drop '(1 2 3) 0)", "(1 2 3)");
+        test_eval_success("(define x '(1 2 3)) (eq? (drop x 1) (cdr x))", "#t");
+        test_eval_success("(define x '(1 2 3)) (eq? (drop x 0) x)", "#t");
+    }
+
+    #[test]
+    fn take_and_drop_error_on_short_lists() {
+        test_eval_err(
+            "(take '(1 2 3) 4)",
+            RuntimeErrorType::IndexOutOfRange {
+                index: 4.0,
+                length: 3,
+            },
+        );
+        test_eval_err(
+            "(drop '(1 2 3) 4)",
+            RuntimeErrorType::IndexOutOfRange {
+                index: 4.0,
+                length: 3,
+            },
+        );
+        test_eval_err("(take '(1 2 . 3) 1)", RuntimeErrorType::ExpectedList);
+    }
+
+    #[test]
+    fn map_works() {
+        test_eval_success("(map (lambda (x) (* x x)) '())", "()");
+        test_eval_success("(map (lambda (x) (* x x)) '(1 2 3))", "(1 4 9)");
+        test_eval_success("(map + '(1 2 3) '(10 20 30))", "(11 22 33)");
+        test_eval_success("(map + '(1 2 3) '(10 20))", "(11 22)");
+        test_eval_err("(map +)", RuntimeErrorType::WrongNumberOfArguments);
+        test_eval_err("(map + '(1 . 2))", RuntimeErrorType::ExpectedList);
+    }
+
+    #[test]
+    fn for_each_works() {
+        test_eval_success("(for-each display '(1 2 3))", "123");
+        test_eval_success(
+            "(for-each (lambda (a b) (display (+ a b))) '(1 2) '(3 4))",
+            "46",
+        );
+    }
+
+    #[test]
+    fn map_and_for_each_handle_edge_cases() {
+        // The procedure shouldn't be called at all when there's nothing to
+        // map over, so its errors shouldn't surface.
+        test_eval_success("(map (lambda (x) (car x)) '())", "()");
+        test_eval_success("(map + '(1 2) '())", "()");
+        test_eval_success("(for-each (lambda (x) (car x)) '())", "");
+        test_eval_err("(map car)", RuntimeErrorType::WrongNumberOfArguments);
+        test_eval_err("(for-each car)", RuntimeErrorType::WrongNumberOfArguments);
+    }
+
+    #[test]
+    fn append_map_works() {
+        test_eval_success("(append-map list '(1 2) '(3 4))", "(1 3 2 4)");
+        test_eval_success("(append-map (lambda (x) (list x x)) '(1 2))", "(1 1 2 2)");
+        test_eval_success("(append-map list '())", "()");
+        test_eval_err(
+            "(append-map (lambda (x) x) '(1))",
+            RuntimeErrorType::ExpectedList,
+        );
+    }
+
+    #[test]
+    fn count_works() {
+        test_eval_success("(count even? '(1 2 3 4 6))", "3");
+        test_eval_success("(count even? '(1 3))", "0");
+        test_eval_success("(count even? '())", "0");
+        test_eval_success("(count < '(1 5 2) '(2 4 3 0))", "2");
+        test_eval_success("(count < '(1 2) '())", "0");
+        test_eval_err("(count even?)", RuntimeErrorType::WrongNumberOfArguments);
+        test_eval_err("(count even? '(1 . 2))", RuntimeErrorType::ExpectedList);
+        test_eval_err("(count 1 '(1))", RuntimeErrorType::ExpectedProcedure);
+    }
+
+    #[test]
+    fn zip_works() {
+        test_eval_success("(zip '(1 2) '(a b))", "((1 a) (2 b))");
+        test_eval_success("(zip '(1 2 3))", "((1) (2) (3))");
+        test_eval_success("(zip '(1 2 3) '(a b) '(x y z))", "((1 a x) (2 b y))");
+        test_eval_success("(zip '(1 2) '())", "()");
+        test_eval_err("(zip)", RuntimeErrorType::WrongNumberOfArguments);
+        test_eval_err("(zip '(1 . 2))", RuntimeErrorType::ExpectedList);
+    }
+
+    #[test]
+    fn unzip_works() {
+        test_eval_success("(unzip1 '((1 a) (2 b)))", "(1 2)");
+        test_eval_success("(unzip1 '())", "()");
+        test_eval_success("(unzip2 '((1 a) (2 b x)))", "(1 2) (a b)");
+        test_eval_success("(unzip2 (zip '(1 2) '(a b)))", "(1 2) (a b)");
+        test_eval_success("(receive (a b) (unzip2 '()) (list a b))", "(() ())");
+        test_eval_err("(unzip1 '(()))", RuntimeErrorType::ExpectedPair);
+        test_eval_err("(unzip2 '((1 a) (2)))", RuntimeErrorType::ExpectedPair);
+        test_eval_err("(unzip1 '((1) . 2))", RuntimeErrorType::ExpectedList);
+    }
+
+    #[test]
+    fn flatten_works() {
+        test_eval_success("(flatten '(1 (2 (3)) 4))", "(1 2 3 4)");
+        test_eval_success("(flatten '(() (()) 1))", "(1)");
+        test_eval_success("(flatten '())", "()");
+        test_eval_err("(flatten 1)", RuntimeErrorType::ExpectedList);
+        test_eval_err("(flatten '(1 (2 . 3)))", RuntimeErrorType::ExpectedList);
+    }
+
+    #[test]
+    fn flatten_works_on_deeply_nested_lists() {
+        let interpreter = test_eval_successes(&[
+            (
+                "(define (nest n x) (if (= n 0) x (nest (- n 1) (list x))))",
+                "",
+            ),
+            ("(define x (nest 10000 '(1 2)))", ""),
+            ("(flatten x)", "(1 2)"),
+        ]);
+        // Dropping lists this deeply nested would itself overflow the stack.
+        std::mem::forget(interpreter);
+    }
+
+    #[test]
+    fn flatten_rejects_cyclic_lists() {
+        test_eval_err(
+            "(define x (list 1 2)) (set-cdr! (cdr x) x) (flatten x)",
+            RuntimeErrorType::ExpectedList,
+        );
+        test_eval_err(
+            "(define x (list 1 (list 2))) (set-car! (car (cdr x)) x) (flatten x)",
+            RuntimeErrorType::ExpectedList,
+        );
+    }
+
+    #[test]
+    fn list_builtins_accept_shared_sublists() {
+        test_eval_successes(&[
+            ("(define x (list 1))", ""),
+            ("(define y (list x x))", ""),
+            ("y", "((1) (1))"),
+            ("(flatten y)", "(1 1)"),
+            ("(find pair? y)", "(1)"),
+            ("(append-map (lambda (l) l) y)", "(1 1)"),
+            ("(delete-duplicates y)", "((1))"),
+            ("(list-sort (lambda (a b) #f) y)", "((1) (1))"),
+        ]);
+    }
+
+    #[test]
+    fn list_builtins_visit_shared_sublists_once() {
+        let start = std::time::Instant::now();
+        test_eval_successes(&[
+            ("(define x (list 1))", ""),
+            (
+                "(define (wrap n) (if (> n 0) (begin (set! x (list x x)) (wrap (- n 1)))))",
+                "",
+            ),
+            ("(wrap 30)", ""),
+            ("(eq? (list-ref x 1) (car x))", "#t"),
+            ("(list-tail x 2)", "()"),
+        ]);
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn every_works() {
+        test_eval_success("(every even? '(2 4 6))", "#t");
+        test_eval_success("(every even? '(2 3 6))", "#f");
+        test_eval_success("(every even? '())", "#t");
+        test_eval_success("(every (lambda (x) (and (even? x) x)) '(2 4 6))", "6");
+        test_eval_success("(every < '(1 2 3) '(2 3))", "#t");
+        test_eval_success("(every < '(1 2 3) '(2 1 4))", "#f");
+        test_eval_err("(every even? '(1 . 2))", RuntimeErrorType::ExpectedList);
+    }
+
+    #[test]
+    fn every_short_circuits() {
+        test_eval_success(
+            "(every (lambda (x) (display x) (< x 3)) '(1 2 3 4 5))",
+            "123#f",
+        );
+    }
+
+    #[test]
+    fn any_works() {
+        test_eval_success("(any even? '(1 2 3))", "#t");
+        test_eval_success("(any even? '(1 3 5))", "#f");
+        test_eval_success("(any even? '())", "#f");
+        test_eval_success("(any (lambda (x) (and (even? x) x)) '(1 4 6))", "4");
+        test_eval_success("(any > '(1 2 3) '(2 1))", "#t");
+        test_eval_success("(any > '(1 2 3) '(2 3))", "#f");
+    }
+
+    #[test]
+    fn any_short_circuits() {
+        test_eval_success(
+            "(any (lambda (x) (display x) (and (> x 2) (* x 10))) '(1 2 3 4 5))",
+            "12330",
+        );
+    }
+
+    #[test]
+    fn map_and_for_each_can_be_interrupted() {
+        let mut interpreter = Interpreter::new();
+        let source_id = interpreter
+            .source_mapper
+            .add("<code>".into(), "(define lst '(1 2 3))".into());
+        interpreter.evaluate(source_id).unwrap();
+        let display = interpreter
+            .environment
+            .get(&interpreter.string_interner.intern("display"))
+            .unwrap();
+        let lst = interpreter
+            .environment
+            .get(&interpreter.string_interner.intern("lst"))
+            .unwrap();
+        let (tx, rx) = channel();
+        interpreter.keyboard_interrupt_channel = Some(rx);
+        interpreter.printer.disable_autoflush = true;
+
+        for func in [super::map, super::for_each] {
+            tx.send(()).unwrap();
+            let ctx = BuiltinProcedureContext {
+                interpreter: &mut interpreter,
+                range: (0, 0, None),
+            };
+            let Err(err) = func(ctx, &display, std::slice::from_ref(&lst)) else {
+                panic!("expected keyboard interrupt");
+            };
+            assert_eq!(err.0, RuntimeErrorType::KeyboardInterrupt);
+            // The interrupt should have been noticed before anything was displayed.
+            assert_eq!(interpreter.printer.take_buffered_output(), "");
+        }
+    }
+
+    #[test]
+    fn huge_iotas_can_be_interrupted() {
+        let mut interpreter = Interpreter::new();
+        let (tx, rx) = channel();
+        interpreter.keyboard_interrupt_channel = Some(rx);
+        tx.send(()).unwrap();
+        let ctx = BuiltinProcedureContext {
+            interpreter: &mut interpreter,
+            range: (0, 0, None),
+        };
+        let count = Value::Number(1e18).into();
+        let Err(err) = super::iota(ctx, &count, &[]) else {
+            panic!("expected keyboard interrupt");
+        };
+        assert_eq!(err.0, RuntimeErrorType::KeyboardInterrupt);
+    }
 }
@@ -0,0 +1,310 @@
+use std::fs::read_to_string;
+
+use crate::{
+    builtin_procedure::{BuiltinProcedureContext, BuiltinProcedureFn},
+    builtins::Builtin,
+    callable::CallableResult,
+    interpreter::RuntimeErrorType,
+    mutable_string::MutableString,
+    port::Port,
+    source_mapped::SourceMappable,
+    value::{SourceValue, Value},
+};
+
+pub fn get_builtins() -> super::Builtins {
+    vec![
+        Builtin::Procedure("port?", BuiltinProcedureFn::Unary(port)),
+        Builtin::Procedure("input-port?", BuiltinProcedureFn::Unary(input_port)),
+        Builtin::Procedure("output-port?", BuiltinProcedureFn::Unary(output_port)),
+        Builtin::Procedure("eof-object", BuiltinProcedureFn::Nullary(eof_object)),
+        Builtin::Procedure("eof-object?", BuiltinProcedureFn::Unary(is_eof_object)),
+        Builtin::Procedure(
+            "open-input-string",
+            BuiltinProcedureFn::Unary(open_input_string),
+        ),
+        Builtin::Procedure("read-char", BuiltinProcedureFn::Unary(read_char)),
+        Builtin::Procedure("peek-char", BuiltinProcedureFn::Unary(peek_char)),
+        Builtin::Procedure("read-string", BuiltinProcedureFn::Binary(read_string)),
+        Builtin::Procedure("read-all", BuiltinProcedureFn::Unary(read_all)),
+        Builtin::Procedure("parse-file", BuiltinProcedureFn::Unary(parse_file)),
+        Builtin::Procedure(
+            "open-output-string",
+            BuiltinProcedureFn::Nullary(open_output_string),
+        ),
+        Builtin::Procedure(
+            "get-output-string",
+            BuiltinProcedureFn::Unary(get_output_string),
+        ),
+        Builtin::Procedure("write-char", BuiltinProcedureFn::UnaryVariadic(write_char)),
+        Builtin::Procedure(
+            "write-string",
+            BuiltinProcedureFn::UnaryVariadic(write_string),
+        ),
+    ]
+}
+
+fn char_or_eof(char: Option<char>) -> CallableResult {
+    Ok(match char {
+        Some(char) => Value::Character(char).into(),
+        None => Value::EofObject.into(),
+    })
+}
+
+fn port(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(matches!(value.0, Value::Port(_)).into())
+}
+
+fn input_port(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(value.expect_input_port().is_ok().into())
+}
+
+fn output_port(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(value.expect_output_port().is_ok().into())
+}
+
+fn eof_object(_ctx: BuiltinProcedureContext) -> CallableResult {
+    Ok(Value::EofObject.into())
+}
+
+fn is_eof_object(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(matches!(value.0, Value::EofObject).into())
+}
+
+fn open_input_string(ctx: BuiltinProcedureContext, string: &SourceValue) -> CallableResult {
+    let string = string.expect_string()?.to_string();
+    Ok(Value::Port(Port::string_input(&string))
+        .source_mapped(ctx.range)
+        .into())
+}
+
+fn read_char(_ctx: BuiltinProcedureContext, port: &SourceValue) -> CallableResult {
+    char_or_eof(port.expect_input_port()?.read_char())
+}
+
+fn peek_char(_ctx: BuiltinProcedureContext, port: &SourceValue) -> CallableResult {
+    char_or_eof(port.expect_input_port()?.peek_char())
+}
+
+fn read_string(
+    ctx: BuiltinProcedureContext,
+    k: &SourceValue,
+    port: &SourceValue,
+) -> CallableResult {
+    let k = k.expect_non_negative_integer()?;
+    Ok(match port.expect_input_port()?.read_string(k) {
+        Some(string) => Value::String(MutableString::new(string))
+            .source_mapped(ctx.range)
+            .into(),
+        None => Value::EofObject.into(),
+    })
+}
+
+/// Parses the given source code into a list of its top-level datums, without
+/// evaluating them. The code is added to the source mapper under the given
+/// filename, so that the datums (and any parse error) have source locations.
+fn parse_into_list(ctx: BuiltinProcedureContext, filename: String, code: String) -> CallableResult {
+    let source_id = ctx.interpreter.source_mapper.add(filename, code);
+    let datums = ctx.interpreter.parse(source_id)?;
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(datums)
+        .source_mapped(ctx.range)
+        .into())
+}
+
+/// Like calling `read` until the port is exhausted.
+fn read_all(ctx: BuiltinProcedureContext, port: &SourceValue) -> CallableResult {
+    let code = port.expect_input_port()?.read_remaining();
+    parse_into_list(ctx, "<port>".to_string(), code)
+}
+
+fn parse_file(ctx: BuiltinProcedureContext, filename: &SourceValue) -> CallableResult {
+    let filename = filename.expect_string()?.to_string();
+    let Ok(code) = read_to_string(&filename) else {
+        return Err(RuntimeErrorType::FileReadFailed(filename).source_mapped(ctx.range));
+    };
+    parse_into_list(ctx, filename, code)
+}
+
+fn open_output_string(ctx: BuiltinProcedureContext) -> CallableResult {
+    Ok(Value::Port(Port::string_output())
+        .source_mapped(ctx.range)
+        .into())
+}
+
+fn get_output_string(ctx: BuiltinProcedureContext, port: &SourceValue) -> CallableResult {
+    let string = port.expect_output_port()?.output_string();
+    Ok(Value::String(MutableString::new(string))
+        .source_mapped(ctx.range)
+        .into())
+}
+
+/// Writes the given string to the port in `rest`, if one is provided, or
+/// to standard output otherwise.
+fn write_to_optional_port(
+    ctx: BuiltinProcedureContext,
+    string: &str,
+    rest: &[SourceValue],
+) -> CallableResult {
+    match rest {
+        [] => ctx.interpreter.printer.print(string),
+        [port] => port.expect_output_port()?.write_string(string),
+        _ => return Err(RuntimeErrorType::WrongNumberOfArguments.source_mapped(ctx.range)),
+    }
+    ctx.undefined()
+}
+
+fn write_char(
+    ctx: BuiltinProcedureContext,
+    char: &SourceValue,
+    rest: &[SourceValue],
+) -> CallableResult {
+    let char = char.expect_character()?;
+    write_to_optional_port(ctx, &char.to_string(), rest)
+}
+
+fn write_string(
+    ctx: BuiltinProcedureContext,
+    string: &SourceValue,
+    rest: &[SourceValue],
+) -> CallableResult {
+    let string = string.expect_string()?.to_string();
+    write_to_optional_port(ctx, &string, rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        interpreter::{Interpreter, RuntimeErrorType},
+        parser::ParseErrorType,
+        test_util::{test_eval_err, test_eval_success, test_eval_successes},
+    };
+
+    #[test]
+    fn port_predicates_work() {
+        test_eval_success(r#"(port? (open-input-string "hi"))"#, "#t");
+        test_eval_success(r#"(input-port? (open-input-string "hi"))"#, "#t");
+        test_eval_success(r#"(port? "hi")"#, "#f");
+        test_eval_success(r#"(input-port? "hi")"#, "#f");
+        test_eval_success("(port? (open-output-string))", "#t");
+        test_eval_success("(output-port? (open-output-string))", "#t");
+        test_eval_success("(input-port? (open-output-string))", "#f");
+        test_eval_success(r#"(output-port? (open-input-string "hi"))"#, "#f");
+        test_eval_success("(open-output-string)", "#<string-output-port>");
+    }
+
+    #[test]
+    fn eof_objects_work() {
+        test_eval_success("(eof-object)", "#<eof>");
+        test_eval_success("(eof-object? (eof-object))", "#t");
+        test_eval_success("(eq? (eof-object) (eof-object))", "#t");
+        test_eval_success("(eof-object? '())", "#f");
+    }
+
+    #[test]
+    fn read_char_and_peek_char_work() {
+        test_eval_successes(&[
+            (r#"(define p (open-input-string "ab"))"#, ""),
+            ("(peek-char p)", r"#\a"),
+            ("(read-char p)", r"#\a"),
+            ("(peek-char p)", r"#\b"),
+            ("(read-char p)", r"#\b"),
+            ("(peek-char p)", "#<eof>"),
+            ("(read-char p)", "#<eof>"),
+            ("(eof-object? (read-char p))", "#t"),
+        ]);
+    }
+
+    #[test]
+    fn read_string_works() {
+        test_eval_successes(&[
+            (r#"(define p (open-input-string "hello"))"#, ""),
+            ("(read-string 0 p)", r#""""#),
+            ("(read-string 2 p)", r#""he""#),
+            ("(read-string 10 p)", r#""llo""#),
+            ("(read-string 0 p)", r#""""#),
+            ("(read-string 1 p)", "#<eof>"),
+        ]);
+    }
+
+    #[test]
+    fn read_all_works() {
+        test_eval_success(
+            r#"(read-all (open-input-string "(define x 1) foo \"bar\" (a . b)"))"#,
+            r#"((define x 1) foo "bar" (a . b))"#,
+        );
+        test_eval_success(
+            r#"(vector-length (list->vector (read-all (open-input-string "1 2 3"))))"#,
+            "3",
+        );
+        test_eval_success(r#"(read-all (open-input-string ""))"#, "()");
+        test_eval_successes(&[
+            (r#"(define p (open-input-string "1 2"))"#, ""),
+            ("(read-char p)", r"#\1"),
+            ("(read-all p)", "(2)"),
+            ("(read-all p)", "()"),
+        ]);
+        test_eval_err(
+            r#"(read-all (open-input-string "(1 2"))"#,
+            RuntimeErrorType::Parse(ParseErrorType::MissingRightParen),
+        );
+        test_eval_err("(read-all 1)", RuntimeErrorType::ExpectedInputPort);
+    }
+
+    #[test]
+    fn parse_file_works() {
+        let path = std::env::temp_dir().join(format!("ascheme-parse-{}.scm", std::process::id()));
+        std::fs::write(&path, "(display 1)\n; hi\n(+ 1 2) boop").unwrap();
+        let mut interpreter = Interpreter::new();
+        let code = format!("(parse-file {:?})", path.to_str().unwrap());
+        let source_id = interpreter.source_mapper.add("<code>".into(), code);
+        let value = interpreter.evaluate(source_id).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(value.to_string(), "((display 1) (+ 1 2) boop)");
+        test_eval_err(
+            r#"(parse-file "/nonexistent/file.scm")"#,
+            RuntimeErrorType::FileReadFailed("/nonexistent/file.scm".to_string()),
+        );
+    }
+
+    #[test]
+    fn write_char_and_write_string_work() {
+        test_eval_successes(&[
+            ("(define p (open-output-string))", ""),
+            ("(get-output-string p)", r#""""#),
+            (r"(write-char #\a p)", ""),
+            (r#"(write-string "b \"c\"" p)"#, ""),
+            ("(get-output-string p)", r#""ab \"c\"""#),
+            (r"(write-char #\d p)", ""),
+            ("(get-output-string p)", r#""ab \"c\"d""#),
+        ]);
+        test_eval_success(r#"(write-string "hi") (write-char #\!)"#, "hi!");
+    }
+
+    #[test]
+    fn port_builtins_raise_errors() {
+        test_eval_err("(open-input-string 1)", RuntimeErrorType::ExpectedString);
+        test_eval_err(r#"(read-char "hi")"#, RuntimeErrorType::ExpectedInputPort);
+        test_eval_err(
+            "(read-char (open-output-string))",
+            RuntimeErrorType::ExpectedInputPort,
+        );
+        test_eval_err(
+            r#"(write-char #\a (open-input-string "hi"))"#,
+            RuntimeErrorType::ExpectedOutputPort,
+        );
+        test_eval_err(
+            r#"(get-output-string (open-input-string "hi"))"#,
+            RuntimeErrorType::ExpectedOutputPort,
+        );
+        test_eval_err(
+            r#"(write-string #\a (open-output-string))"#,
+            RuntimeErrorType::ExpectedString,
+        );
+        test_eval_err(
+            r#"(read-string -1 (open-input-string "hi"))"#,
+            RuntimeErrorType::ExpectedNonNegativeInteger,
+        );
+    }
+}
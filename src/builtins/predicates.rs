@@ -0,0 +1,67 @@
+use crate::{
+    builtin_procedure::{BuiltinProcedureContext, BuiltinProcedureFn},
+    builtins::Builtin,
+    callable::CallableResult,
+    value::SourceValue,
+};
+
+pub fn get_builtins() -> super::Builtins {
+    vec![
+        Builtin::Procedure("integer?", BuiltinProcedureFn::Unary(integer)),
+        Builtin::Procedure(
+            "exact-nonnegative-integer?",
+            BuiltinProcedureFn::Unary(exact_nonnegative_integer),
+        ),
+    ]
+}
+
+/// Note that since all numbers are currently floats, this considers any finite,
+/// whole-valued number to be an integer.
+fn integer(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(value.expect_integer().is_ok().into())
+}
+
+/// Returns whether the value can be used as an index or count.
+fn exact_nonnegative_integer(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(value.expect_non_negative_integer().is_ok().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::{test_eval_success, test_eval_successes};
+
+    const INFINITY: &str = "
+        (define infinity
+          (let loop ((n 400) (result 1))
+            (if (= n 0) result (loop (- n 1) (* result 10)))))
+    ";
+
+    #[test]
+    fn integer_works() {
+        test_eval_success("(integer? 2)", "#t");
+        test_eval_success("(integer? 2.0)", "#t");
+        test_eval_success("(integer? -2.0)", "#t");
+        test_eval_success("(integer? 2.5)", "#f");
+        test_eval_success("(integer? \"2\")", "#f");
+        test_eval_success("(integer? '2)", "#t");
+    }
+
+    #[test]
+    fn exact_nonnegative_integer_works() {
+        test_eval_success("(exact-nonnegative-integer? 0)", "#t");
+        test_eval_success("(exact-nonnegative-integer? 5)", "#t");
+        test_eval_success("(exact-nonnegative-integer? -1)", "#f");
+        test_eval_success("(exact-nonnegative-integer? 1.5)", "#f");
+        test_eval_success("(exact-nonnegative-integer? 'a)", "#f");
+    }
+
+    #[test]
+    fn integer_is_false_for_infinities_and_nan() {
+        test_eval_successes(&[
+            (INFINITY, ""),
+            ("(integer? infinity)", "#f"),
+            ("(integer? (- infinity))", "#f"),
+            ("(integer? (sqrt -1))", "#f"),
+        ]);
+    }
+}
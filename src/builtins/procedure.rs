@@ -0,0 +1,172 @@
+use std::rc::Rc;
+
+use crate::{
+    builtin_procedure::{BuiltinProcedure, BuiltinProcedureContext, BuiltinProcedureFn},
+    builtins::Builtin,
+    callable::{Callable, CallableResult},
+    derived_procedure::{DerivedProcedure, DerivedProcedureKind},
+    procedure::Procedure,
+    source_mapped::SourceMappable,
+    value::{SourceValue, Value},
+};
+
+pub fn get_builtins() -> super::Builtins {
+    vec![
+        Builtin::Procedure("identity", BuiltinProcedureFn::Unary(identity)),
+        Builtin::Procedure("compose", BuiltinProcedureFn::NullaryVariadic(compose)),
+        Builtin::Procedure("partial", BuiltinProcedureFn::UnaryVariadic(partial)),
+    ]
+}
+
+fn derived_procedure(
+    ctx: BuiltinProcedureContext,
+    name: &'static str,
+    kind: DerivedProcedureKind,
+) -> CallableResult {
+    let name = ctx.interpreter.string_interner.intern(name);
+    let derived = DerivedProcedure::new(name, ctx.interpreter.new_id(), kind);
+    Ok(
+        Value::Callable(Callable::Procedure(Procedure::Derived(derived)))
+            .source_mapped(ctx.range)
+            .into(),
+    )
+}
+
+fn identity(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(value.clone().into())
+}
+
+fn compose(ctx: BuiltinProcedureContext, operands: &[SourceValue]) -> CallableResult {
+    if operands.is_empty() {
+        let identity = BuiltinProcedure {
+            func: BuiltinProcedureFn::Unary(identity),
+            name: ctx.interpreter.string_interner.intern("identity"),
+        };
+        return Ok(
+            Value::Callable(Callable::Procedure(Procedure::Builtin(identity)))
+                .source_mapped(ctx.range)
+                .into(),
+        );
+    }
+    let mut procedures = Vec::with_capacity(operands.len());
+    for operand in operands {
+        procedures.push(operand.expect_procedure()?);
+    }
+    derived_procedure(
+        ctx,
+        "compose",
+        DerivedProcedureKind::Composition(Rc::new(procedures)),
+    )
+}
+
+fn partial(
+    ctx: BuiltinProcedureContext,
+    procedure: &SourceValue,
+    args: &[SourceValue],
+) -> CallableResult {
+    let procedure = procedure.expect_procedure()?;
+    derived_procedure(
+        ctx,
+        "partial",
+        DerivedProcedureKind::PartialApplication(Rc::new(procedure), Rc::new(args.to_vec())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        interpreter::RuntimeErrorType,
+        test_util::{test_eval_err, test_eval_success, test_eval_successes},
+    };
+
+    #[test]
+    fn identity_works() {
+        test_eval_success("(identity 5)", "5");
+        test_eval_success("(identity '(1 2))", "(1 2)");
+    }
+
+    #[test]
+    fn compose_works() {
+        test_eval_success("((compose car cdr) '(1 2 3))", "2");
+        test_eval_success("((compose) 5)", "5");
+        test_eval_success("(eq? (compose) identity)", "#t");
+        test_eval_success("((compose car) '(1 2 3))", "1");
+        test_eval_success("((compose sqrt *) 12 75)", "30");
+        test_eval_success(
+            "((compose (lambda (x) (* x 2)) (lambda (x) (+ x 1)) +) 1 2)",
+            "8",
+        );
+        test_eval_err("(compose car 1)", RuntimeErrorType::ExpectedProcedure);
+        test_eval_err("((compose) 1 2)", RuntimeErrorType::WrongNumberOfArguments);
+        test_eval_err(
+            "((compose car cdr) 1 2)",
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+    }
+
+    #[test]
+    fn compose_is_properly_tail_recursive() {
+        test_eval_success(
+            "
+            (define (count-down n)
+              (if (= n 0)
+                'done
+                ((compose count-down (lambda (n) (- n 1))) n)))
+            (count-down 10000)
+            ",
+            "done",
+        );
+    }
+
+    #[test]
+    fn partial_works() {
+        test_eval_success("((partial + 10) 5)", "15");
+        test_eval_success("((partial list 1 2) 3 4)", "(1 2 3 4)");
+        test_eval_success("((partial list))", "()");
+        test_eval_success("((partial cons 1 2))", "(1 . 2)");
+        test_eval_success("((compose (partial * 2) (partial + 1)) 3)", "8");
+        test_eval_err("(partial 1 2)", RuntimeErrorType::ExpectedProcedure);
+        test_eval_err(
+            "((partial cons 1))",
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+        test_eval_err(
+            "((partial car 1) 2)",
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+    }
+
+    #[test]
+    fn partial_is_properly_tail_recursive() {
+        test_eval_success(
+            "
+            (define (count-down n)
+              (if (= n 0)
+                'done
+                ((partial count-down (- n 1)))))
+            (count-down 10000)
+            ",
+            "done",
+        );
+    }
+
+    #[test]
+    fn composed_procedures_work_with_garbage_collection() {
+        test_eval_successes(&[
+            (
+                "
+                (define (make-adder n)
+                  (define f (compose (lambda (x) (if (eq? x 'self) f (+ x n)))))
+                  f)
+                (define add-5 (make-adder 5))
+                (define add-1 (make-adder 1))
+                (define add-1 0)
+                ",
+                "",
+            ),
+            // Only the scope of the discarded procedure should be collected.
+            ("(gc)", "1"),
+            ("(add-5 1)", "6"),
+        ]);
+    }
+}
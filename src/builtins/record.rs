@@ -0,0 +1,275 @@
+use std::rc::Rc;
+
+use crate::{
+    builtins::Builtin,
+    callable::{Callable, CallableResult},
+    interpreter::{RuntimeError, RuntimeErrorType},
+    procedure::Procedure,
+    record::{RecordProcedure, RecordProcedureKind, RecordType},
+    source_mapped::{SourceMappable, SourceMapped},
+    special_form::SpecialFormContext,
+    string_interner::InternedString,
+    value::{SourceValue, Value},
+};
+
+pub fn get_builtins() -> super::Builtins {
+    vec![Builtin::SpecialForm(
+        "define-record-type",
+        define_record_type,
+    )]
+}
+
+fn expect_list(value: &SourceValue) -> Result<Rc<Vec<SourceValue>>, RuntimeError> {
+    match value.try_into_list() {
+        Some(list) if !list.0.is_empty() => Ok(list.0),
+        _ => Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(value.1)),
+    }
+}
+
+struct FieldSpec {
+    name: SourceMapped<InternedString>,
+    accessor: SourceMapped<InternedString>,
+    modifier: Option<SourceMapped<InternedString>>,
+}
+
+impl FieldSpec {
+    fn parse(value: &SourceValue) -> Result<Self, RuntimeError> {
+        let list = expect_list(value)?;
+        if list.len() > 3 || list.len() < 2 {
+            return Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(value.1));
+        }
+        let identifier =
+            |value: &SourceValue| -> Result<SourceMapped<InternedString>, RuntimeError> {
+                Ok(value.expect_identifier()?.source_mapped(value.1))
+            };
+        Ok(FieldSpec {
+            name: identifier(&list[0])?,
+            accessor: identifier(&list[1])?,
+            modifier: list.get(2).map(identifier).transpose()?,
+        })
+    }
+}
+
+/// Implements the `define-record-type` form from R7RS section 5.5 (also known
+/// as SRFI 9), e.g.:
+///
+/// ```scheme
+/// (define-record-type <point>
+///   (make-point x y)
+///   point?
+///   (x point-x set-point-x!)
+///   (y point-y))
+/// ```
+///
+/// Note that we don't currently bind the name of the record type itself to
+/// anything.
+fn define_record_type(ctx: SpecialFormContext) -> CallableResult {
+    if ctx.operands.len() < 3 {
+        return Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(ctx.range));
+    }
+    let type_name = ctx.operands[0].expect_identifier()?;
+    let constructor = expect_list(&ctx.operands[1])?;
+    let constructor_name = constructor[0].expect_identifier()?;
+    let predicate_name = ctx.operands[2].expect_identifier()?;
+    let field_specs = ctx.operands[3..]
+        .iter()
+        .map(FieldSpec::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut fields: Vec<InternedString> = Vec::with_capacity(field_specs.len());
+    for spec in field_specs.iter() {
+        if fields.contains(&spec.name.0) {
+            return Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(spec.name.1));
+        }
+        fields.push(spec.name.0.clone());
+    }
+
+    let mut constructor_indices = Vec::with_capacity(constructor.len() - 1);
+    for field in constructor[1..].iter() {
+        let name = field.expect_identifier()?;
+        let Some(index) = fields.iter().position(|field| field == &name) else {
+            return Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(field.1));
+        };
+        constructor_indices.push(index);
+    }
+
+    let record_type = Rc::new(RecordType {
+        name: type_name,
+        fields,
+    });
+    let mut define = |name: SourceMapped<InternedString>, kind: RecordProcedureKind| {
+        let procedure = RecordProcedure {
+            name: name.0.clone(),
+            record_type: record_type.clone(),
+            kind,
+        };
+        ctx.interpreter.environment.define(
+            name.0,
+            Value::Callable(Callable::Procedure(Procedure::Record(procedure)))
+                .source_mapped(name.1),
+        );
+    };
+
+    define(
+        constructor_name.source_mapped(constructor[0].1),
+        RecordProcedureKind::Constructor(Rc::new(constructor_indices)),
+    );
+    define(
+        predicate_name.source_mapped(ctx.operands[2].1),
+        RecordProcedureKind::Predicate,
+    );
+    for (index, spec) in field_specs.into_iter().enumerate() {
+        define(spec.accessor, RecordProcedureKind::Accessor(index));
+        if let Some(modifier) = spec.modifier {
+            define(modifier, RecordProcedureKind::Modifier(index));
+        }
+    }
+
+    ctx.undefined()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        interpreter::RuntimeErrorType,
+        test_util::{test_eval_err, test_eval_success, test_eval_successes},
+    };
+
+    const POINT: &str = "
+        (define-record-type <point>
+          (make-point x y)
+          point?
+          (x point-x set-point-x!)
+          (y point-y))
+    ";
+
+    #[test]
+    fn constructor_and_accessors_work() {
+        test_eval_successes(&[
+            (POINT, ""),
+            ("(define p (make-point 1 2))", ""),
+            ("p", "#<record point>"),
+            ("(point-x p)", "1"),
+            ("(point-y p)", "2"),
+            ("make-point", "#<procedure make-point>"),
+        ]);
+    }
+
+    #[test]
+    fn predicate_works() {
+        test_eval_successes(&[
+            (POINT, ""),
+            ("(point? (make-point 1 2))", "#t"),
+            ("(point? '(1 2))", "#f"),
+            ("(point? 5)", "#f"),
+            (
+                "(define-record-type <other> (make-other x) other? (x other-x))",
+                "",
+            ),
+            ("(point? (make-other 1))", "#f"),
+        ]);
+    }
+
+    #[test]
+    fn modifiers_work() {
+        test_eval_successes(&[
+            (POINT, ""),
+            ("(define p (make-point 1 2))", ""),
+            ("(set-point-x! p 5)", ""),
+            ("(point-x p)", "5"),
+            ("(point-y p)", "2"),
+        ]);
+    }
+
+    #[test]
+    fn fields_not_in_constructor_are_undefined() {
+        test_eval_success(
+            "
+            (define-record-type thing (make-thing b) thing? (a thing-a) (b thing-b))
+            (list (thing-a (make-thing 1)) (thing-b (make-thing 1)))
+            ",
+            "(#!void 1)",
+        );
+    }
+
+    #[test]
+    fn records_work_with_eq() {
+        test_eval_successes(&[
+            (POINT, ""),
+            ("(define p (make-point 1 2))", ""),
+            ("(eq? p p)", "#t"),
+            ("(eq? p (make-point 1 2))", "#f"),
+            ("(eq? point-x point-x)", "#t"),
+            ("(eq? point-x point-y)", "#f"),
+        ]);
+    }
+
+    #[test]
+    fn accessors_work_when_called_many_times() {
+        test_eval_successes(&[
+            (POINT, ""),
+            ("(define p (make-point 1 2))", ""),
+            (
+                "
+                (define (sum-fields n acc)
+                  (if (= n 0)
+                      acc
+                      (sum-fields (- n 1) (+ acc (point-x p) (point-y p)))))
+                ",
+                "",
+            ),
+            ("(sum-fields 10000 0)", "30000"),
+            ("(set-point-x! p 5)", ""),
+            ("(sum-fields 10000 0)", "70000"),
+        ]);
+    }
+
+    #[test]
+    fn records_are_garbage_collected() {
+        test_eval_success(
+            "
+            (define-record-type node (make-node next) node? (next node-next set-node-next!))
+            (define n (make-node 0))
+            (set-node-next! n n)
+            (define n 0)
+            (gc)
+            ",
+            "1",
+        );
+    }
+
+    #[test]
+    fn accessors_raise_errors_on_other_types() {
+        let mut interpreter = test_eval_success(POINT, "");
+        let point = interpreter.intern("<point>");
+        interpreter.eval_err(
+            "(point-x 5)",
+            RuntimeErrorType::ExpectedRecord(point.clone()),
+        );
+        interpreter.eval_err(
+            "(set-point-x! '(1 2) 5)",
+            RuntimeErrorType::ExpectedRecord(point.clone()),
+        );
+        interpreter.eval_err("(make-point 1)", RuntimeErrorType::WrongNumberOfArguments);
+    }
+
+    #[test]
+    fn malformed_definitions_raise_errors() {
+        test_eval_err(
+            "(define-record-type point (make-point z) point? (x point-x))",
+            RuntimeErrorType::MalformedSpecialForm,
+        );
+        test_eval_err(
+            "(define-record-type point (make-point) point? (x point-x) (x point-x2))",
+            RuntimeErrorType::MalformedSpecialForm,
+        );
+        test_eval_err(
+            "(define-record-type point (make-point) point? (x))",
+            RuntimeErrorType::MalformedSpecialForm,
+        );
+        test_eval_err(
+            "(define-record-type point make-point point?)",
+            RuntimeErrorType::MalformedSpecialForm,
+        );
+    }
+}
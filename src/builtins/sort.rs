@@ -0,0 +1,143 @@
+use crate::{
+    builtin_procedure::{BuiltinProcedureContext, BuiltinProcedureFn},
+    builtins::Builtin,
+    callable::CallableResult,
+    interpreter::RuntimeError,
+    procedure::Procedure,
+    source_mapped::SourceMappable,
+    value::SourceValue,
+};
+
+pub fn get_builtins() -> super::Builtins {
+    vec![
+        Builtin::Procedure("list-sort", BuiltinProcedureFn::Binary(list_sort)),
+        Builtin::Procedure("vector-sort!", BuiltinProcedureFn::Binary(vector_sort_mut)),
+    ]
+}
+
+fn is_less(
+    ctx: &mut BuiltinProcedureContext,
+    less: &Procedure,
+    a: &SourceValue,
+    b: &SourceValue,
+) -> Result<bool, RuntimeError> {
+    ctx.interpreter.check_interrupt(ctx.range)?;
+    let result =
+        ctx.interpreter
+            .call_procedure(less.clone(), &[a.clone(), b.clone()], ctx.range)?;
+    Ok(result.0.as_bool())
+}
+
+/// Sorts the given values with a merge sort, which is stable: values that
+/// are equal according to the comparator stay in the order they were in.
+///
+/// We can't use Rust's own sort, since the comparator can fail.
+fn stable_sort(
+    ctx: &mut BuiltinProcedureContext,
+    less: &Procedure,
+    mut items: Vec<SourceValue>,
+) -> Result<Vec<SourceValue>, RuntimeError> {
+    if items.len() <= 1 {
+        return Ok(items);
+    }
+    let right = items.split_off(items.len() / 2);
+    let left = stable_sort(ctx, less, items)?;
+    let right = stable_sort(ctx, less, right)?;
+    let mut result = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+        // Only take from the right if it's strictly less, to keep the sort
+        // stable.
+        if is_less(ctx, less, b, a)? {
+            result.push(right.next().unwrap());
+        } else {
+            result.push(left.next().unwrap());
+        }
+    }
+    result.extend(left);
+    result.extend(right);
+    Ok(result)
+}
+
+/// Like R6RS, this takes the comparator first.
+fn list_sort(
+    mut ctx: BuiltinProcedureContext,
+    less: &SourceValue,
+    list: &SourceValue,
+) -> CallableResult {
+    let less = less.expect_procedure()?;
+    let items = list.expect_list()?.as_ref().clone();
+    let sorted = stable_sort(&mut ctx, &less, items)?;
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(sorted)
+        .source_mapped(ctx.range)
+        .into())
+}
+
+fn vector_sort_mut(
+    mut ctx: BuiltinProcedureContext,
+    less: &SourceValue,
+    vector: &SourceValue,
+) -> CallableResult {
+    let less = less.expect_procedure()?;
+    let vector = vector.expect_vector()?;
+    // Note that we're sorting a copy of the items, rather than sorting them in
+    // place, since the comparator might look at (or change) the vector.
+    let items = vector.items().clone();
+    vector.set_items(stable_sort(&mut ctx, &less, items)?);
+    ctx.undefined()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        interpreter::RuntimeErrorType,
+        test_util::{test_eval_err, test_eval_success},
+    };
+
+    #[test]
+    fn list_sort_works() {
+        test_eval_success("(list-sort < '())", "()");
+        test_eval_success("(list-sort < '(3 1 2))", "(1 2 3)");
+        test_eval_success("(list-sort > '(3 1 4 1 5 9 2 6))", "(9 6 5 4 3 2 1 1)");
+        test_eval_err("(list-sort < '(1 a))", RuntimeErrorType::ExpectedNumber);
+        test_eval_err("(list-sort '(1 2) <)", RuntimeErrorType::ExpectedProcedure);
+    }
+
+    #[test]
+    fn list_sort_is_stable() {
+        test_eval_success(
+            "
+            (define (car< a b) (< (car a) (car b)))
+            (map cdr (list-sort car< '((2 . a) (1 . b) (2 . c) (1 . d) (0 . e) (2 . f))))
+            ",
+            "(e b d a c f)",
+        );
+    }
+
+    #[test]
+    fn vector_sort_mut_works() {
+        test_eval_success(
+            "(define v (list->vector '(5 3 1 4))) (vector-sort! < v) v",
+            "#(1 3 4 5)",
+        );
+        test_eval_success("(vector-sort! < (list->vector '(2 1)))", "");
+        test_eval_err("(vector-sort! < '(2 1))", RuntimeErrorType::ExpectedVector);
+    }
+
+    #[test]
+    fn vector_sort_mut_is_stable() {
+        test_eval_success(
+            "
+            (define (car< a b) (< (car a) (car b)))
+            (define v (list->vector '((2 . a) (1 . b) (2 . c) (1 . d) (0 . e))))
+            (vector-sort! car< v)
+            (map cdr (vector->list v))
+            ",
+            "(e b d a c)",
+        );
+    }
+}
@@ -0,0 +1,734 @@
+use crate::{
+    builtin_procedure::{BuiltinProcedureContext, BuiltinProcedureFn},
+    builtins::Builtin,
+    callable::CallableResult,
+    interpreter::{RuntimeError, RuntimeErrorType},
+    mutable_string::MutableString,
+    source_mapped::SourceMappable,
+    value::{SourceValue, Value},
+};
+
+use super::{
+    char::fold_case,
+    util::{expect_elements, optional_range_args},
+};
+
+pub fn get_builtins() -> super::Builtins {
+    vec![
+        Builtin::Procedure("string?", BuiltinProcedureFn::Unary(string)),
+        Builtin::Procedure("char?", BuiltinProcedureFn::Unary(char)),
+        Builtin::Procedure(
+            "string->list",
+            BuiltinProcedureFn::UnaryVariadic(string_to_list),
+        ),
+        Builtin::Procedure("list->string", BuiltinProcedureFn::Unary(list_to_string)),
+        Builtin::Procedure(
+            "string->vector",
+            BuiltinProcedureFn::Unary(string_to_vector),
+        ),
+        Builtin::Procedure(
+            "vector->string",
+            BuiltinProcedureFn::Unary(vector_to_string),
+        ),
+        Builtin::Procedure("string-upcase", BuiltinProcedureFn::Unary(string_upcase)),
+        Builtin::Procedure(
+            "string-downcase",
+            BuiltinProcedureFn::Unary(string_downcase),
+        ),
+        Builtin::Procedure(
+            "string-foldcase",
+            BuiltinProcedureFn::Unary(string_foldcase),
+        ),
+        Builtin::Procedure("string-reverse", BuiltinProcedureFn::Unary(string_reverse)),
+        Builtin::Procedure("substring", BuiltinProcedureFn::UnaryVariadic(substring)),
+        Builtin::Procedure("string-map", BuiltinProcedureFn::UnaryVariadic(string_map)),
+        Builtin::Procedure(
+            "string-for-each",
+            BuiltinProcedureFn::UnaryVariadic(string_for_each),
+        ),
+        Builtin::Procedure("string-trim", BuiltinProcedureFn::Unary(string_trim)),
+        Builtin::Procedure("string-pad", BuiltinProcedureFn::UnaryVariadic(string_pad)),
+        Builtin::Procedure(
+            "string-pad-right",
+            BuiltinProcedureFn::UnaryVariadic(string_pad_right),
+        ),
+        Builtin::Procedure("string-prefix?", BuiltinProcedureFn::Binary(string_prefix)),
+        Builtin::Procedure("string-suffix?", BuiltinProcedureFn::Binary(string_suffix)),
+        Builtin::Procedure(
+            "string-search-forward",
+            BuiltinProcedureFn::Ternary(string_search_forward),
+        ),
+        Builtin::Procedure(
+            "string-match-glob?",
+            BuiltinProcedureFn::Binary(string_match_glob),
+        ),
+    ]
+}
+
+/// Returns the characters of the given string as values.
+fn string_chars(value: &SourceValue) -> Result<Vec<SourceValue>, RuntimeError> {
+    let string = value.expect_string()?;
+    Ok(string
+        .to_string()
+        .chars()
+        .map(|char| Value::Character(char).into())
+        .collect())
+}
+
+/// Creates a string out of the given values, raising an error that points at
+/// the first value that isn't a character.
+fn chars_to_string(values: &[SourceValue]) -> Result<Value, RuntimeError> {
+    let chars = expect_elements(values, "character", SourceValue::expect_character)?;
+    Ok(Value::String(MutableString::new(
+        chars.into_iter().collect(),
+    )))
+}
+
+fn string(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(matches!(value.0, Value::String(_)).into())
+}
+
+fn char(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(matches!(value.0, Value::Character(_)).into())
+}
+
+/// Takes optional start and end indices, which are in characters rather than
+/// bytes.
+fn string_to_list(
+    ctx: BuiltinProcedureContext,
+    value: &SourceValue,
+    rest: &[SourceValue],
+) -> CallableResult {
+    let mut chars = string_chars(value)?;
+    let (start, end) = optional_range_args(ctx.range, rest, chars.len())?;
+    chars.truncate(end);
+    chars.drain(..start);
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(chars)
+        .source_mapped(ctx.range)
+        .into())
+}
+
+fn list_to_string(ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(chars_to_string(&value.expect_list()?)?
+        .source_mapped(ctx.range)
+        .into())
+}
+
+fn string_to_vector(ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    let chars = string_chars(value)?;
+    Ok(Value::Vector(ctx.interpreter.vector_manager.vector(chars))
+        .source_mapped(ctx.range)
+        .into())
+}
+
+fn vector_to_string(ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(chars_to_string(&value.expect_vector()?.items())?
+        .source_mapped(ctx.range)
+        .into())
+}
+
+/// Takes a start index and an optional end index, both of which are in
+/// characters rather than bytes.
+fn substring(
+    ctx: BuiltinProcedureContext,
+    string: &SourceValue,
+    rest: &[SourceValue],
+) -> CallableResult {
+    let chars: Vec<char> = string.expect_string()?.to_string().chars().collect();
+    let (start, end) = match rest {
+        [start] => (start.expect_index_or_end(chars.len())?, chars.len()),
+        [start, end] => {
+            let end = end.expect_index_or_end(chars.len())?;
+            (start.expect_index_or_end(end)?, end)
+        }
+        _ => return Err(RuntimeErrorType::WrongNumberOfArguments.source_mapped(ctx.range)),
+    };
+    Ok(
+        Value::String(MutableString::new(chars[start..end].iter().collect()))
+            .source_mapped(ctx.range)
+            .into(),
+    )
+}
+
+/// Returns a new string that's the result of applying the given function to
+/// the contents of the given string.
+fn map_string<F: FnOnce(&str) -> String>(
+    ctx: BuiltinProcedureContext,
+    value: &SourceValue,
+    func: F,
+) -> CallableResult {
+    let string = value.expect_string()?.to_string();
+    Ok(Value::String(MutableString::new(func(&string)))
+        .source_mapped(ctx.range)
+        .into())
+}
+
+fn string_upcase(ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    map_string(ctx, value, str::to_uppercase)
+}
+
+fn string_downcase(ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    map_string(ctx, value, str::to_lowercase)
+}
+
+/// Folds the case of each character separately, so unlike `string-downcase`,
+/// a final sigma folds the same way as any other sigma.
+fn string_foldcase(ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    map_string(ctx, value, |string| string.chars().map(fold_case).collect())
+}
+
+fn string_reverse(ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    map_string(ctx, value, |string| string.chars().rev().collect())
+}
+
+/// Returns the characters at each position of the given strings, stopping
+/// at the end of the shortest one.
+fn zip_strings(
+    ctx: &BuiltinProcedureContext,
+    strings: &[SourceValue],
+) -> Result<Vec<Vec<SourceValue>>, RuntimeError> {
+    if strings.is_empty() {
+        return Err(RuntimeErrorType::WrongNumberOfArguments.source_mapped(ctx.range));
+    }
+    let strings = strings
+        .iter()
+        .map(string_chars)
+        .collect::<Result<Vec<_>, _>>()?;
+    let len = strings.iter().map(|chars| chars.len()).min().unwrap_or(0);
+    Ok((0..len)
+        .map(|i| strings.iter().map(|chars| chars[i].clone()).collect())
+        .collect())
+}
+
+fn string_map(
+    ctx: BuiltinProcedureContext,
+    func: &SourceValue,
+    strings: &[SourceValue],
+) -> CallableResult {
+    let procedure = func.expect_procedure()?;
+    let mut result = String::new();
+    for args in zip_strings(&ctx, strings)? {
+        ctx.interpreter.check_interrupt(ctx.range)?;
+        let char = ctx
+            .interpreter
+            .call_procedure(procedure.clone(), &args, ctx.range)?;
+        result.push(char.expect_character()?);
+    }
+    Ok(Value::String(MutableString::new(result))
+        .source_mapped(ctx.range)
+        .into())
+}
+
+fn string_for_each(
+    ctx: BuiltinProcedureContext,
+    func: &SourceValue,
+    strings: &[SourceValue],
+) -> CallableResult {
+    let procedure = func.expect_procedure()?;
+    for args in zip_strings(&ctx, strings)? {
+        ctx.interpreter.check_interrupt(ctx.range)?;
+        ctx.interpreter
+            .call_procedure(procedure.clone(), &args, ctx.range)?;
+    }
+    ctx.undefined()
+}
+
+fn string_trim(ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    map_string(ctx, value, |string| string.trim().to_string())
+}
+
+/// The longest string that padding can produce, so that a mistyped length
+/// raises an error rather than exhausting memory.
+const MAX_PADDED_LENGTH: usize = 1 << 24;
+
+/// Pads the given string with a character (a space by default) until it has
+/// the given length, or truncates it if it's longer. Padding and truncation
+/// both happen on the left if `on_left` is true, and on the right otherwise.
+fn pad_string(
+    ctx: BuiltinProcedureContext,
+    string: &SourceValue,
+    rest: &[SourceValue],
+    on_left: bool,
+) -> CallableResult {
+    let (length, pad_char) = match rest {
+        [length] => (length.expect_size(MAX_PADDED_LENGTH)?, ' '),
+        [length, pad_char] => (
+            length.expect_size(MAX_PADDED_LENGTH)?,
+            pad_char.expect_character()?,
+        ),
+        _ => return Err(RuntimeErrorType::WrongNumberOfArguments.source_mapped(ctx.range)),
+    };
+    map_string(ctx, string, |string| {
+        let chars: Vec<char> = string.chars().collect();
+        let padding = std::iter::repeat_n(pad_char, length.saturating_sub(chars.len()));
+        if on_left {
+            let start = chars.len().saturating_sub(length);
+            padding.chain(chars[start..].iter().copied()).collect()
+        } else {
+            let end = chars.len().min(length);
+            chars[..end].iter().copied().chain(padding).collect()
+        }
+    })
+}
+
+fn string_pad(
+    ctx: BuiltinProcedureContext,
+    string: &SourceValue,
+    rest: &[SourceValue],
+) -> CallableResult {
+    pad_string(ctx, string, rest, true)
+}
+
+fn string_pad_right(
+    ctx: BuiltinProcedureContext,
+    string: &SourceValue,
+    rest: &[SourceValue],
+) -> CallableResult {
+    pad_string(ctx, string, rest, false)
+}
+
+fn string_prefix(
+    _ctx: BuiltinProcedureContext,
+    prefix: &SourceValue,
+    string: &SourceValue,
+) -> CallableResult {
+    let prefix = prefix.expect_string()?.to_string();
+    Ok(string
+        .expect_string()?
+        .to_string()
+        .starts_with(&prefix)
+        .into())
+}
+
+fn string_suffix(
+    _ctx: BuiltinProcedureContext,
+    suffix: &SourceValue,
+    string: &SourceValue,
+) -> CallableResult {
+    let suffix = suffix.expect_string()?.to_string();
+    Ok(string
+        .expect_string()?
+        .to_string()
+        .ends_with(&suffix)
+        .into())
+}
+
+/// Returns the index of the first occurrence of the pattern in the string at
+/// or after the given start index, or `#f` if there isn't one. Indices are in
+/// characters rather than bytes.
+fn string_search_forward(
+    _ctx: BuiltinProcedureContext,
+    pattern: &SourceValue,
+    string: &SourceValue,
+    start: &SourceValue,
+) -> CallableResult {
+    let pattern: Vec<char> = pattern.expect_string()?.to_string().chars().collect();
+    let chars: Vec<char> = string.expect_string()?.to_string().chars().collect();
+    let start = start.expect_index_or_end(chars.len())?;
+    let index = (start..=chars.len().saturating_sub(pattern.len()))
+        .find(|&i| chars[i..].starts_with(&pattern));
+    Ok(match index {
+        Some(index) => (index as f64).into(),
+        None => false.into(),
+    })
+}
+
+/// Returns whether the string matches the glob pattern, in which `*` matches
+/// any run of characters and `?` matches any single character.
+///
+/// This is iterative rather than recursive: when a mismatch occurs, we only
+/// ever need to backtrack to the most recent `*`, having it consume one more
+/// character, so it takes at most O(pattern length * string length) time.
+fn glob_matches(pattern: &[char], string: &[char]) -> bool {
+    let (mut p, mut s) = (0, 0);
+    let mut last_star: Option<(usize, usize)> = None;
+    while s < string.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                last_star = Some((p, s));
+                p += 1;
+            }
+            Some(&char) if char == '?' || char == string[s] => {
+                p += 1;
+                s += 1;
+            }
+            _ => {
+                let Some((star_p, star_s)) = last_star else {
+                    return false;
+                };
+                last_star = Some((star_p, star_s + 1));
+                p = star_p + 1;
+                s = star_s + 1;
+            }
+        }
+    }
+    pattern[p..].iter().all(|&char| char == '*')
+}
+
+fn string_match_glob(
+    _ctx: BuiltinProcedureContext,
+    pattern: &SourceValue,
+    string: &SourceValue,
+) -> CallableResult {
+    let pattern: Vec<char> = pattern.expect_string()?.to_string().chars().collect();
+    let string: Vec<char> = string.expect_string()?.to_string().chars().collect();
+    Ok(glob_matches(&pattern, &string).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        interpreter::RuntimeErrorType,
+        test_util::{test_eval_err, test_eval_success},
+    };
+
+    use super::glob_matches;
+
+    #[test]
+    fn characters_work() {
+        test_eval_success(r"#\a", r"#\a");
+        test_eval_success(r"#\(", r"#\(");
+        test_eval_success(r"#\space", r"#\space");
+        test_eval_success(r"#\newline", r"#\newline");
+        test_eval_success(r"(display #\a)", "a");
+        test_eval_success(r"(char? #\a)", "#t");
+        test_eval_success(r#"(char? "a")"#, "#f");
+        test_eval_success(r"(eqv? #\a #\a)", "#t");
+        test_eval_success(r"(eqv? #\a #\b)", "#f");
+    }
+
+    #[test]
+    fn invalid_characters_raise_errors() {
+        test_eval_err(
+            r"#\blarg",
+            RuntimeErrorType::Parse(crate::parser::ParseErrorType::InvalidCharacter),
+        );
+    }
+
+    #[test]
+    fn string_works() {
+        test_eval_success(r#"(string? "a")"#, "#t");
+        test_eval_success(r"(string? #\a)", "#f");
+    }
+
+    #[test]
+    fn string_to_list_works() {
+        test_eval_success(r#"(string->list "")"#, "()");
+        test_eval_success(r#"(string->list "ab c")"#, r"(#\a #\b #\space #\c)");
+        test_eval_success(r#"(string->list "abc" 0 3)"#, r"(#\a #\b #\c)");
+        test_eval_success(r#"(string->list "abc" 1)"#, r"(#\b #\c)");
+        test_eval_success(r#"(string->list "abcd" 1 3)"#, r"(#\b #\c)");
+        test_eval_success(r#"(string->list "abc" 3)"#, "()");
+        test_eval_success(r#"(string->list "héllo" 1 3)"#, r"(#\é #\l)");
+        test_eval_success(r#"(list->string (string->list "hello" 1 4))"#, r#""ell""#);
+    }
+
+    #[test]
+    fn string_to_list_errors_on_invalid_ranges() {
+        test_eval_err(
+            r#"(string->list "abc" 4)"#,
+            RuntimeErrorType::IndexOutOfRange {
+                index: 4.0,
+                length: 3,
+            },
+        );
+        test_eval_err(
+            r#"(string->list "abc" 0 4)"#,
+            RuntimeErrorType::IndexOutOfRange {
+                index: 4.0,
+                length: 3,
+            },
+        );
+        test_eval_err(
+            r#"(string->list "abc" 2 1)"#,
+            RuntimeErrorType::IndexOutOfRange {
+                index: 2.0,
+                length: 1,
+            },
+        );
+        test_eval_err(
+            r#"(string->list "abc" -1)"#,
+            RuntimeErrorType::IndexOutOfRange {
+                index: -1.0,
+                length: 3,
+            },
+        );
+        test_eval_err(
+            r#"(string->list "abc" 0.5)"#,
+            RuntimeErrorType::ExpectedInteger,
+        );
+        test_eval_err(
+            r#"(string->list "abc" 0 1 2)"#,
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+    }
+
+    #[test]
+    fn list_to_string_works() {
+        test_eval_success(r"(list->string '())", r#""""#);
+        test_eval_success(r"(list->string (list #\a #\b))", r#""ab""#);
+        test_eval_err(
+            r"(list->string (list #\a #\b 1))",
+            RuntimeErrorType::WrongElementType {
+                index: 2,
+                expected: "character",
+                actual: "number",
+            },
+        );
+    }
+
+    #[test]
+    fn string_to_vector_works() {
+        test_eval_success(r#"(string->vector "")"#, "#()");
+        test_eval_success(r#"(string->vector "abc")"#, r"#(#\a #\b #\c)");
+        test_eval_err("(string->vector 1)", RuntimeErrorType::ExpectedString);
+    }
+
+    #[test]
+    fn vector_to_string_works() {
+        test_eval_success(r#"(vector->string (string->vector "hello"))"#, r#""hello""#);
+        test_eval_success(
+            r#"(vector->string (list->vector (list #\h #\i)))"#,
+            r#""hi""#,
+        );
+        test_eval_err(
+            r#"(vector->string (list->vector (list #\h "i")))"#,
+            RuntimeErrorType::WrongElementType {
+                index: 1,
+                expected: "character",
+                actual: "string",
+            },
+        );
+        test_eval_err("(vector->string \"hi\")", RuntimeErrorType::ExpectedVector);
+    }
+
+    #[test]
+    fn case_conversion_works() {
+        test_eval_success(r#"(string-upcase "Hello World")"#, r#""HELLO WORLD""#);
+        test_eval_success(r#"(string-downcase "Hello World")"#, r#""hello world""#);
+        test_eval_success(r#"(string-upcase "straße")"#, r#""STRASSE""#);
+        test_eval_success(r#"(string-downcase "ΑΒΓ")"#, r#""αβγ""#);
+        test_eval_err("(string-upcase 'hi)", RuntimeErrorType::ExpectedString);
+    }
+
+    #[test]
+    fn string_foldcase_works() {
+        test_eval_success(r#"(string-foldcase "Hello")"#, r#""hello""#);
+        test_eval_success(r#"(string-foldcase "Straße")"#, r#""strasse""#);
+        test_eval_success(r#"(string-foldcase "ΟΔΟΣ")"#, r#""οδοσ""#);
+        test_eval_success(r#"(string-downcase "ΟΔΟΣ")"#, r#""οδος""#);
+        test_eval_success(
+            r#"(equal? (string-foldcase "STRASSE") (string-foldcase "straße"))"#,
+            "#t",
+        );
+    }
+
+    #[test]
+    fn substring_works() {
+        test_eval_success(r#"(substring "hello" 1 3)"#, r#""el""#);
+        test_eval_success(r#"(substring "hello" 2)"#, r#""llo""#);
+        test_eval_success(r#"(substring "hello" 5)"#, r#""""#);
+        test_eval_success(r#"(substring "héllo" 1 2)"#, r#""é""#);
+        test_eval_err(
+            r#"(substring "hello")"#,
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+    }
+
+    #[test]
+    fn substring_errors_on_invalid_indices() {
+        test_eval_err(
+            r#"(substring "hello" -1)"#,
+            RuntimeErrorType::IndexOutOfRange {
+                index: -1.0,
+                length: 5,
+            },
+        );
+        test_eval_err(
+            r#"(substring "hello" 1.5)"#,
+            RuntimeErrorType::ExpectedInteger,
+        );
+        test_eval_err(
+            r#"(substring "hello" 0 6)"#,
+            RuntimeErrorType::IndexOutOfRange {
+                index: 6.0,
+                length: 5,
+            },
+        );
+        test_eval_err(
+            r#"(substring "hello" 3 2)"#,
+            RuntimeErrorType::IndexOutOfRange {
+                index: 3.0,
+                length: 2,
+            },
+        );
+        test_eval_err(
+            r#"(substring "hello" 'a)"#,
+            RuntimeErrorType::ExpectedInteger,
+        );
+    }
+
+    #[test]
+    fn string_reverse_works() {
+        test_eval_success(r#"(string-reverse "hello")"#, r#""olleh""#);
+        test_eval_success(r#"(string-reverse "")"#, r#""""#);
+        test_eval_success(r#"(string-reverse "héllo→")"#, r#""→olléh""#);
+        test_eval_err("(string-reverse 'hi)", RuntimeErrorType::ExpectedString);
+    }
+
+    #[test]
+    fn string_map_works() {
+        test_eval_success(r#"(string-map char-upcase "hello")"#, r#""HELLO""#);
+        test_eval_success(r#"(string-map char-upcase "")"#, r#""""#);
+        test_eval_success(
+            r#"(string-map (lambda (a b) (if (char? a) b a)) "abc" "xy")"#,
+            r#""xy""#,
+        );
+        test_eval_err(
+            r#"(string-map (lambda (c) 1) "abc")"#,
+            RuntimeErrorType::ExpectedCharacter,
+        );
+        test_eval_err(
+            "(string-map char-upcase 'abc)",
+            RuntimeErrorType::ExpectedString,
+        );
+        test_eval_err(
+            "(string-map char-upcase)",
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+    }
+
+    #[test]
+    fn string_for_each_works() {
+        test_eval_success(r#"(string-for-each display "abc")"#, "abc");
+        test_eval_success(
+            r#"(string-for-each (lambda (a b) (display b) (display a)) "abc" "12")"#,
+            "1a2b",
+        );
+    }
+
+    #[test]
+    fn string_trim_works() {
+        test_eval_success(r#"(string-trim "  hi there \n")"#, r#""hi there""#);
+        test_eval_success(r#"(string-trim "hi")"#, r#""hi""#);
+        test_eval_success(r#"(string-trim "   ")"#, r#""""#);
+    }
+
+    #[test]
+    fn string_prefix_and_suffix_work() {
+        test_eval_success(r#"(string-prefix? "ab" "abc")"#, "#t");
+        test_eval_success(r#"(string-prefix? "" "abc")"#, "#t");
+        test_eval_success(r#"(string-prefix? "bc" "abc")"#, "#f");
+        test_eval_success(r#"(string-prefix? "abcd" "abc")"#, "#f");
+        test_eval_success(r#"(string-suffix? "bc" "abc")"#, "#t");
+        test_eval_success(r#"(string-suffix? "" "abc")"#, "#t");
+        test_eval_success(r#"(string-suffix? "ab" "abc")"#, "#f");
+        test_eval_success(r#"(string-suffix? "abc" "abc")"#, "#t");
+        test_eval_err(
+            r#"(string-prefix? 'a "abc")"#,
+            RuntimeErrorType::ExpectedString,
+        );
+    }
+
+    #[test]
+    fn string_search_forward_works() {
+        test_eval_success(r#"(string-search-forward "b" "abcb" 0)"#, "1");
+        test_eval_success(r#"(string-search-forward "b" "abcb" 2)"#, "3");
+        test_eval_success(r#"(string-search-forward "cb" "abcb" 0)"#, "2");
+        test_eval_success(r#"(string-search-forward "x" "abcb" 0)"#, "#f");
+        test_eval_success(r#"(string-search-forward "abcbd" "abcb" 0)"#, "#f");
+        test_eval_success(r#"(string-search-forward "" "abc" 3)"#, "3");
+        test_eval_success(r#"(string-search-forward "l" "héllo" 0)"#, "2");
+        test_eval_err(
+            r#"(string-search-forward "b" "abc" 4)"#,
+            RuntimeErrorType::IndexOutOfRange {
+                index: 4.0,
+                length: 3,
+            },
+        );
+    }
+
+    #[test]
+    fn string_match_glob_works() {
+        test_eval_success(r#"(string-match-glob? "abc" "abc")"#, "#t");
+        test_eval_success(r#"(string-match-glob? "abc" "abd")"#, "#f");
+        test_eval_success(r#"(string-match-glob? "a?c" "abc")"#, "#t");
+        test_eval_success(r#"(string-match-glob? "a?c" "ac")"#, "#f");
+        test_eval_success(r#"(string-match-glob? "*.scm" "boop.scm")"#, "#t");
+        test_eval_success(r#"(string-match-glob? "*.scm" "boop.sch")"#, "#f");
+        test_eval_success(r#"(string-match-glob? "a*b*c" "aXXbYYbZc")"#, "#t");
+        test_eval_success(r#"(string-match-glob? "a*b*c" "aXXbYYbZ")"#, "#f");
+        test_eval_success(r#"(string-match-glob? "*" "")"#, "#t");
+        test_eval_success(r#"(string-match-glob? "?" "")"#, "#f");
+        test_eval_success(r#"(string-match-glob? "**a**" "bab")"#, "#t");
+        test_eval_success(r#"(string-match-glob? "h?llo*" "héllo world")"#, "#t");
+    }
+
+    #[test]
+    fn glob_matching_does_not_backtrack_catastrophically() {
+        let pattern: Vec<char> = "a*".repeat(50).chars().chain(['b']).collect();
+        let string: Vec<char> = "a".repeat(1000).chars().collect();
+        assert!(!glob_matches(&pattern, &string));
+    }
+
+    #[test]
+    fn string_pad_works() {
+        test_eval_success(r#"(string-pad "42" 5)"#, r#""   42""#);
+        test_eval_success(r#"(string-pad "42" 5 #\0)"#, r#""00042""#);
+        test_eval_success(r#"(string-pad "12345" 3)"#, r#""345""#);
+        test_eval_success(r#"(string-pad "ñé" 3 #\*)"#, r#""*ñé""#);
+        test_eval_success(r#"(string-pad "ñé" 1)"#, r#""é""#);
+        test_eval_success(r#"(string-pad "hi" 0)"#, r#""""#);
+    }
+
+    #[test]
+    fn string_pad_right_works() {
+        test_eval_success(r#"(string-pad-right "42" 5)"#, r#""42   ""#);
+        test_eval_success(r#"(string-pad-right "42" 5 #\.)"#, r#""42...""#);
+        test_eval_success(r#"(string-pad-right "12345" 3)"#, r#""123""#);
+        test_eval_success(r#"(string-pad-right "ñé" 1)"#, r#""ñ""#);
+    }
+
+    #[test]
+    fn string_pad_rejects_absurd_lengths() {
+        test_eval_err(
+            r#"(string-pad "a" (* 1000000 1000000 1000000))"#,
+            RuntimeErrorType::SizeTooLarge {
+                size: 1e18,
+                max: 1 << 24,
+            },
+        );
+        test_eval_err(
+            r#"(string-pad-right "a" (* 1000000 1000000 1000000) #\x)"#,
+            RuntimeErrorType::SizeTooLarge {
+                size: 1e18,
+                max: 1 << 24,
+            },
+        );
+    }
+
+    #[test]
+    fn string_pad_raises_errors() {
+        test_eval_err(
+            r#"(string-pad "hi" -1)"#,
+            RuntimeErrorType::ExpectedNonNegativeInteger,
+        );
+        test_eval_err(
+            r#"(string-pad "hi" 3 "x")"#,
+            RuntimeErrorType::ExpectedCharacter,
+        );
+        test_eval_err(
+            r#"(string-pad-right "hi")"#,
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+    }
+
+    #[test]
+    fn string_operations_return_fresh_strings() {
+        test_eval_success(r#"(define s "hi") (eq? s (string-trim s))"#, "#f");
+        test_eval_success(r#"(define s "hi") (eq? s (string-pad s 2))"#, "#f");
+    }
+}
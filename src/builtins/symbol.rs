@@ -0,0 +1,71 @@
+use crate::{
+    builtin_procedure::{BuiltinProcedureContext, BuiltinProcedureFn},
+    builtins::Builtin,
+    callable::CallableResult,
+    source_mapped::SourceMappable,
+    value::{SourceValue, Value},
+};
+
+pub fn get_builtins() -> super::Builtins {
+    vec![
+        Builtin::Procedure(
+            "symbol-append",
+            BuiltinProcedureFn::NullaryVariadic(symbol_append),
+        ),
+        Builtin::Procedure(
+            "symbol<?",
+            BuiltinProcedureFn::NullaryVariadic(symbol_less_than),
+        ),
+    ]
+}
+
+fn symbol_append(ctx: BuiltinProcedureContext, operands: &[SourceValue]) -> CallableResult {
+    let mut name = String::new();
+    for operand in operands {
+        name.push_str(operand.expect_identifier()?.as_ref());
+    }
+    let symbol = ctx.interpreter.string_interner.intern(name);
+    Ok(Value::Symbol(symbol).source_mapped(ctx.range).into())
+}
+
+fn symbol_less_than(_ctx: BuiltinProcedureContext, operands: &[SourceValue]) -> CallableResult {
+    let mut symbols = Vec::with_capacity(operands.len());
+    for operand in operands {
+        symbols.push(operand.expect_identifier()?);
+    }
+    Ok(symbols
+        .windows(2)
+        .all(|pair| pair[0].as_ref() < pair[1].as_ref())
+        .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        interpreter::RuntimeErrorType,
+        test_util::{test_eval_err, test_eval_success},
+    };
+
+    #[test]
+    fn symbol_append_works() {
+        test_eval_success("(symbol-append 'foo 'bar)", "foobar");
+        test_eval_success("(symbol-append 'foo)", "foo");
+        test_eval_success("(eq? (symbol-append 'foo 'bar 'baz) 'foobarbaz)", "#t");
+        test_eval_success("(symbol-append)", "");
+        test_eval_err(
+            "(symbol-append 'foo 1)",
+            RuntimeErrorType::ExpectedIdentifier,
+        );
+    }
+
+    #[test]
+    fn symbol_less_than_works() {
+        test_eval_success("(symbol<? 'a 'b)", "#t");
+        test_eval_success("(symbol<? 'b 'a)", "#f");
+        test_eval_success("(symbol<? 'a 'a)", "#f");
+        test_eval_success("(symbol<? 'a 'b 'c)", "#t");
+        test_eval_success("(symbol<? 'a 'c 'b)", "#f");
+        test_eval_success("(symbol<? 'a)", "#t");
+        test_eval_err("(symbol<? 'a \"b\")", RuntimeErrorType::ExpectedIdentifier);
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{interpreter::RuntimeError, value::SourceValue};
+use crate::{
+    interpreter::{RuntimeError, RuntimeErrorType},
+    source_mapped::{SourceMappable, SourceRange},
+    value::SourceValue,
+};
 
 pub fn number_args(operands: &[SourceValue]) -> Result<Vec<f64>, RuntimeError> {
     let mut numbers = Vec::with_capacity(operands.len());
@@ -7,3 +11,67 @@ pub fn number_args(operands: &[SourceValue]) -> Result<Vec<f64>, RuntimeError> {
     }
     Ok(numbers)
 }
+
+/// Converts every element of a sequence using the given `expect_*` function.
+///
+/// If an element can't be converted, the error says which element it was and
+/// what its type is, which is more helpful than just saying what was expected.
+pub fn expect_elements<T, F>(
+    values: &[SourceValue],
+    expected: &'static str,
+    expect: F,
+) -> Result<Vec<T>, RuntimeError>
+where
+    F: Fn(&SourceValue) -> Result<T, RuntimeError>,
+{
+    let mut results = Vec::with_capacity(values.len());
+    for (index, value) in values.iter().enumerate() {
+        match expect(value) {
+            Ok(result) => results.push(result),
+            Err(_) => {
+                return Err(RuntimeErrorType::WrongElementType {
+                    index,
+                    expected,
+                    actual: value.0.type_name(),
+                }
+                .source_mapped(value.1))
+            }
+        }
+    }
+    Ok(results)
+}
+
+/// Parses an optional radix argument from the given rest arguments, which
+/// must be an integer from 2 to 16. Defaults to 10.
+pub fn optional_radix_arg(range: SourceRange, rest: &[SourceValue]) -> Result<u32, RuntimeError> {
+    match rest {
+        [] => Ok(10),
+        [radix] => {
+            let value = radix.expect_integer()?;
+            if (2.0..=16.0).contains(&value) {
+                Ok(value as u32)
+            } else {
+                Err(RuntimeErrorType::InvalidRadix.source_mapped(radix.1))
+            }
+        }
+        _ => Err(RuntimeErrorType::WrongNumberOfArguments.source_mapped(range)),
+    }
+}
+
+/// Parses the optional `start` and `end` arguments that many vector and
+/// string procedures take, defaulting to the whole sequence.
+pub fn optional_range_args(
+    range: SourceRange,
+    rest: &[SourceValue],
+    length: usize,
+) -> Result<(usize, usize), RuntimeError> {
+    match rest {
+        [] => Ok((0, length)),
+        [start] => Ok((start.expect_index_or_end(length)?, length)),
+        [start, end] => {
+            let end = end.expect_index_or_end(length)?;
+            Ok((start.expect_index_or_end(end)?, end))
+        }
+        _ => Err(RuntimeErrorType::WrongNumberOfArguments.source_mapped(range)),
+    }
+}
@@ -0,0 +1,214 @@
+use std::rc::Rc;
+
+use crate::{
+    builtin_procedure::{BuiltinProcedureContext, BuiltinProcedureFn},
+    builtins::Builtin,
+    callable::{CallableResult, CallableSuccess, TailCallContext},
+    compound_procedure::{Body, CompoundProcedure, Signature},
+    interpreter::RuntimeErrorType,
+    procedure::Procedure,
+    source_mapped::SourceMappable,
+    special_form::SpecialFormContext,
+    value::{SourceValue, Value},
+};
+
+pub fn get_builtins() -> super::Builtins {
+    vec![
+        Builtin::Procedure("values", BuiltinProcedureFn::NullaryVariadic(values)),
+        Builtin::Procedure(
+            "call-with-values",
+            BuiltinProcedureFn::Binary(call_with_values),
+        ),
+        Builtin::SpecialForm("receive", receive),
+        Builtin::SpecialForm("define-values", define_values),
+    ]
+}
+
+/// Returns a value that represents the given values. A single value just
+/// represents itself.
+pub fn make_values(mut values: Vec<SourceValue>) -> Value {
+    if values.len() == 1 {
+        values.pop().unwrap().0
+    } else {
+        Value::MultipleValues(Rc::new(values))
+    }
+}
+
+/// The inverse of `make_values()`.
+pub fn unpack_values(value: SourceValue) -> Vec<SourceValue> {
+    match value.0 {
+        Value::MultipleValues(values) => values.as_ref().clone(),
+        _ => vec![value],
+    }
+}
+
+fn values(ctx: BuiltinProcedureContext, operands: &[SourceValue]) -> CallableResult {
+    Ok(make_values(operands.to_vec())
+        .source_mapped(ctx.range)
+        .into())
+}
+
+fn call_with_values(
+    ctx: BuiltinProcedureContext,
+    producer: &SourceValue,
+    consumer: &SourceValue,
+) -> CallableResult {
+    let producer = producer.expect_procedure()?;
+    let consumer = consumer.expect_procedure()?;
+    let result = ctx.interpreter.call_procedure(producer, &[], ctx.range)?;
+    Ok(CallableSuccess::TailCall(TailCallContext {
+        bound_procedure: consumer.bind(ctx.range, &unpack_values(result))?,
+    }))
+}
+
+/// SRFI-8's `(receive formals expression body...)`, which binds the values of
+/// the expression to the formals, as if they were arguments to a procedure.
+fn receive(ctx: SpecialFormContext) -> CallableResult {
+    if ctx.operands.len() < 3 {
+        return Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(ctx.range));
+    }
+    let signature = Signature::parse(ctx.operands[0].clone())?;
+    let body = Body::try_new(&ctx.operands[2..], ctx.range)?;
+    let values = ctx.interpreter.eval_expression(&ctx.operands[1])?;
+    let procedure = Procedure::Compound(CompoundProcedure::create(
+        ctx.interpreter.new_id(),
+        signature,
+        body,
+        ctx.interpreter.environment.capture_lexical_scope(),
+    ));
+    Ok(CallableSuccess::TailCall(TailCallContext {
+        bound_procedure: procedure.bind(ctx.range, &unpack_values(values))?,
+    }))
+}
+
+/// `(define-values formals expression)`, which defines each of the formals
+/// in the current scope, using the values of the expression.
+fn define_values(ctx: SpecialFormContext) -> CallableResult {
+    if ctx.operands.len() != 2 {
+        return Err(RuntimeErrorType::MalformedSpecialForm.source_mapped(ctx.range));
+    }
+    let signature = Signature::parse(ctx.operands[0].clone())?;
+    let values = unpack_values(ctx.interpreter.eval_expression(&ctx.operands[1])?);
+    if !signature.is_valid_arity(values.len()) {
+        return Err(RuntimeErrorType::WrongNumberOfArguments.source_mapped(ctx.range));
+    }
+    signature.bind_args(values, ctx.interpreter);
+    ctx.undefined()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        interpreter::RuntimeErrorType,
+        test_util::{test_eval_err, test_eval_success},
+    };
+
+    #[test]
+    fn values_works() {
+        test_eval_success("(values 1)", "1");
+        test_eval_success("(values 1 2)", "1 2");
+        test_eval_success("(values)", "");
+    }
+
+    #[test]
+    fn call_with_values_works() {
+        // From R5RS 6.4.
+        test_eval_success(
+            "(call-with-values (lambda () (values 4 5)) (lambda (a b) b))",
+            "5",
+        );
+        test_eval_success("(call-with-values * -)", "-1");
+        test_eval_success("(call-with-values (lambda () (values)) list)", "()");
+        test_eval_err(
+            "(call-with-values (lambda () (values 1 2)) (lambda (a) a))",
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+    }
+
+    #[test]
+    fn receive_works() {
+        test_eval_success("(receive (a b) (values 1 2) (list b a))", "(2 1)");
+        test_eval_success(
+            "(receive (a . rest) (values 1 2 3) (list a rest))",
+            "(1 (2 3))",
+        );
+        test_eval_success("(receive all (values 1 2) all)", "(1 2)");
+        test_eval_success("(receive all (values) all)", "()");
+        test_eval_success("(receive (q r) (floor/ 7 2) (display q) r)", "31");
+        test_eval_success("(receive (x) 5 (* x 2))", "10");
+        test_eval_success("(define x 1) (receive (y) 2 (+ x y))", "3");
+    }
+
+    #[test]
+    fn define_values_works() {
+        test_eval_success("(define-values (q r) (floor/ 17 5)) (list q r)", "(3 2)");
+        test_eval_success(
+            "(define-values (a . rest) (values 1 2 3)) (list a rest)",
+            "(1 (2 3))",
+        );
+        test_eval_success("(define-values all (values 1 2)) all", "(1 2)");
+        test_eval_success("(define-values (x) 5) x", "5");
+        test_eval_success("(define-values () (values)) 1", "1");
+        test_eval_success(
+            "(define x 1) (define (f) (define-values (x y) (values 2 3)) (+ x y)) (list (f) x)",
+            "(5 1)",
+        );
+    }
+
+    #[test]
+    fn define_values_errors_on_mismatched_values() {
+        test_eval_err(
+            "(define-values (a b) (values 1 2 3))",
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+        test_eval_err(
+            "(define-values (a b) 1)",
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+        test_eval_err(
+            "(define-values (a b . c) 1)",
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+        test_eval_err(
+            "(define-values (a))",
+            RuntimeErrorType::MalformedSpecialForm,
+        );
+        test_eval_err(
+            "(define-values (1) 1)",
+            RuntimeErrorType::ExpectedIdentifier,
+        );
+    }
+
+    #[test]
+    fn receive_errors_on_mismatched_values() {
+        test_eval_err(
+            "(receive (a b) (values 1 2 3) a)",
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+        test_eval_err(
+            "(receive (a b . c) 1 a)",
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+        test_eval_err("(receive (a) 1)", RuntimeErrorType::MalformedSpecialForm);
+        test_eval_err("(receive (1) 1 2)", RuntimeErrorType::ExpectedIdentifier);
+    }
+
+    #[test]
+    fn single_value_operands_work() {
+        test_eval_success("(+ (values 1) 2)", "3");
+        test_eval_success("(list (values 'a) (values 'b))", "(a b)");
+    }
+
+    #[test]
+    fn multiple_value_operands_raise_errors() {
+        test_eval_err(
+            "(+ (values 1 2) 3)",
+            RuntimeErrorType::MultipleValuesAsOperand,
+        );
+        test_eval_err("(list (values))", RuntimeErrorType::MultipleValuesAsOperand);
+        test_eval_err(
+            "((lambda (x) x) (values 1 2))",
+            RuntimeErrorType::MultipleValuesAsOperand,
+        );
+    }
+}
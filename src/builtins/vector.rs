@@ -0,0 +1,442 @@
+use crate::{
+    builtin_procedure::{BuiltinProcedureContext, BuiltinProcedureFn},
+    builtins::Builtin,
+    callable::CallableResult,
+    interpreter::RuntimeErrorType,
+    source_mapped::SourceMappable,
+    value::{SourceValue, Value},
+};
+
+use super::util::{expect_elements, optional_range_args};
+
+pub fn get_builtins() -> super::Builtins {
+    vec![
+        Builtin::Procedure("vector?", BuiltinProcedureFn::Unary(is_vector)),
+        Builtin::Procedure("vector", BuiltinProcedureFn::NullaryVariadic(vector)),
+        Builtin::Procedure("vector-length", BuiltinProcedureFn::Unary(vector_length)),
+        Builtin::Procedure("vector-ref", BuiltinProcedureFn::Binary(vector_ref)),
+        Builtin::Procedure("vector->list", BuiltinProcedureFn::Unary(vector_to_list)),
+        Builtin::Procedure("list->vector", BuiltinProcedureFn::Unary(list_to_vector)),
+        Builtin::Procedure(
+            "vector-copy",
+            BuiltinProcedureFn::UnaryVariadic(vector_copy),
+        ),
+        Builtin::Procedure(
+            "vector-copy!",
+            BuiltinProcedureFn::UnaryVariadic(vector_copy_into),
+        ),
+        Builtin::Procedure(
+            "vector-append",
+            BuiltinProcedureFn::NullaryVariadic(vector_append),
+        ),
+        Builtin::Procedure(
+            "vector-binary-search",
+            BuiltinProcedureFn::Ternary(vector_binary_search),
+        ),
+    ]
+}
+
+fn is_vector(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(matches!(value.0, Value::Vector(_)).into())
+}
+
+fn vector(ctx: BuiltinProcedureContext, operands: &[SourceValue]) -> CallableResult {
+    Ok(
+        Value::Vector(ctx.interpreter.vector_manager.vector(operands.to_vec()))
+            .source_mapped(ctx.range)
+            .into(),
+    )
+}
+
+fn vector_length(_ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    Ok(Value::Number(value.expect_vector()?.len() as f64).into())
+}
+
+fn vector_ref(
+    _ctx: BuiltinProcedureContext,
+    vector: &SourceValue,
+    k: &SourceValue,
+) -> CallableResult {
+    let vector = vector.expect_vector()?;
+    let items = vector.items();
+    let index = k.expect_index(items.len())?;
+    Ok(items[index].clone().into())
+}
+
+fn vector_to_list(ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    let items = value.expect_vector()?.items().clone();
+    Ok(ctx
+        .interpreter
+        .pair_manager
+        .vec_to_list(items)
+        .source_mapped(ctx.range)
+        .into())
+}
+
+fn list_to_vector(ctx: BuiltinProcedureContext, value: &SourceValue) -> CallableResult {
+    let items = value.expect_list()?;
+    Ok(Value::Vector(
+        ctx.interpreter
+            .vector_manager
+            .vector(items.as_ref().clone()),
+    )
+    .source_mapped(ctx.range)
+    .into())
+}
+
+fn vector_copy(
+    ctx: BuiltinProcedureContext,
+    vector: &SourceValue,
+    rest: &[SourceValue],
+) -> CallableResult {
+    let vector = vector.expect_vector()?;
+    let items = vector.items();
+    let (start, end) = optional_range_args(ctx.range, rest, items.len())?;
+    let copy = items[start..end].to_vec();
+    Ok(Value::Vector(ctx.interpreter.vector_manager.vector(copy))
+        .source_mapped(ctx.range)
+        .into())
+}
+
+/// Implements `(vector-copy! to at from [start [end]])`.
+fn vector_copy_into(
+    ctx: BuiltinProcedureContext,
+    to: &SourceValue,
+    rest: &[SourceValue],
+) -> CallableResult {
+    let [at, from, rest @ ..] = rest else {
+        return Err(RuntimeErrorType::WrongNumberOfArguments.source_mapped(ctx.range));
+    };
+    let to = to.expect_vector()?;
+    let from = from.expect_vector()?;
+
+    // The source and destination may be the same vector, so we copy the
+    // source range out before writing anything, which makes overlapping
+    // ranges work regardless of which direction they overlap in.
+    let copy = {
+        let items = from.items();
+        let (start, end) = optional_range_args(ctx.range, rest, items.len())?;
+        items[start..end].to_vec()
+    };
+    let length = to.len();
+    let at_index = at.expect_index_or_end(length)?;
+    if at_index + copy.len() > length {
+        return Err(RuntimeErrorType::IndexOutOfRange {
+            index: at_index as f64,
+            length,
+        }
+        .source_mapped(at.1));
+    }
+    to.items_mut()[at_index..at_index + copy.len()].clone_from_slice(&copy);
+    ctx.undefined()
+}
+
+fn vector_append(ctx: BuiltinProcedureContext, operands: &[SourceValue]) -> CallableResult {
+    let vectors = expect_elements(operands, "vector", SourceValue::expect_vector)?;
+    let mut items = vec![];
+    for vector in vectors {
+        items.extend(vector.items().iter().cloned());
+    }
+    Ok(Value::Vector(ctx.interpreter.vector_manager.vector(items))
+        .source_mapped(ctx.range)
+        .into())
+}
+
+/// Implements `(vector-binary-search vector key compare)`, where the vector is
+/// sorted and `(compare element key)` returns a negative number, zero, or a
+/// positive number if the element is less than, equal to, or greater than the
+/// key. Returns the index of a matching element, or `#f` if there isn't one.
+fn vector_binary_search(
+    ctx: BuiltinProcedureContext,
+    vector: &SourceValue,
+    key: &SourceValue,
+    compare: &SourceValue,
+) -> CallableResult {
+    let vector = vector.expect_vector()?;
+    let compare = compare.expect_procedure()?;
+    // Nothing can change a vector's length, so these bounds stay valid even
+    // if the comparator mutates the vector.
+    let mut low = 0;
+    let mut high = vector.len();
+    while low < high {
+        ctx.interpreter.check_interrupt(ctx.range)?;
+        let middle = low + (high - low) / 2;
+        let element = vector.items()[middle].clone();
+        let ordering = ctx
+            .interpreter
+            .call_procedure(compare.clone(), &[element, key.clone()], ctx.range)?
+            .expect_number()?;
+        if ordering < 0.0 {
+            low = middle + 1;
+        } else if ordering > 0.0 {
+            high = middle;
+        } else {
+            return Ok(Value::Number(middle as f64).into());
+        }
+    }
+    Ok(false.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        interpreter::RuntimeErrorType,
+        test_util::{test_eval_err, test_eval_success, test_eval_successes},
+    };
+
+    #[test]
+    fn vector_works() {
+        test_eval_success("(vector? (list->vector '(1 2)))", "#t");
+        test_eval_success("(vector? '(1 2))", "#f");
+    }
+
+    #[test]
+    fn vector_constructor_works() {
+        test_eval_success("(vector)", "#()");
+        test_eval_success("(vector 1 (+ 1 1) \"three\")", "#(1 2 \"three\")");
+        test_eval_success("(apply vector '(1 2 3))", "#(1 2 3)");
+        test_eval_success("(eq? (vector) (vector))", "#f");
+    }
+
+    #[test]
+    fn vector_binary_search_works() {
+        test_eval_successes(&[
+            ("(define v (vector 1 3 5 7 9 11))", ""),
+            ("(vector-binary-search v 1 -)", "0"),
+            ("(vector-binary-search v 7 -)", "3"),
+            ("(vector-binary-search v 11 -)", "5"),
+            ("(vector-binary-search v 0 -)", "#f"),
+            ("(vector-binary-search v 6 -)", "#f"),
+            ("(vector-binary-search v 12 -)", "#f"),
+            ("(vector-binary-search (vector) 1 -)", "#f"),
+        ]);
+        test_eval_err(
+            "(vector-binary-search (vector 1) 1 (lambda (a b) #t))",
+            RuntimeErrorType::ExpectedNumber,
+        );
+        test_eval_err(
+            "(vector-binary-search '(1) 1 -)",
+            RuntimeErrorType::ExpectedVector,
+        );
+    }
+
+    #[test]
+    fn vector_binary_search_is_logarithmic() {
+        test_eval_successes(&[
+            ("(define calls 0)", ""),
+            (
+                "(define (compare a b) (set! calls (+ calls 1)) (- a b))",
+                "",
+            ),
+            ("(define v (list->vector (iota 1024)))", ""),
+            ("(vector-binary-search v 1000 compare)", "1000"),
+            ("(<= calls 11)", "#t"),
+        ]);
+    }
+
+    #[test]
+    fn vector_length_works() {
+        test_eval_success("(vector-length (list->vector '()))", "0");
+        test_eval_success("(vector-length (list->vector '(1 2)))", "2");
+        test_eval_err("(vector-length '(1 2))", RuntimeErrorType::ExpectedVector);
+    }
+
+    #[test]
+    fn vector_ref_works() {
+        test_eval_success("(vector-ref (list->vector '(1 2 3)) 0)", "1");
+        test_eval_success("(vector-ref (list->vector '(1 2 3)) 2)", "3");
+        test_eval_err("(vector-ref '(1 2 3) 0)", RuntimeErrorType::ExpectedVector);
+        test_eval_err(
+            "(vector-ref (list->vector '(1 2 3)) 1.5)",
+            RuntimeErrorType::ExpectedInteger,
+        );
+        test_eval_err(
+            "(vector-ref (list->vector '(1 2 3)) -1)",
+            RuntimeErrorType::IndexOutOfRange {
+                index: -1.0,
+                length: 3,
+            },
+        );
+    }
+
+    #[test]
+    fn vector_ref_errors_on_out_of_range_indices() {
+        test_eval_err(
+            "(vector-ref (list->vector '(1 2 3)) 3)",
+            RuntimeErrorType::IndexOutOfRange {
+                index: 3.0,
+                length: 3,
+            },
+        );
+        test_eval_err(
+            "(vector-ref (list->vector '(1 2 3)) -1)",
+            RuntimeErrorType::IndexOutOfRange {
+                index: -1.0,
+                length: 3,
+            },
+        );
+    }
+
+    #[test]
+    fn list_to_vector_and_back_works() {
+        test_eval_success("(list->vector '(1 (2) \"three\"))", "#(1 (2) \"three\")");
+        test_eval_success("(vector->list (list->vector '(1 2 3)))", "(1 2 3)");
+        test_eval_success("(vector->list (list->vector '()))", "()");
+    }
+
+    #[test]
+    fn list_to_vector_rejects_cyclic_lists() {
+        test_eval_err(
+            "(define x (list 1 2)) (set-cdr! (cdr x) x) (list->vector x)",
+            RuntimeErrorType::ExpectedList,
+        );
+    }
+
+    #[test]
+    fn vector_copy_works() {
+        test_eval_successes(&[
+            ("(define v (list->vector '(1 2 3 4)))", ""),
+            ("(vector-copy v)", "#(1 2 3 4)"),
+            ("(eq? v (vector-copy v))", "#f"),
+            ("(vector-copy v 1)", "#(2 3 4)"),
+            ("(vector-copy v 1 3)", "#(2 3)"),
+            ("(vector-copy v 4)", "#()"),
+        ]);
+        test_eval_err(
+            "(vector-copy (list->vector '(1 2)) 2 1)",
+            RuntimeErrorType::IndexOutOfRange {
+                index: 2.0,
+                length: 1,
+            },
+        );
+        test_eval_err(
+            "(vector-copy (list->vector '(1 2)) 0 1 2)",
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+    }
+
+    #[test]
+    fn vector_append_works() {
+        test_eval_success("(vector-append)", "#()");
+        test_eval_success(
+            "(vector-append (list->vector '(1)) (list->vector '()) (list->vector '(2 3)))",
+            "#(1 2 3)",
+        );
+        test_eval_err(
+            "(vector-append (list->vector '(1)) '(2))",
+            RuntimeErrorType::WrongElementType {
+                index: 1,
+                expected: "vector",
+                actual: "pair",
+            },
+        );
+    }
+
+    #[test]
+    fn vector_copy_into_works() {
+        test_eval_successes(&[
+            ("(define v (list->vector '(1 2 3 4 5)))", ""),
+            ("(vector-copy! v 0 (list->vector '(a b)))", ""),
+            ("v", "#(a b 3 4 5)"),
+            ("(vector-copy! v 3 (list->vector '(x y z)) 1)", ""),
+            ("v", "#(a b 3 y z)"),
+            ("(vector-copy! v 5 (list->vector '(x y z)) 1 1)", ""),
+            ("v", "#(a b 3 y z)"),
+        ]);
+        test_eval_err(
+            "(vector-copy! (list->vector '(1 2)) 1 (list->vector '(a b)))",
+            RuntimeErrorType::IndexOutOfRange {
+                index: 1.0,
+                length: 2,
+            },
+        );
+        test_eval_err(
+            "(vector-copy! (list->vector '(1 2)) 0)",
+            RuntimeErrorType::WrongNumberOfArguments,
+        );
+    }
+
+    #[test]
+    fn vector_copy_into_handles_overlapping_ranges() {
+        test_eval_successes(&[
+            ("(define v (list->vector '(1 2 3 4 5)))", ""),
+            ("(vector-copy! v 1 v 0 3)", ""),
+            ("v", "#(1 1 2 3 5)"),
+            ("(vector-copy! v 0 v 2)", ""),
+            ("v", "#(2 3 5 3 5)"),
+        ]);
+    }
+
+    #[test]
+    fn vectors_work_with_equality() {
+        test_eval_successes(&[
+            ("(define v (list->vector '(1 (2))))", ""),
+            ("(eq? v v)", "#t"),
+            ("(eq? v (list->vector '(1 (2))))", "#f"),
+            ("(equal? v (list->vector '(1 (2))))", "#t"),
+            ("(equal? v (list->vector '(1 (3))))", "#f"),
+            ("(equal? v (list->vector '(1)))", "#f"),
+        ]);
+    }
+
+    #[test]
+    fn vectors_containing_themselves_can_be_displayed() {
+        test_eval_successes(&[
+            ("(define l (list 1))", ""),
+            ("(define v (list->vector (list l)))", ""),
+            ("(set-car! l v)", ""),
+            ("l", "(#(<CYCLIC LIST>))"),
+            ("v", "#((<CYCLIC VECTOR>))"),
+        ]);
+    }
+
+    #[test]
+    fn vector_copy_into_can_make_a_vector_contain_itself() {
+        test_eval_successes(&[
+            ("(define v (vector 1 2))", ""),
+            ("(vector-copy! v 0 (vector v))", ""),
+            ("v", "#(<CYCLIC VECTOR> 2)"),
+            ("(eq? (vector-ref v 0) v)", "#t"),
+        ]);
+    }
+
+    #[test]
+    fn vectors_appearing_more_than_once_are_not_cycles() {
+        test_eval_success(
+            "(define v (vector 1)) (list v (vector v v))",
+            "(#(1) #(#(1) #(1)))",
+        );
+    }
+
+    #[test]
+    fn vectors_are_garbage_collected() {
+        // Breaking the cycle through the pair is enough to free the vector via
+        // regular ref-counting.
+        test_eval_success(
+            "
+            (define x (list 1))
+            (define v (list->vector (list x)))
+            (set-car! x v)
+            (define x 0)
+            (define v 0)
+            (gc)
+            ",
+            "1",
+        );
+    }
+
+    #[test]
+    fn copied_vectors_are_garbage_collected() {
+        test_eval_success(
+            "
+            (define x (list 1))
+            (define v (vector-append (list->vector (list x)) (vector-copy (list->vector '(2)))))
+            (set-car! x v)
+            (define x 0)
+            (define v 0)
+            (gc)
+            ",
+            "1",
+        );
+    }
+}
@@ -81,7 +81,9 @@ impl Signature {
         }
     }
 
-    fn bind_args(&self, mut operands: Vec<SourceValue>, interpreter: &mut Interpreter) {
+    /// Defines each of the signature's names in the current scope. The caller
+    /// is responsible for ensuring that the arity is valid.
+    pub fn bind_args(&self, mut operands: Vec<SourceValue>, interpreter: &mut Interpreter) {
         match self {
             Signature::FixedArgs(arg_names) => {
                 for (name, value) in arg_names.iter().zip(operands) {
@@ -162,6 +164,11 @@ impl CompoundProcedure {
         self.id
     }
 
+    /// Returns the source range of the expression that defined the procedure.
+    pub fn source_range(&self) -> SourceRange {
+        self.body.0 .1
+    }
+
     pub fn call(
         &self,
         interpreter: &mut Interpreter,
@@ -174,7 +181,7 @@ impl CompoundProcedure {
         let body = &self.body.0 .0;
         self.signature.bind_args(operands, interpreter);
 
-        let result = interpreter.eval_expressions_in_tail_context(body)?;
+        let result = interpreter.eval_body_with_internal_defines(body)?;
 
         // Note that the environment won't have been popped if an error occured above--this is
         // so we can examine it afterwards, if needed. It's up to the caller to clean things
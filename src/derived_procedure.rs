@@ -0,0 +1,94 @@
+use std::rc::Rc;
+
+use crate::{
+    callable::{CallableResult, CallableSuccess, TailCallContext},
+    gc::{Traverser, Visitor},
+    interpreter::Interpreter,
+    procedure::Procedure,
+    source_mapped::SourceRange,
+    string_interner::InternedString,
+    value::SourceValue,
+};
+
+#[derive(Debug, Clone)]
+pub enum DerivedProcedureKind {
+    /// Applies the given procedures, of which there's at least one, from
+    /// right to left.
+    Composition(Rc<Vec<Procedure>>),
+    /// Applies the given procedure to the given leading arguments, followed
+    /// by whatever arguments it's called with.
+    PartialApplication(Rc<Procedure>, Rc<Vec<SourceValue>>),
+}
+
+/// A procedure that's created at runtime by a builtin, from other procedures.
+#[derive(Debug, Clone)]
+pub struct DerivedProcedure {
+    pub name: InternedString,
+    id: u32,
+    kind: DerivedProcedureKind,
+}
+
+impl DerivedProcedure {
+    pub fn new(name: InternedString, id: u32, kind: DerivedProcedureKind) -> Self {
+        DerivedProcedure { name, id, kind }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn is_valid_arity(&self, operands_len: usize) -> bool {
+        match &self.kind {
+            DerivedProcedureKind::Composition(procedures) => procedures
+                .last()
+                .expect("compositions have at least one procedure")
+                .is_valid_arity(operands_len),
+            DerivedProcedureKind::PartialApplication(procedure, args) => {
+                procedure.is_valid_arity(args.len() + operands_len)
+            }
+        }
+    }
+
+    pub fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        range: SourceRange,
+        operands: Vec<SourceValue>,
+    ) -> CallableResult {
+        match &self.kind {
+            DerivedProcedureKind::Composition(procedures) => {
+                let (first, rest) = procedures
+                    .split_first()
+                    .expect("compositions have at least one procedure");
+                let mut operands = operands;
+                for procedure in rest.iter().rev() {
+                    let result = interpreter.call_procedure(procedure.clone(), &operands, range)?;
+                    operands = vec![result];
+                }
+                // The leftmost procedure is called in tail position.
+                Ok(CallableSuccess::TailCall(TailCallContext {
+                    bound_procedure: first.clone().bind(range, &operands)?,
+                }))
+            }
+            DerivedProcedureKind::PartialApplication(procedure, args) => {
+                let mut all_operands = args.as_ref().clone();
+                all_operands.extend(operands);
+                Ok(CallableSuccess::TailCall(TailCallContext {
+                    bound_procedure: procedure.as_ref().clone().bind(range, &all_operands)?,
+                }))
+            }
+        }
+    }
+}
+
+impl Traverser for DerivedProcedure {
+    fn traverse(&self, visitor: &Visitor) {
+        match &self.kind {
+            DerivedProcedureKind::Composition(procedures) => visitor.traverse(procedures),
+            DerivedProcedureKind::PartialApplication(procedure, args) => {
+                visitor.traverse(procedure);
+                visitor.traverse(args);
+            }
+        }
+    }
+}
@@ -27,16 +27,15 @@ impl Scope {
         }
     }
 
-    fn change(&self, identifier: &InternedString, value: &SourceValue) -> bool {
-        if self.bindings.borrow_mut().contains_key(identifier) {
-            self.bindings
-                .borrow_mut()
-                .insert(identifier.clone(), value.clone());
-            true
+    /// Changes the value of an existing binding in this scope or its ancestors,
+    /// returning its previous value, or `None` if no binding exists.
+    fn change(&self, identifier: &InternedString, value: &SourceValue) -> Option<SourceValue> {
+        if let Some(previous) = self.bindings.borrow_mut().get_mut(identifier) {
+            Some(std::mem::replace(previous, value.clone()))
         } else {
             self.parent
                 .as_ref()
-                .map_or(false, |parent| parent.0.change(identifier, value))
+                .and_then(|parent| parent.0.change(identifier, value))
         }
     }
 
@@ -76,6 +75,21 @@ impl Traverser for Scope {
 #[derive(Debug, Clone)]
 pub struct CapturedLexicalScope(Option<Tracked<SourceMapped<Scope>>>);
 
+impl CapturedLexicalScope {
+    /// Returns a captured scope that represents the global environment.
+    pub fn global() -> Self {
+        CapturedLexicalScope(None)
+    }
+
+    pub fn points_at_same_memory_as(&self, other: &CapturedLexicalScope) -> bool {
+        match (&self.0, &other.0) {
+            (None, None) => true,
+            (Some(a), Some(b)) => std::ptr::eq(&**a, &**b),
+            _ => false,
+        }
+    }
+}
+
 impl Traverser for CapturedLexicalScope {
     fn traverse(&self, visitor: &Visitor) {
         if let Some(scope) = &self.0 {
@@ -88,6 +102,10 @@ impl Traverser for CapturedLexicalScope {
 pub struct Environment {
     globals: Scope,
     lexical_scopes: Vec<Tracked<SourceMapped<Scope>>>,
+    /// Lexical scopes that have been hidden by `push_isolated()`. We keep them
+    /// here, rather than letting the caller hold onto them, so that they're
+    /// still reachable during garbage collection.
+    suspended_lexical_scopes: Vec<Vec<Tracked<SourceMapped<Scope>>>>,
     tracker: ObjectTracker<SourceMapped<Scope>>,
 }
 
@@ -106,6 +124,11 @@ impl Environment {
 
     pub fn clear_lexical_scopes(&mut self) {
         self.lexical_scopes.clear();
+        self.suspended_lexical_scopes.clear();
+    }
+
+    pub fn lexical_scope_depth(&self) -> usize {
+        self.lexical_scopes.len()
     }
 
     pub fn capture_lexical_scope(&self) -> CapturedLexicalScope {
@@ -132,6 +155,26 @@ impl Environment {
         self.lexical_scopes.pop();
     }
 
+    /// Activate the given captured scope on its own, hiding all currently
+    /// active lexical scopes until `pop_isolated()` is called. The global
+    /// captured scope makes only globals visible.
+    pub fn push_isolated(&mut self, scope: CapturedLexicalScope) {
+        let suspended = std::mem::take(&mut self.lexical_scopes);
+        self.suspended_lexical_scopes.push(suspended);
+        if let Some(scope) = scope.0 {
+            self.lexical_scopes.push(scope);
+        }
+    }
+
+    /// Reactivate the lexical scopes that were hidden by the most recent call
+    /// to `push_isolated()`.
+    pub fn pop_isolated(&mut self) {
+        self.lexical_scopes = self
+            .suspended_lexical_scopes
+            .pop()
+            .expect("pop_isolated() called without matching push_isolated()!");
+    }
+
     pub fn get(&self, identifier: &InternedString) -> Option<SourceValue> {
         if let Some(scope) = self.lexical_scopes.last() {
             if let Some(value) = scope.0.get(identifier) {
@@ -141,24 +184,53 @@ impl Environment {
         self.globals.get(identifier)
     }
 
-    /// Attempt to change the value of an existing binding. Errors if no binding exists.
+    /// Attempt to change the value of an existing binding, returning its previous value.
+    ///
+    /// Errors if no binding exists. If the identifier is bound in some other lexical
+    /// scope that isn't accessible from the current one, the error says so.
     pub fn change(
         &mut self,
         identifier: &InternedString,
         value: SourceValue,
-    ) -> Result<(), RuntimeErrorType> {
+    ) -> Result<SourceValue, RuntimeErrorType> {
         if let Some(scope) = self.lexical_scopes.last_mut() {
-            if scope.0.change(identifier, &value) {
-                return Ok(());
+            if let Some(previous) = scope.0.change(identifier, &value) {
+                return Ok(previous);
             }
         }
-        if self.globals.change(identifier, &value) {
-            Ok(())
+        if let Some(previous) = self.globals.change(identifier, &value) {
+            Ok(previous)
+        } else if self.is_bound_in_any_lexical_scope(identifier) {
+            Err(RuntimeErrorType::InaccessibleVariable(identifier.clone()))
         } else {
             Err(RuntimeErrorType::UnboundVariable(identifier.clone()))
         }
     }
 
+    /// Returns whether the identifier is bound in any lexical scope that's still
+    /// alive, regardless of whether it's accessible from the current one.
+    fn is_bound_in_any_lexical_scope(&self, identifier: &InternedString) -> bool {
+        self.tracker
+            .all()
+            .iter()
+            .any(|scope| scope.0.bindings.borrow().contains_key(identifier))
+    }
+
+    /// Returns whether the identifier refers to a global binding (or would, if
+    /// one existed) from the current scope.
+    pub fn resolves_to_global(&self, identifier: &InternedString) -> bool {
+        match self.lexical_scopes.last() {
+            Some(scope) => scope.0.get(identifier).is_none(),
+            None => true,
+        }
+    }
+
+    /// Returns whether `define` would create a global binding from the
+    /// current scope.
+    pub fn is_at_global_scope(&self) -> bool {
+        self.lexical_scopes.is_empty()
+    }
+
     /// This works like the `define` Scheme builtin, which creates/sets the value at the
     /// current scope--it will *not* modify an existing binding in a parent lexical scope.
     pub fn define(&mut self, identifier: InternedString, value: SourceValue) {
@@ -169,10 +241,33 @@ impl Environment {
         }
     }
 
+    /// Returns the names of all globals that start with the given string.
     pub fn find_global_matches(&self, query: &str) -> Vec<String> {
+        self.find_globals(|name| name.starts_with(query))
+    }
+
+    /// Returns the names of all globals that contain the given string anywhere.
+    pub fn find_global_contains(&self, query: &str) -> Vec<String> {
+        self.find_globals(|name| name.contains(query))
+    }
+
+    /// Returns all global bindings, sorted by name.
+    pub fn globals(&self) -> Vec<(InternedString, SourceValue)> {
+        let mut results: Vec<(InternedString, SourceValue)> = self
+            .globals
+            .bindings
+            .borrow()
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        results.sort_by(|(a, _), (b, _)| a.as_ref().cmp(b.as_ref()));
+        results
+    }
+
+    fn find_globals<F: Fn(&str) -> bool>(&self, predicate: F) -> Vec<String> {
         let mut results = vec![];
         for key in self.globals.bindings.borrow().keys() {
-            if key.as_ref().starts_with(query) {
+            if predicate(key.as_ref()) {
                 results.push(key.as_ref().to_string())
             }
         }
@@ -184,5 +279,6 @@ impl Traverser for Environment {
     fn traverse(&self, visitor: &Visitor) {
         visitor.traverse(&self.globals);
         visitor.traverse(&self.lexical_scopes);
+        visitor.traverse(&self.suspended_lexical_scopes);
     }
 }
@@ -38,6 +38,11 @@ impl<T: Traverser> GCRootManager<T> {
     pub fn stats(&self) -> String {
         self.tracker.stats()
     }
+
+    /// Returns all the objects that are currently rooted.
+    pub fn all(&self) -> Vec<Tracked<GCRooted<T>>> {
+        self.tracker.all()
+    }
 }
 
 pub struct GCRooted<T: Traverser>(T);
@@ -0,0 +1,166 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use crate::{
+    gc::{Traverser, Visitor},
+    interpreter::RuntimeError,
+    object_tracker::{CycleBreaker, ObjectTracker, Tracked},
+    value::SourceValue,
+};
+
+/// Maps hashes to all the key-value pairs whose keys have that hash.
+type Buckets = HashMap<u64, Vec<(SourceValue, SourceValue)>>;
+
+#[derive(Debug, Default, Clone)]
+pub struct HashTableInner {
+    buckets: RefCell<Buckets>,
+}
+
+impl CycleBreaker for HashTableInner {
+    fn break_cycles(&self) {
+        self.buckets.borrow_mut().clear();
+    }
+
+    fn debug_name(&self) -> &'static str {
+        "HashTable"
+    }
+}
+
+impl Traverser for HashTableInner {
+    fn traverse(&self, visitor: &Visitor) {
+        for entries in self.buckets.borrow().values() {
+            for (key, value) in entries {
+                visitor.traverse(key);
+                visitor.traverse(value);
+            }
+        }
+    }
+}
+
+/// A hash table. It doesn't know how to hash or compare keys itself, so
+/// callers need to provide the hash of every key, along with a function
+/// that determines whether an existing key is the one being looked for.
+#[derive(Debug, Clone)]
+pub struct HashTable(Tracked<HashTableInner>);
+
+impl HashTable {
+    fn as_ptr(&self) -> *const HashTableInner {
+        &*self.0 as *const HashTableInner
+    }
+
+    pub fn points_at_same_memory_as(&self, other: &HashTable) -> bool {
+        self.as_ptr() == other.as_ptr()
+    }
+
+    /// Returns the number of entries in the hash table.
+    pub fn len(&self) -> usize {
+        self.0
+            .buckets
+            .borrow()
+            .values()
+            .map(|entries| entries.len())
+            .sum()
+    }
+
+    fn find_index<F>(&self, hash: u64, mut is_key: F) -> Result<Option<usize>, RuntimeError>
+    where
+        F: FnMut(&SourceValue) -> Result<bool, RuntimeError>,
+    {
+        // Clone the bucket, so that the key comparison function is free to do
+        // whatever it wants (including mutating this hash table).
+        let Some(entries) = self.0.buckets.borrow().get(&hash).cloned() else {
+            return Ok(None);
+        };
+        for (i, (key, _value)) in entries.iter().enumerate() {
+            if is_key(key)? {
+                return Ok(Some(i));
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn get<F>(&self, hash: u64, is_key: F) -> Result<Option<SourceValue>, RuntimeError>
+    where
+        F: FnMut(&SourceValue) -> Result<bool, RuntimeError>,
+    {
+        let Some(index) = self.find_index(hash, is_key)? else {
+            return Ok(None);
+        };
+        Ok(Some(self.0.buckets.borrow()[&hash][index].1.clone()))
+    }
+
+    pub fn set<F>(
+        &self,
+        hash: u64,
+        is_key: F,
+        key: SourceValue,
+        value: SourceValue,
+    ) -> Result<(), RuntimeError>
+    where
+        F: FnMut(&SourceValue) -> Result<bool, RuntimeError>,
+    {
+        let index = self.find_index(hash, is_key)?;
+        let mut buckets = self.0.buckets.borrow_mut();
+        let entries = buckets.entry(hash).or_default();
+        match index {
+            Some(index) => entries[index].1 = value,
+            None => entries.push((key, value)),
+        }
+        Ok(())
+    }
+
+    /// Returns all the key-value pairs in the hash table, in no particular
+    /// order.
+    pub fn entries(&self) -> Vec<(SourceValue, SourceValue)> {
+        self.0
+            .buckets
+            .borrow()
+            .values()
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    /// Removes the entry with the given key, returning whether it existed.
+    pub fn delete<F>(&self, hash: u64, is_key: F) -> Result<bool, RuntimeError>
+    where
+        F: FnMut(&SourceValue) -> Result<bool, RuntimeError>,
+    {
+        let Some(index) = self.find_index(hash, is_key)? else {
+            return Ok(false);
+        };
+        let mut buckets = self.0.buckets.borrow_mut();
+        let entries = buckets.get_mut(&hash).unwrap();
+        entries.remove(index);
+        if entries.is_empty() {
+            buckets.remove(&hash);
+        }
+        Ok(true)
+    }
+}
+
+impl Traverser for HashTable {
+    fn traverse(&self, visitor: &Visitor) {
+        visitor.traverse(&self.0);
+    }
+}
+
+#[derive(Default)]
+pub struct HashTableManager(ObjectTracker<HashTableInner>);
+
+impl HashTableManager {
+    pub fn hash_table(&mut self) -> HashTable {
+        HashTable(self.0.track(HashTableInner::default()))
+    }
+
+    pub fn get_stats_as_string(&self) -> String {
+        format!("Hash tables: {}", self.0.stats())
+    }
+
+    pub fn begin_mark(&mut self) {
+        self.0.begin_mark();
+    }
+
+    pub fn sweep(&mut self) -> usize {
+        self.0.sweep()
+    }
+}
@@ -1,44 +1,111 @@
-use std::{ops::Deref, sync::mpsc::Receiver};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{BufWriter, Write},
+    ops::Deref,
+    sync::mpsc::Receiver,
+    time::Instant,
+};
+
+use colored::Colorize;
 
 use crate::{
+    bound_procedure::BoundProcedure,
     builtins::{self, add_library_source},
     callable::{Callable, CallableResult, CallableSuccess, TailCallContext},
     environment::Environment,
     gc::Visitor,
     gc_rooted::GCRootManager,
+    hash_table::HashTableManager,
     pair::PairManager,
-    parser::{parse, ParseError, ParseErrorType},
+    parser::{ParseError, ParseErrorType, Parser, DEFAULT_MAX_PARSE_DEPTH},
+    procedure::Procedure,
+    record::RecordManager,
     source_mapped::{SourceMappable, SourceMapped, SourceRange},
     source_mapper::{SourceId, SourceMapper},
     special_form::SpecialFormContext,
     stdio_printer::StdioPrinter,
     string_interner::{InternedString, StringInterner},
+    tokenizer::Tokenizer,
     tracked_stats::TrackedStats,
     value::{SourceValue, Value},
+    vector::VectorManager,
 };
 
 const DEFAULT_MAX_STACK_SIZE: usize = 128;
 
+/// Once the call stack reaches this percentage of its maximum size, we
+/// warn the user that they might be heading for a stack overflow.
+const STACK_DEPTH_WARNING_PERCENTAGE: usize = 90;
+
+/// Runs of at least this many identical frames in a traceback are collapsed
+/// into a single frame followed by a summary.
+const MIN_COLLAPSED_TRACEBACK_FRAMES: usize = 3;
+
 #[derive(Debug, PartialEq)]
 pub enum RuntimeErrorType {
     Parse(ParseErrorType),
     UnboundVariable(InternedString),
+    InaccessibleVariable(InternedString),
     MalformedExpression,
     MalformedSpecialForm,
     MalformedBindingList,
     ExpectedNumber,
+    ExpectedBoolean,
+    ExpectedInteger,
+    ExpectedNonNegativeInteger,
     ExpectedCallable,
     ExpectedProcedure,
+    /// Contains the name of the special form that was used as a procedure.
+    SpecialFormIsNotProcedure(InternedString),
     ExpectedIdentifier,
     ExpectedPair,
     ExpectedList,
+    ExpectedString,
+    ExpectedCharacter,
+    ExpectedVector,
+    ExpectedHashTable,
+    ExpectedEnvironment,
+    ExpectedInputPort,
+    ExpectedOutputPort,
+    ExpectedRecord(InternedString),
+    IndexOutOfRange {
+        index: f64,
+        length: usize,
+    },
+    WrongElementType {
+        index: usize,
+        expected: &'static str,
+        actual: &'static str,
+    },
+    InvalidRadix,
+    /// Contains the requested size and the largest size allowed.
+    SizeTooLarge {
+        size: f64,
+        max: usize,
+    },
+    /// Contains the integer and the largest magnitude allowed.
+    IntegerTooLarge {
+        integer: f64,
+        max: f64,
+    },
     WrongNumberOfArguments,
     DuplicateParameter,
     DuplicateVariableInBindings,
     StackOverflow,
+    TooManyTailCalls,
     KeyboardInterrupt,
     DivisionByZero,
-    AssertionFailure,
+    NoMatchingPattern,
+    KeyNotFound,
+    MultipleValuesAsOperand,
+    /// Contains the source code of the expression that failed.
+    AssertionFailure(String),
+    /// Contains the name of the file that couldn't be read.
+    FileReadFailed(String),
+    /// Contains the representation of the object that was raised.
+    UncaughtException(String),
+    ExceptionHandlerReturned,
 }
 
 pub type RuntimeError = SourceMapped<RuntimeErrorType>;
@@ -53,14 +120,49 @@ pub struct Interpreter {
     pub environment: Environment,
     pub string_interner: StringInterner,
     pub pair_manager: PairManager,
+    pub record_manager: RecordManager,
+    pub vector_manager: VectorManager,
+    pub hash_table_manager: HashTableManager,
     pub source_mapper: SourceMapper,
     pub tracing: bool,
+    /// If set, tracing output is written here instead of being printed. It is
+    /// flushed whenever evaluation finishes.
+    pub trace_file: Option<BufWriter<File>>,
+    /// Whether `set!` returns the previous value of the variable it changes,
+    /// as some Schemes do, rather than an undefined value.
+    pub set_returns_previous_value: bool,
+    /// Whether errors and tracebacks are shown in color. Even when set, the
+    /// `NO_COLOR` environment variable and non-terminal output disable it.
+    pub colorize_errors: bool,
+    /// Whether to print a warning when `define` or `set!` rebinds the global
+    /// name of a builtin, e.g. `(define + -)`.
+    pub warn_on_builtin_redefinition: bool,
+    /// The names of the builtins the interpreter was constructed with.
+    builtin_names: HashSet<InternedString>,
     pub max_stack_size: usize,
+    /// The maximum number of lists and quotes that source code can nest
+    /// inside each other.
+    pub max_parse_depth: usize,
+    /// If set, the maximum number of tail calls that can be made in a row
+    /// before we give up, which keeps runaway tail loops from hanging (e.g.
+    /// in tests).
+    pub max_tail_iterations: Option<usize>,
     pub keyboard_interrupt_channel: Option<Receiver<()>>,
+    /// Whether non-standard builtins and syntax are disabled. This can only
+    /// be set at construction time, since it affects which builtins exist.
+    strict_r5rs: bool,
+    /// Whether identifiers are lowercased when parsed, so that symbols are
+    /// case-insensitive.
+    pub fold_case: bool,
     pub printer: StdioPrinter,
     pub failed_tests: usize,
+    /// The handlers installed by `with-exception-handler`, innermost last.
+    pub exception_handlers: Vec<Procedure>,
     tracked_stats: Option<TrackedStats>,
+    /// The stats from the most recent call to `track-stats`.
+    pub last_tracked_stats: Option<TrackedStats>,
     has_evaluated_library: bool,
+    has_warned_about_stack_depth: bool,
     next_id: u32,
     stack: Vec<SourceRange>,
     stack_traversal_root: GCRootManager<SourceValue>,
@@ -68,26 +170,52 @@ pub struct Interpreter {
 
 impl Interpreter {
     pub fn new() -> Self {
+        Self::with_strict_r5rs(false)
+    }
+
+    /// Creates an interpreter that only supports standard R5RS builtins and
+    /// syntax, for writing portable programs.
+    pub fn new_strict_r5rs() -> Self {
+        Self::with_strict_r5rs(true)
+    }
+
+    fn with_strict_r5rs(strict_r5rs: bool) -> Self {
         let source_mapper = SourceMapper::default();
         let mut string_interner = StringInterner::default();
         let pair_manager = PairManager::default();
         let mut environment = Environment::default();
-        builtins::populate_environment(&mut environment, &mut string_interner);
+        let builtin_names =
+            builtins::populate_environment(&mut environment, &mut string_interner, strict_r5rs);
         Interpreter {
             environment,
             string_interner,
             pair_manager,
+            record_manager: RecordManager::default(),
+            vector_manager: VectorManager::default(),
+            hash_table_manager: HashTableManager::default(),
             source_mapper,
             tracing: false,
+            trace_file: None,
+            set_returns_previous_value: false,
+            colorize_errors: false,
+            warn_on_builtin_redefinition: false,
+            builtin_names,
             max_stack_size: DEFAULT_MAX_STACK_SIZE,
+            max_parse_depth: DEFAULT_MAX_PARSE_DEPTH,
+            max_tail_iterations: None,
             keyboard_interrupt_channel: None,
+            strict_r5rs,
+            fold_case: false,
             next_id: 1,
             stack: vec![],
             stack_traversal_root: GCRootManager::default(),
             has_evaluated_library: false,
+            has_warned_about_stack_depth: false,
             tracked_stats: None,
+            last_tracked_stats: None,
             printer: StdioPrinter::new(),
             failed_tests: 0,
+            exception_handlers: vec![],
         }
     }
 
@@ -100,6 +228,12 @@ impl Interpreter {
     pub fn print_stats(&self) {
         self.printer
             .println(self.pair_manager.get_stats_as_string());
+        self.printer
+            .println(self.record_manager.get_stats_as_string());
+        self.printer
+            .println(self.vector_manager.get_stats_as_string());
+        self.printer
+            .println(self.hash_table_manager.get_stats_as_string());
         self.printer.println(self.environment.get_stats_as_string());
         self.printer.println(format!(
             "Objects in call stack: {}",
@@ -109,15 +243,69 @@ impl Interpreter {
             .println(format!("Interned strings: {}", self.string_interner.len()));
     }
 
+    /// Returns the number of values of each type that are rooted because
+    /// they're part of the code currently being evaluated, sorted by type name.
+    pub fn rooted_value_counts(&self) -> Vec<(&'static str, usize)> {
+        let mut counts = HashMap::new();
+        for rooted in self.stack_traversal_root.all() {
+            *counts.entry(rooted.0.type_name()).or_insert(0) += 1;
+        }
+        let mut counts: Vec<_> = counts.into_iter().collect();
+        counts.sort();
+        counts
+    }
+
+    /// Returns a representation of the given value like the one provided by its
+    /// `Display` implementation, but with extra information that requires access
+    /// to the interpreter, such as where compound procedures were defined.
+    pub fn repr_value(&self, value: &SourceValue) -> String {
+        if let Value::Callable(Callable::Procedure(Procedure::Compound(compound))) = &value.0 {
+            if let Some(line) = self.source_mapper.get_first_line(&compound.source_range()) {
+                let repr = value.to_string();
+                let repr = repr.strip_suffix('>').unwrap_or(&repr);
+                return format!("{repr} at {}>", line.location());
+            }
+        }
+        value.to_string()
+    }
+
     pub fn show_err_and_traceback(&self, err: RuntimeError) {
-        self.printer.eprintln(format!(
-            "Error: {:?} in {}",
-            err.0,
-            self.source_mapper.trace(&err.1).join("\n")
-        ));
+        self.printer.eprintln(self.describe_err(&err));
         self.printer.eprintln(self.traceback());
     }
 
+    fn describe_err(&self, err: &RuntimeError) -> String {
+        let label = if self.colorize_errors {
+            "Error:".red().to_string()
+        } else {
+            "Error:".to_string()
+        };
+        format!(
+            "{label} {:?} in {}",
+            err.0,
+            self.source_mapper
+                .trace_with_color(&err.1, self.colorize_errors)
+                .join("\n")
+        )
+    }
+
+    /// Returns whether the given name is one the interpreter defined itself,
+    /// either natively or in the standard library.
+    pub fn is_builtin_name(&self, name: &InternedString) -> bool {
+        self.builtin_names.contains(name)
+    }
+
+    /// Called when the global binding for the given name is about to be
+    /// changed, to warn the user if it shadows a builtin.
+    pub fn maybe_warn_about_builtin_redefinition(&self, name: &InternedString) {
+        if self.warn_on_builtin_redefinition && self.is_builtin_name(name) {
+            self.printer.println(format!(
+                "Warning: redefining builtin '{}'. This will affect any code that uses it.",
+                name
+            ));
+        }
+    }
+
     fn expect_callable(&mut self, expression: &SourceValue) -> Result<Callable, RuntimeError> {
         if let Value::Callable(callable) = self.eval_expression(&expression)?.0 {
             Ok(callable)
@@ -126,6 +314,110 @@ impl Interpreter {
         }
     }
 
+    /// Pushes a frame for the given procedure onto the call stack, raising an
+    /// error if the stack is already full.
+    fn push_stack_frame(
+        &mut self,
+        procedure: &Procedure,
+        frame_source_range: SourceRange,
+        combination_source_range: SourceRange,
+    ) -> Result<(), RuntimeError> {
+        if self.stack.len() >= self.max_stack_size {
+            return Err(RuntimeErrorType::StackOverflow.source_mapped(combination_source_range));
+        }
+        self.stack.push(frame_source_range);
+        self.maybe_warn_about_stack_depth();
+        if let Some(ref mut stats) = &mut self.tracked_stats {
+            stats.update_call_stack_depth(self.stack.len());
+            stats.track_call(procedure.name());
+        }
+        Ok(())
+    }
+
+    /// Returns the number of frames currently on the call stack.
+    pub fn stack_depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Discards any frames above the given depth, e.g. ones left behind by
+    /// an error, so that a builtin can keep calling procedures afterwards.
+    pub fn truncate_stack(&mut self, depth: usize) {
+        self.stack.truncate(depth);
+    }
+
+    /// Returns an error if the user has pressed CTRL-C since we last checked.
+    ///
+    /// Builtins that can loop for a long time without calling back into the
+    /// interpreter should call this periodically, so they can be interrupted.
+    pub fn check_interrupt(&self, range: SourceRange) -> Result<(), RuntimeError> {
+        if let Some(channel) = &self.keyboard_interrupt_channel {
+            if channel.try_recv().is_ok() {
+                return Err(RuntimeErrorType::KeyboardInterrupt.source_mapped(range));
+            }
+        }
+        Ok(())
+    }
+
+    /// Warns the user, at most once per evaluation, if the call stack is getting
+    /// close to overflowing.
+    fn maybe_warn_about_stack_depth(&mut self) {
+        if self.has_warned_about_stack_depth
+            || self.stack.len() * 100 < self.max_stack_size * STACK_DEPTH_WARNING_PERCENTAGE
+        {
+            return;
+        }
+        self.has_warned_about_stack_depth = true;
+        self.printer.eprintln(format!(
+            "Warning: call stack depth has reached {} of a maximum of {}. This may be due to \
+            unbounded recursion, or a recursive call that isn't in a tail position.",
+            self.stack.len(),
+            self.max_stack_size
+        ));
+    }
+
+    /// Calls the given procedure with the given (already-evaluated) operands,
+    /// evaluating any tail calls it makes, and returns the final result.
+    ///
+    /// This is intended for use by builtins that need to call procedures that
+    /// were passed to them.
+    pub fn call_procedure(
+        &mut self,
+        procedure: Procedure,
+        operands: &[SourceValue],
+        range: SourceRange,
+    ) -> Result<SourceValue, RuntimeError> {
+        self.push_stack_frame(&procedure, range, range)?;
+        let bound = procedure.bind(range, operands)?;
+        let result = self.call_bound_procedure(bound)?;
+        // Note that the stack won't unwind if an error occured above, see `eval_callable()`.
+        self.stack.pop();
+        self.eval_tail_calls(result, range)
+    }
+
+    /// Calls the given bound procedure, timing it if we're profiling.
+    ///
+    /// Note that the timing only covers the procedure's own evaluation, up to the
+    /// point at which it returns a value or a tail call.
+    fn call_bound_procedure(&mut self, bound: BoundProcedure) -> CallableResult {
+        let is_profiling = self
+            .tracked_stats
+            .as_ref()
+            .is_some_and(|stats| stats.is_profiling());
+        if !is_profiling {
+            return bound.call(self);
+        }
+        let name = bound.name().cloned();
+        if let Some(stats) = &mut self.tracked_stats {
+            stats.begin_timing(name.as_ref());
+        }
+        let start = Instant::now();
+        let result = bound.call(self);
+        if let Some(stats) = &mut self.tracked_stats {
+            stats.end_timing(name.as_ref(), start.elapsed());
+        }
+        result
+    }
+
     fn eval_callable(
         &mut self,
         callable: Callable,
@@ -143,18 +435,9 @@ impl Interpreter {
                 (special_form.func)(ctx)
             }
             Callable::Procedure(procedure) => {
-                if self.stack.len() >= self.max_stack_size {
-                    return Err(
-                        RuntimeErrorType::StackOverflow.source_mapped(combination_source_range)
-                    );
-                }
-                self.stack.push(operator_source_range);
-                if let Some(ref mut stats) = &mut self.tracked_stats {
-                    stats.update_call_stack_depth(self.stack.len());
-                    stats.track_call(procedure.name());
-                }
+                self.push_stack_frame(&procedure, operator_source_range, combination_source_range)?;
                 let bound = procedure.eval_and_bind(self, combination_source_range, operands)?;
-                let result = bound.call(self)?;
+                let result = self.call_bound_procedure(bound)?;
                 // Note that the stack won't unwind if an error occured above--this is so we can get a stack trace
                 // afterwards. It's up to the caller to clean things up after an error.
                 self.stack.pop();
@@ -170,7 +453,12 @@ impl Interpreter {
         match &expression.0 {
             Value::Pair(pair) => {
                 let Some(expressions) = pair.try_as_rc_list() else {
-                    return Err(RuntimeErrorType::MalformedExpression.source_mapped(expression.1));
+                    // If the combination is an improper list, point at the part that
+                    // makes it improper, rather than the whole thing.
+                    let range = pair
+                        .try_get_improper_tail()
+                        .map_or(expression.1, |tail| tail.1);
+                    return Err(RuntimeErrorType::MalformedExpression.source_mapped(range));
                 };
                 // Unwrap b/c it's from a pair, guaranteed not to be an empty list.
                 let operator = expressions.get(0).unwrap();
@@ -198,12 +486,21 @@ impl Interpreter {
 
     fn lazy_eval_expression(&mut self, expression: &SourceValue) -> CallableResult {
         match &expression.0 {
-            Value::EmptyList | Value::Callable(_) => {
+            Value::EmptyList
+            | Value::Callable(_)
+            | Value::Record(_)
+            | Value::HashTable(_)
+            | Value::MultipleValues(_)
+            | Value::Port(_)
+            | Value::Environment(_) => {
                 Err(RuntimeErrorType::MalformedExpression.source_mapped(expression.1))
             }
             Value::Undefined => Ok(Value::Undefined.into()),
+            Value::EofObject => Ok(Value::EofObject.into()),
             Value::Number(number) => Ok(Value::Number(*number).into()),
             Value::Boolean(boolean) => Ok(Value::Boolean(*boolean).into()),
+            Value::Character(char) => Ok(Value::Character(*char).into()),
+            Value::Vector(vector) => Ok(Value::Vector(vector.clone()).into()),
             Value::String(string) => Ok(Value::String(string.clone()).into()),
             Value::Symbol(identifier) => {
                 if let Some(value) = self.environment.get(identifier) {
@@ -223,7 +520,7 @@ impl Interpreter {
                 let combination = SourceMapped(&expressions, expression.1);
                 let operands = &expressions[1..];
                 if self.tracing {
-                    self.printer.println(format!(
+                    self.trace(format!(
                         "Evaluating callable {}",
                         self.source_mapper.trace(&combination.1).join("\n")
                     ));
@@ -233,32 +530,55 @@ impl Interpreter {
         }
     }
 
+    /// Outputs the given line of tracing information.
+    fn trace(&mut self, line: String) {
+        match &mut self.trace_file {
+            // There's not much we can do if writing fails, and it's better
+            // than aborting whatever is being traced.
+            Some(file) => {
+                let _ = writeln!(file, "{line}");
+            }
+            None => self.printer.println(line),
+        }
+    }
+
     pub fn eval_expression(
         &mut self,
         expression: &SourceValue,
     ) -> Result<SourceValue, RuntimeError> {
-        let mut result = self.lazy_eval_expression(expression)?;
+        let result = self.lazy_eval_expression(expression)?;
+        self.eval_tail_calls(result, expression.1)
+    }
+
+    /// Keeps evaluating the given result until it's no longer a tail call.
+    fn eval_tail_calls(
+        &mut self,
+        mut result: CallableSuccess,
+        range: SourceRange,
+    ) -> Result<SourceValue, RuntimeError> {
+        let mut iterations: usize = 0;
         loop {
-            if let Some(channel) = &self.keyboard_interrupt_channel {
-                if channel.try_recv().is_ok() {
-                    return Err(RuntimeErrorType::KeyboardInterrupt.source_mapped(expression.1));
-                }
-            }
+            self.check_interrupt(range)?;
             match result {
                 CallableSuccess::Value(value) => return Ok(value),
                 CallableSuccess::TailCall(tail_call_context) => {
+                    iterations += 1;
+                    if self.max_tail_iterations.is_some_and(|max| iterations > max) {
+                        return Err(RuntimeErrorType::TooManyTailCalls
+                            .source_mapped(tail_call_context.bound_procedure.range));
+                    }
                     if let Some(ref mut stats) = &mut self.tracked_stats {
                         stats.track_tail_call(tail_call_context.bound_procedure.name())
                     }
                     if self.tracing {
-                        self.printer.println(format!(
+                        self.trace(format!(
                             "Evaluating tail call {}",
                             self.source_mapper
                                 .trace(&tail_call_context.bound_procedure.range)
                                 .join("\n")
                         ));
                     }
-                    result = tail_call_context.bound_procedure.call(self)?;
+                    result = self.call_bound_procedure(tail_call_context.bound_procedure)?;
                 }
             }
         }
@@ -280,6 +600,41 @@ impl Interpreter {
         self.eval_expression_in_tail_context(last_expression)
     }
 
+    /// Evaluates the body of a procedure, `let`, or similar construct in the
+    /// current lexical scope.
+    ///
+    /// Any internal definitions in the body are bound (to an undefined value)
+    /// before anything is evaluated, as with `letrec*`, so they shadow outer
+    /// bindings throughout the whole body rather than only after they run.
+    pub fn eval_body_with_internal_defines(
+        &mut self,
+        body: &[SourceValue],
+    ) -> Result<CallableSuccess, RuntimeError> {
+        for expression in body {
+            if let Some(name) = self.get_internal_define_name(expression) {
+                self.environment.define(name, Value::Undefined.into());
+            }
+        }
+        self.eval_expressions_in_tail_context(body)
+    }
+
+    /// If the given expression is a `define`, returns the name it defines.
+    fn get_internal_define_name(&self, expression: &SourceValue) -> Option<InternedString> {
+        let list = expression.try_into_list()?;
+        match list.0.as_slice() {
+            [SourceMapped(Value::Symbol(keyword), _), target, ..]
+                if keyword.as_ref() == "define" =>
+            {
+                match &target.0 {
+                    Value::Symbol(name) => Some(name.clone()),
+                    Value::Pair(pair) => pair.car().expect_identifier().ok(),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
     pub fn eval_expressions(
         &mut self,
         expressions: &[SourceValue],
@@ -293,12 +648,16 @@ impl Interpreter {
 
     pub fn parse(&mut self, source_id: SourceId) -> Result<Vec<SourceValue>, ParseError> {
         let code = self.source_mapper.get_contents(source_id);
-        parse(
+        Parser::new(
             code,
+            Tokenizer::new(&code, Some(source_id)),
             &mut self.string_interner,
             &mut self.pair_manager,
-            Some(source_id),
         )
+        .with_strict_r5rs(self.strict_r5rs)
+        .with_fold_case(self.fold_case)
+        .with_max_depth(self.max_parse_depth)
+        .parse_all()
     }
 
     pub fn evaluate(&mut self, source_id: SourceId) -> Result<SourceValue, RuntimeError> {
@@ -306,6 +665,9 @@ impl Interpreter {
             let library_source_id = add_library_source(&mut self.source_mapper);
             self.evaluate_source_id(library_source_id)?;
             self.has_evaluated_library = true;
+            for (name, _) in self.environment.globals() {
+                self.builtin_names.insert(name);
+            }
         }
         self.evaluate_source_id(source_id)
     }
@@ -316,18 +678,40 @@ impl Interpreter {
         // alternatively, make this method re-entrant).
         self.stack.clear();
         self.environment.clear_lexical_scopes();
-        match self.parse(source_id) {
+        self.has_warned_about_stack_depth = false;
+        let result = match self.parse(source_id) {
             Ok(expressions) => {
-                let mut last_value: SourceValue = Value::Undefined.into();
+                let mut result = Ok(Value::Undefined.into());
                 for expression in self.stack_traversal_root.root_many(expressions) {
-                    last_value = self.eval_expression(expression.deref())?;
+                    result = self.eval_expression(expression.deref());
+                    if result.is_err() {
+                        break;
+                    }
                 }
-                Ok(last_value)
+                result
             }
             Err(err) => Err(err.into()),
+        };
+        if let Some(file) = &mut self.trace_file {
+            let _ = file.flush();
         }
+        result
+    }
+
+    /// Resets any state that only makes sense while code is being evaluated,
+    /// so that an error partway through one evaluation (e.g. a REPL line)
+    /// doesn't leave stale call stack or scope information behind for the next.
+    pub fn reset_transient_state(&mut self) {
+        self.stack.clear();
+        self.environment.clear_lexical_scopes();
+        self.tracked_stats = None;
+        self.failed_tests = 0;
+        self.exception_handlers.clear();
     }
 
+    /// Returns a description of the call stack. Runs of identical
+    /// consecutive frames, e.g. from deep non-tail recursion, are summarized
+    /// rather than shown in full.
     pub fn traceback(&self) -> String {
         if self.stack.is_empty() {
             return "".to_string();
@@ -336,10 +720,30 @@ impl Interpreter {
         let mut lines =
             vec!["Traceback (excluding tail calls, most recent call last):".to_string()];
 
-        for source_range in self.stack.iter() {
-            for line in self.source_mapper.trace(source_range) {
-                lines.push(format!("  {}", line));
+        let mut i = 0;
+        while i < self.stack.len() {
+            let source_range = &self.stack[i];
+            let run_length = self.stack[i..]
+                .iter()
+                .take_while(|&other| other == source_range)
+                .count();
+            let shown = if run_length >= MIN_COLLAPSED_TRACEBACK_FRAMES {
+                1
+            } else {
+                run_length
+            };
+            for _ in 0..shown {
+                for line in self
+                    .source_mapper
+                    .trace_with_color(source_range, self.colorize_errors)
+                {
+                    lines.push(format!("  {}", line));
+                }
             }
+            if shown < run_length {
+                lines.push(format!("  ... ({} frames) ...", run_length - shown));
+            }
+            i += run_length;
         }
 
         lines.join("\n")
@@ -359,20 +763,30 @@ impl Interpreter {
         visitor.debug = debug;
         self.environment.begin_mark();
         self.pair_manager.begin_mark();
+        self.record_manager.begin_mark();
+        self.vector_manager.begin_mark();
+        self.hash_table_manager.begin_mark();
         visitor.traverse(&self.environment);
         visitor.traverse(&self.stack_traversal_root);
         let env_cycles = self.environment.sweep();
         let pair_cycles = self.pair_manager.sweep();
+        let record_cycles = self.record_manager.sweep();
+        let vector_cycles = self.vector_manager.sweep();
+        let hash_table_cycles = self.hash_table_manager.sweep();
         if visitor.debug {
             self.printer.println(format!(
-                "Lexical scopes reclaimed: {env_cycles}\nPairs reclaimed: {pair_cycles}",
+                "Lexical scopes reclaimed: {env_cycles}\nPairs reclaimed: {pair_cycles}\nRecords reclaimed: {record_cycles}\nVectors reclaimed: {vector_cycles}\nHash tables reclaimed: {hash_table_cycles}",
             ));
         }
-        env_cycles + pair_cycles
+        env_cycles + pair_cycles + record_cycles + vector_cycles + hash_table_cycles
     }
 
-    pub fn start_tracking_stats(&mut self) {
-        self.tracked_stats = Some(TrackedStats::default())
+    pub fn start_tracking_stats(&mut self, profiling: bool) {
+        self.tracked_stats = Some(if profiling {
+            TrackedStats::with_profiling()
+        } else {
+            TrackedStats::default()
+        })
     }
 
     pub fn take_tracked_stats(&mut self) -> Option<TrackedStats> {
@@ -382,7 +796,14 @@ impl Interpreter {
 
 #[cfg(test)]
 mod tests {
-    use crate::test_util::test_eval_success;
+    use std::io::BufWriter;
+
+    use crate::{
+        interpreter::{Interpreter, RuntimeErrorType},
+        parser::{ParseErrorType, DEFAULT_MAX_PARSE_DEPTH},
+        test_util::{test_eval_success, TestInterpreter},
+        tokenizer::TokenType,
+    };
 
     #[test]
     fn trivial_expressions_work() {
@@ -397,6 +818,190 @@ mod tests {
         test_eval_success("(quote (1 2 . 3))", "(1 2 . 3)");
     }
 
+    #[test]
+    fn strict_r5rs_mode_disables_non_standard_features() {
+        let mut interpreter = TestInterpreter::new(Interpreter::new());
+        interpreter.eval_success("(eq? gc gc)", "#t");
+        interpreter.eval_success("(list #!void)", "(#!void)");
+
+        let mut interpreter = TestInterpreter::new(Interpreter::new_strict_r5rs());
+        let gc = interpreter.intern("gc");
+        interpreter.eval_err("gc", RuntimeErrorType::UnboundVariable(gc));
+        interpreter.eval_err(
+            "#!void",
+            RuntimeErrorType::Parse(ParseErrorType::Unexpected(TokenType::Undefined)),
+        );
+        interpreter.eval_success("(+ 1 2)", "3");
+    }
+
+    #[test]
+    fn deeply_nested_data_can_be_evaluated_and_printed() {
+        // Leave room for the enclosing list and quote.
+        let depth = DEFAULT_MAX_PARSE_DEPTH - 2;
+        let nested = format!("{}{}", "(".repeat(depth), ")".repeat(depth));
+        let code = format!("(define x '{nested}) (gc) (equal? x '{nested}) x");
+        let mut interpreter = Interpreter::new();
+        let source_id = interpreter.source_mapper.add("<code>".into(), code);
+        let value = interpreter.evaluate(source_id).unwrap();
+        assert_eq!(value.to_string(), nested);
+    }
+
+    #[test]
+    fn fold_case_mode_makes_symbols_case_insensitive() {
+        test_eval_success("(eq? 'Foo 'foo)", "#f");
+
+        let mut interpreter = Interpreter::new();
+        interpreter.fold_case = true;
+        let mut interpreter = TestInterpreter::new(interpreter);
+        interpreter.eval_success("(eq? 'Foo 'foo)", "#t");
+        interpreter.eval_success("(DEFINE X 5) (+ x 1)", "6");
+        interpreter.eval_success("'Foo", "foo");
+        interpreter.eval_success("\"Foo\"", "\"Foo\"");
+        interpreter.eval_success("#\\A", "#\\A");
+        interpreter.eval_success("(symbol-append 'a 'B)", "ab");
+    }
+
+    #[test]
+    fn redefining_builtins_can_warn() {
+        let mut interpreter = TestInterpreter::new(Interpreter::new());
+        interpreter.eval_success("(define car cdr)", "");
+
+        let mut interpreter = Interpreter::new();
+        interpreter.warn_on_builtin_redefinition = true;
+        let mut interpreter = TestInterpreter::new(interpreter);
+        interpreter.eval_success(
+            "(define + -) (+ 5 3)",
+            "Warning: redefining builtin '+'. This will affect any code that uses it.\n2",
+        );
+        interpreter.eval_success(
+            "(set! * +)",
+            "Warning: redefining builtin '*'. This will affect any code that uses it.\n",
+        );
+        interpreter.eval_success(
+            "(define (list) 1)",
+            "Warning: redefining builtin 'list'. This will affect any code that uses it.\n",
+        );
+        interpreter.eval_success("(define (f - x) (set! - x) (define * 1) -) (f 1 2)", "2");
+        interpreter.eval_success("(define my-var 1)", "");
+    }
+
+    #[test]
+    fn traceback_collapses_repeated_frames() {
+        let mut interpreter = Interpreter::new();
+        let source_id = interpreter.source_mapper.add(
+            "<code>".into(),
+            "(define (f n) (if (= n 0) (car '()) (+ 1 (f (- n 1)))))\n(f 20)".into(),
+        );
+        interpreter.evaluate(source_id).unwrap_err();
+        assert_eq!(
+            interpreter.traceback(),
+            [
+                "Traceback (excluding tail calls, most recent call last):",
+                "  \"<code>\", line 2:",
+                "  | (f 20)",
+                "  |  ^",
+                "  \"<code>\", line 1:",
+                "  | (define (f n) (if (= n 0) (car '()) (+ 1 (f (- n 1)))))",
+                "  |                                           ^",
+                "  ... (18 frames) ...",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn errors_are_unchanged_when_not_colorized() {
+        let mut interpreter = Interpreter::new();
+        let source_id = interpreter
+            .source_mapper
+            .add("<code>".into(), "(+ 1 (if))".into());
+        let err = interpreter.evaluate(source_id).unwrap_err();
+        assert_eq!(
+            interpreter.describe_err(&err),
+            "Error: MalformedSpecialForm in \"<code>\", line 1:\n| (+ 1 (if))\n|      ^^^^"
+        );
+    }
+
+    #[test]
+    fn repr_value_includes_where_compound_procedures_are_defined() {
+        let mut interpreter = Interpreter::new();
+        let source_id = interpreter
+            .source_mapper
+            .add("<code>".into(), "\n(define (boop) 1)\nboop".into());
+        let value = interpreter.evaluate(source_id).unwrap();
+        let repr = interpreter.repr_value(&value);
+        assert!(repr.starts_with("#<procedure boop #"), "{repr}");
+        assert!(repr.ends_with(" at \"<code>\", line 2>"), "{repr}");
+        assert_eq!(interpreter.repr_value(&5.0.into()), "5");
+    }
+
+    #[test]
+    fn improper_combinations_point_at_improper_tail() {
+        let mut interpreter = Interpreter::new();
+        let source_id = interpreter
+            .source_mapper
+            .add("<code>".into(), "(+ 1 . 2)".into());
+        let err = interpreter.evaluate(source_id).unwrap_err();
+        assert_eq!(err.0, RuntimeErrorType::MalformedExpression);
+        assert_eq!(
+            interpreter.source_mapper.trace(&err.1),
+            vec!["\"<code>\", line 1:", "| (+ 1 . 2)", "|         ^"]
+        );
+    }
+
+    #[test]
+    fn deep_recursion_warns_about_stack_depth() {
+        let mut interpreter = Interpreter::new();
+        interpreter.max_stack_size = 20;
+        let source_id = interpreter.source_mapper.add(
+            "<code>".into(),
+            "(define (f n) (if (= n 0) 0 (+ 1 (f (- n 1))))) (f 17)".into(),
+        );
+        interpreter.evaluate(source_id).unwrap();
+        assert!(interpreter.has_warned_about_stack_depth);
+
+        let source_id = interpreter
+            .source_mapper
+            .add("<code>".into(), "(f 5)".into());
+        interpreter.evaluate(source_id).unwrap();
+        assert!(!interpreter.has_warned_about_stack_depth);
+    }
+
+    #[test]
+    fn tracing_can_be_written_to_file() {
+        let path = std::env::temp_dir().join(format!("ascheme-trace-{}.txt", std::process::id()));
+        let mut interpreter = Interpreter::new();
+        interpreter.printer.disable_autoflush = true;
+        interpreter.tracing = true;
+        interpreter.trace_file = Some(BufWriter::new(std::fs::File::create(&path).unwrap()));
+        let source_id = interpreter
+            .source_mapper
+            .add("<code>".into(), "(display (+ 1 2))".into());
+        interpreter.evaluate(source_id).unwrap();
+        let trace = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(trace.contains("Evaluating callable"));
+        assert_eq!(interpreter.printer.take_buffered_output(), "3");
+    }
+
+    #[test]
+    fn max_tail_iterations_stops_infinite_tail_loops() {
+        let mut interpreter = Interpreter::new();
+        interpreter.max_tail_iterations = Some(1000);
+        let source_id = interpreter
+            .source_mapper
+            .add("<code>".into(), "(define (f) (f)) (f)".into());
+        let err = interpreter.evaluate(source_id).unwrap_err();
+        assert_eq!(err.0, RuntimeErrorType::TooManyTailCalls);
+
+        // Bounded tail loops within the limit still work.
+        let source_id = interpreter.source_mapper.add(
+            "<code>".into(),
+            "(define (g n) (if (= n 0) 'done (g (- n 1)))) (g 999)".into(),
+        );
+        assert_eq!(interpreter.evaluate(source_id).unwrap().to_string(), "done");
+    }
+
     #[test]
     fn booleans_work() {
         test_eval_success("#t", "#t");
@@ -0,0 +1,72 @@
+use crate::tokenizer::{TokenType, Tokenizer};
+
+/// The number of spaces to indent continued lines by, for every unclosed paren.
+const INDENT_SIZE: usize = 2;
+
+/// Returns the positions of all the left parens in the given code that haven't
+/// been closed yet, along with the position of the left paren that matches the
+/// right paren ending at `pos`, if any.
+///
+/// Since this uses the tokenizer, parens in strings and comments are ignored.
+fn scan_parens(code: &str, pos: Option<usize>) -> (Vec<usize>, Option<usize>) {
+    let mut unclosed = vec![];
+    let mut match_at_pos = None;
+    for token in Tokenizer::new(&code, None) {
+        let Ok(token) = token else {
+            continue;
+        };
+        match token.0 {
+            TokenType::LeftParen => unclosed.push(token.1 .0),
+            TokenType::RightParen => {
+                let left_paren = unclosed.pop();
+                if Some(token.1 .1) == pos {
+                    match_at_pos = left_paren;
+                }
+            }
+            _ => {}
+        }
+    }
+    (unclosed, match_at_pos)
+}
+
+/// If the character right before the given cursor position is a right paren,
+/// returns the position of the left paren that matches it.
+pub fn find_matching_left_paren(code: &str, pos: usize) -> Option<usize> {
+    scan_parens(code, Some(pos)).1
+}
+
+/// Returns the number of spaces that a new line added to the end of the given
+/// code should be indented by, based on how many parens are unclosed.
+pub fn get_indentation(code: &str) -> usize {
+    scan_parens(code, None).0.len() * INDENT_SIZE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_matching_left_paren, get_indentation};
+
+    #[test]
+    fn find_matching_left_paren_works() {
+        assert_eq!(find_matching_left_paren("(+ 1 (f 2))", 11), Some(0));
+        assert_eq!(find_matching_left_paren("(+ 1 (f 2))", 10), Some(5));
+        assert_eq!(find_matching_left_paren("(+ 1 (f 2))", 9), None);
+        assert_eq!(find_matching_left_paren("1)", 2), None);
+    }
+
+    #[test]
+    fn find_matching_left_paren_ignores_strings_and_comments() {
+        assert_eq!(find_matching_left_paren(r#"(f "(")"#, 7), Some(0));
+        assert_eq!(find_matching_left_paren(r#"(f ")")"#, 7), Some(0));
+        assert_eq!(find_matching_left_paren("(f ; (\n)", 8), Some(0));
+    }
+
+    #[test]
+    fn get_indentation_works() {
+        assert_eq!(get_indentation(""), 0);
+        assert_eq!(get_indentation("(define (f x)"), 2);
+        assert_eq!(get_indentation("(define (f x"), 4);
+        assert_eq!(get_indentation("(define (f x)\n  (let ((y 1))"), 4);
+        assert_eq!(get_indentation("(display \"(\""), 2);
+        assert_eq!(get_indentation("(f ; (\n"), 2);
+    }
+}
@@ -1,22 +1,33 @@
-use std::cell::RefCell;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use std::sync::mpsc::channel;
-use std::{fs::read_to_string, process};
+use std::{
+    fs::{read_to_string, File},
+    io::BufWriter,
+    process,
+};
 
 use clap::Parser;
+use colored::Colorize;
 use ctrlc;
+use line_editing::{find_matching_left_paren, get_indentation};
 use pair::PairManager;
 use parser::{parse, ParseErrorType};
-use rustyline::{Editor, Helper, Highlighter, Hinter};
+use rustyline::{
+    Cmd, ConditionalEventHandler, Editor, Event, EventContext, EventHandler, Helper, Hinter,
+    KeyCode, KeyEvent, Modifiers, RepeatCount,
+};
 use source_mapper::SourceId;
 use string_interner::StringInterner;
 use tokenizer::{TokenType, TokenizeErrorType, Tokenizer};
-use value::Value;
+use value::{SourceValue, Value};
 
 use crate::interpreter::Interpreter;
 
 use rustyline::completion::Completer;
 use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
 use rustyline::validate::{ValidationContext, ValidationResult, Validator};
 
 mod bound_procedure;
@@ -24,15 +35,20 @@ mod builtin_procedure;
 mod builtins;
 mod callable;
 mod compound_procedure;
+mod derived_procedure;
 mod environment;
 mod gc;
 mod gc_rooted;
+mod hash_table;
 mod interpreter;
+mod line_editing;
 mod mutable_string;
 mod object_tracker;
 mod pair;
 mod parser;
+mod port;
 mod procedure;
+mod record;
 mod source_mapped;
 mod source_mapper;
 mod special_form;
@@ -41,6 +57,7 @@ mod string_interner;
 mod tokenizer;
 mod tracked_stats;
 mod value;
+mod vector;
 
 #[cfg(test)]
 mod test_util;
@@ -57,13 +74,76 @@ pub struct CliArgs {
     #[arg(short, long)]
     pub tracing: bool,
 
+    /// Write tracing output to the given file instead of stdout (implies --tracing).
+    #[arg(long)]
+    pub trace_file: Option<String>,
+
     /// Continue in interactive mode after executing source file.
     #[arg(short, long)]
     pub interactive: bool,
+
+    /// Disable non-standard builtins and syntax, for writing portable programs.
+    #[arg(long)]
+    pub strict_r5rs: bool,
+
+    /// Warn when a program redefines a builtin, e.g. `(define + -)`.
+    #[arg(long)]
+    pub warn_redefinitions: bool,
+
+    /// Disable colored output, which is also disabled by the NO_COLOR
+    /// environment variable.
+    #[arg(long)]
+    pub no_color: bool,
+
+    /// Print each parsed top-level form of the source file without
+    /// evaluating it, then exit.
+    #[arg(long)]
+    pub dump_ast: bool,
+
+    /// Make symbols case-insensitive by lowercasing identifiers when parsing.
+    #[arg(long)]
+    pub fold_case: bool,
 }
 
-#[derive(Helper, Highlighter, Hinter)]
-struct SchemeInputValidator(Rc<RefCell<Interpreter>>);
+#[derive(Helper, Hinter)]
+struct SchemeInputValidator {
+    interpreter: Rc<RefCell<Interpreter>>,
+
+    /// The position of the left paren to highlight, if any.
+    matching_paren: Cell<Option<usize>>,
+}
+
+impl SchemeInputValidator {
+    fn new(interpreter: Rc<RefCell<Interpreter>>) -> Self {
+        SchemeInputValidator {
+            interpreter,
+            matching_paren: Cell::new(None),
+        }
+    }
+}
+
+impl Highlighter for SchemeInputValidator {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        let Some(pos) = self.matching_paren.get() else {
+            return Cow::Borrowed(line);
+        };
+        let mut highlighted = line.to_owned();
+        highlighted.replace_range(pos..=pos, &"(".bold().blue().to_string());
+        Cow::Owned(highlighted)
+    }
+
+    fn highlight_char(&self, line: &str, pos: usize, forced: bool) -> bool {
+        // The line is "forced" to be redrawn without highlighting when it's
+        // accepted, in which case we don't want any highlighting left over.
+        let matching_paren = if forced {
+            None
+        } else {
+            find_matching_left_paren(line, pos)
+        };
+        self.matching_paren.set(matching_paren);
+        matching_paren.is_some()
+    }
+}
 
 impl Completer for SchemeInputValidator {
     type Candidate = String;
@@ -85,7 +165,7 @@ impl Completer for SchemeInputValidator {
             let range = token.1;
             if range.0 <= pos && range.1 >= pos {
                 let token_str = token.source(&line);
-                let interpreter = self.0.borrow();
+                let interpreter = self.interpreter.borrow();
                 let matches = interpreter.environment.find_global_matches(&token_str);
                 return Ok((range.0, matches));
             }
@@ -116,12 +196,71 @@ impl Validator for SchemeInputValidator {
     }
 }
 
+/// When the user presses enter at the end of an expression that has unclosed
+/// parens, this starts a new line indented according to how many there are.
+struct AutoIndentHandler;
+
+impl ConditionalEventHandler for AutoIndentHandler {
+    fn handle(
+        &self,
+        _evt: &Event,
+        _n: RepeatCount,
+        _positive: bool,
+        ctx: &EventContext,
+    ) -> Option<Cmd> {
+        let line = ctx.line();
+        if ctx.pos() != line.len() {
+            return None;
+        }
+        let mut interner = StringInterner::default();
+        let mut pair_manager = PairManager::default();
+        match parse(line, &mut interner, &mut pair_manager, None) {
+            Err(err) if err.0 == ParseErrorType::MissingRightParen => Some(Cmd::Insert(
+                1,
+                format!("\n{}", " ".repeat(get_indentation(line))),
+            )),
+            _ => None,
+        }
+    }
+}
+
 /// Returns true on success, false on failure.
 fn evaluate(interpreter: &mut Interpreter, source_id: SourceId) -> bool {
+    let success = evaluate_and_show_result(interpreter, source_id);
+    interpreter.reset_transient_state();
+    success
+}
+
+/// The names of the variables that hold the most recent results, most recent
+/// first.
+const RESULT_HISTORY_NAMES: [&str; 3] = ["*1", "*2", "*3"];
+
+/// Shifts the given value into the result history, so that `*1` refers to it,
+/// `*2` to the result before it, and so on.
+fn add_to_result_history(interpreter: &mut Interpreter, value: SourceValue) {
+    let names = RESULT_HISTORY_NAMES.map(|name| interpreter.string_interner.intern(name));
+    for i in (1..names.len()).rev() {
+        if let Some(previous) = interpreter.environment.get(&names[i - 1]) {
+            interpreter.environment.define(names[i].clone(), previous);
+        }
+    }
+    interpreter.environment.define(names[0].clone(), value);
+}
+
+fn evaluate_and_show_result(interpreter: &mut Interpreter, source_id: SourceId) -> bool {
     match interpreter.evaluate(source_id) {
         Ok(value) => {
-            if !matches!(value.0, Value::Undefined) {
-                interpreter.printer.println(format!("{}", value));
+            match &value.0 {
+                Value::Undefined => {}
+                Value::MultipleValues(values) => {
+                    for value in values.iter() {
+                        interpreter.printer.println(interpreter.repr_value(value));
+                    }
+                }
+                _ => {
+                    interpreter.printer.println(interpreter.repr_value(&value));
+                    add_to_result_history(interpreter, value);
+                }
             }
             true
         }
@@ -132,6 +271,23 @@ fn evaluate(interpreter: &mut Interpreter, source_id: SourceId) -> bool {
     }
 }
 
+/// Prints each top-level form of the given source without evaluating it.
+/// Returns true on success, false on failure.
+fn dump_ast(interpreter: &mut Interpreter, source_id: SourceId) -> bool {
+    match interpreter.parse(source_id) {
+        Ok(values) => {
+            for value in values {
+                interpreter.printer.println(value.to_string());
+            }
+            true
+        }
+        Err(err) => {
+            interpreter.show_err_and_traceback(err.into());
+            false
+        }
+    }
+}
+
 fn main() {
     let args = CliArgs::parse();
     let (tx, rx) = channel();
@@ -139,13 +295,36 @@ fn main() {
     ctrlc::set_handler(move || tx.send(()).expect("Count not send signal on channel."))
         .expect("Error setting Ctrl-C handler.");
 
-    let mut interpreter = Interpreter::new();
+    let mut interpreter = if args.strict_r5rs {
+        Interpreter::new_strict_r5rs()
+    } else {
+        Interpreter::new()
+    };
     interpreter.tracing = args.tracing;
+    interpreter.colorize_errors = !args.no_color;
+    if args.no_color {
+        colored::control::set_override(false);
+    }
+    interpreter.warn_on_builtin_redefinition = args.warn_redefinitions;
+    interpreter.fold_case = args.fold_case;
+    if let Some(trace_filename) = args.trace_file {
+        let Ok(file) = File::create(&trace_filename) else {
+            eprintln!("Unable to create trace file '{trace_filename}'!");
+            process::exit(1);
+        };
+        interpreter.trace_file = Some(BufWriter::new(file));
+        interpreter.tracing = true;
+    }
     interpreter.keyboard_interrupt_channel = Some(rx);
 
     if let Some(filename) = args.source_filename {
         let contents = read_to_string(&filename).unwrap();
         let source_id = interpreter.source_mapper.add(filename, contents);
+        if args.dump_ast {
+            let success = dump_ast(&mut interpreter, source_id);
+            interpreter.printer.print_buffered_output();
+            process::exit(if success { 0 } else { 1 });
+        }
         let success = evaluate(&mut interpreter, source_id);
         interpreter.printer.print_buffered_output();
         if !args.interactive {
@@ -165,7 +344,11 @@ fn main() {
     };
 
     let interpreter: Rc<RefCell<Interpreter>> = RefCell::new(interpreter).into();
-    rl.set_helper(Some(SchemeInputValidator(interpreter.clone())));
+    rl.set_helper(Some(SchemeInputValidator::new(interpreter.clone())));
+    rl.bind_sequence(
+        KeyEvent(KeyCode::Enter, Modifiers::NONE),
+        EventHandler::Conditional(Box::new(AutoIndentHandler)),
+    );
 
     // Note that we're ignoring the result here, which is generally OK--if it
     // errors, it's probably because the file doesn't exist, and even then
@@ -209,3 +392,98 @@ fn main() {
     // Again, we're ignoring the result here, see above for rationale.
     let _ = rl.save_history(HISTORY_FILENAME);
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{dump_ast, evaluate, interpreter::Interpreter};
+
+    fn evaluate_and_get_output(code: &str) -> String {
+        let mut interpreter = Interpreter::new();
+        interpreter.printer.disable_autoflush = true;
+        let source_id = interpreter.source_mapper.add("<code>".into(), code.into());
+        assert!(evaluate(&mut interpreter, source_id));
+        interpreter.printer.take_buffered_output()
+    }
+
+    #[test]
+    fn evaluate_resets_transient_state_after_errors() {
+        let mut interpreter = Interpreter::new();
+        interpreter.printer.disable_autoflush = true;
+        let mut eval = |code: &str| {
+            let source_id = interpreter.source_mapper.add("<code>".into(), code.into());
+            let success = evaluate(&mut interpreter, source_id);
+            interpreter.printer.take_buffered_output();
+            success
+        };
+        assert!(eval("(define (f x) (let ((y x)) (car y)))"));
+        assert!(!eval("(test-eq 1 2) (f 1)"));
+        assert_eq!(interpreter.environment.lexical_scope_depth(), 0);
+        assert_eq!(interpreter.traceback(), "");
+        assert_eq!(interpreter.failed_tests, 0);
+
+        let mut eval = |code: &str| {
+            let source_id = interpreter.source_mapper.add("<code>".into(), code.into());
+            assert!(evaluate(&mut interpreter, source_id));
+            interpreter.printer.take_buffered_output()
+        };
+        eval("(define y 5)");
+        assert_eq!(eval("y"), "5\n");
+        assert_eq!(eval("(f '(2))"), "2\n");
+    }
+
+    #[test]
+    fn evaluate_prints_values() {
+        assert_eq!(evaluate_and_get_output("(+ 1 2)"), "3\n");
+        assert_eq!(evaluate_and_get_output("(define x 1)"), "");
+    }
+
+    #[test]
+    fn evaluate_records_result_history() {
+        let mut interpreter = Interpreter::new();
+        interpreter.printer.disable_autoflush = true;
+        let mut eval = |code: &str| {
+            let source_id = interpreter.source_mapper.add("<code>".into(), code.into());
+            assert!(evaluate(&mut interpreter, source_id));
+            interpreter.printer.take_buffered_output()
+        };
+        assert_eq!(eval("(+ 1 2)"), "3\n");
+        assert_eq!(eval("*1"), "3\n");
+        assert_eq!(eval("(define x 5)"), "");
+        assert_eq!(eval("(values 1 2)"), "1\n2\n");
+        assert_eq!(eval("(* *1 2)"), "6\n");
+        assert_eq!(eval("(list *1 *2 *3)"), "(6 3 3)\n");
+        assert_eq!(eval("(list *1 *2 *3)"), "((6 3 3) 6 3)\n");
+    }
+
+    #[test]
+    fn dump_ast_prints_parsed_forms_without_evaluating() {
+        let mut interpreter = Interpreter::new();
+        interpreter.printer.disable_autoflush = true;
+        let code = "'(a  b . c) (display \"hi\")";
+        let source_id = interpreter.source_mapper.add("<code>".into(), code.into());
+        assert!(dump_ast(&mut interpreter, source_id));
+        assert_eq!(
+            interpreter.printer.take_buffered_output(),
+            "(quote (a b . c))\n(display \"hi\")\n"
+        );
+    }
+
+    #[test]
+    fn dump_ast_fails_on_parse_errors() {
+        let mut interpreter = Interpreter::new();
+        interpreter.printer.disable_autoflush = true;
+        let source_id = interpreter
+            .source_mapper
+            .add("<code>".into(), "(1 2".into());
+        assert!(!dump_ast(&mut interpreter, source_id));
+    }
+
+    #[test]
+    fn evaluate_prints_each_of_multiple_values() {
+        assert_eq!(
+            evaluate_and_get_output("(values 1 \"two\" 3)"),
+            "1\n\"two\"\n3\n"
+        );
+        assert_eq!(evaluate_and_get_output("(values)"), "");
+    }
+}
@@ -1,5 +1,7 @@
 use std::{cell::RefCell, fmt::Display, rc::Rc};
 
+use crate::tokenizer::parse_hex_escape;
+
 #[derive(Debug, Clone)]
 pub struct MutableString(Rc<RefCell<String>>);
 
@@ -8,24 +10,28 @@ impl MutableString {
         MutableString(Rc::new(RefCell::new(value)))
     }
 
+    /// Note that this assumes the tokenizer has already validated any escape
+    /// sequences in the string.
     pub fn from_tokenized_source(repr: &str) -> Self {
         let mut chars: Vec<char> = Vec::with_capacity(repr.len());
         // The `skip(1)` skips the opening quote.
-        let mut is_escaped = false;
-        for char in repr.chars().skip(1) {
-            if is_escaped {
-                if char == 'n' {
-                    chars.push('\n');
-                } else {
-                    chars.push(char);
-                }
-                is_escaped = false;
-            } else {
-                if char == '\\' {
-                    is_escaped = true;
-                } else {
-                    chars.push(char);
+        let mut source_chars = repr.chars().skip(1);
+        while let Some(char) = source_chars.next() {
+            if char != '\\' {
+                chars.push(char);
+                continue;
+            }
+            match source_chars.next() {
+                Some('n') => chars.push('\n'),
+                Some('t') => chars.push('\t'),
+                Some('r') => chars.push('\r'),
+                Some('0') => chars.push('\0'),
+                Some('x') => {
+                    let digits: String = source_chars.by_ref().take_while(|&c| c != ';').collect();
+                    chars.push(parse_hex_escape(&digits).expect("hex escape should be valid"));
                 }
+                Some(char) => chars.push(char),
+                None => {}
             }
         }
         chars.pop(); // Remove closing quote.
@@ -42,8 +48,34 @@ impl MutableString {
     }
 }
 
+impl PartialEq for MutableString {
+    /// Note that this compares the contents of the strings, rather than
+    /// their identity: use `points_at_same_memory_as()` for the latter.
+    fn eq(&self, other: &Self) -> bool {
+        *self.0.borrow() == *other.0.borrow()
+    }
+}
+
 impl Display for MutableString {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0.borrow().as_str())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::MutableString;
+
+    #[test]
+    fn from_tokenized_source_works() {
+        let parse = |repr: &str| MutableString::from_tokenized_source(repr).to_string();
+        assert_eq!(parse(r#""hello""#), "hello");
+        assert_eq!(parse(r#""a\"b\\c""#), "a\"b\\c");
+        assert_eq!(parse(r#""a\nb""#), "a\nb");
+        assert_eq!(parse(r#""a\tb""#), "a\tb");
+        assert_eq!(parse(r#""a\rb""#), "a\rb");
+        assert_eq!(parse(r#""a\0b""#), "a\0b");
+        assert_eq!(parse(r#""a\x41;b""#), "aAb");
+        assert_eq!(parse(r#""\x3bb;\x1F600;""#), "\u{3bb}\u{1F600}");
+    }
+}
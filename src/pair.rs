@@ -1,11 +1,10 @@
 use std::cell::{Ref, RefCell};
-use std::fmt::Display;
 use std::ops::Deref;
 use std::{collections::HashSet, rc::Rc};
 
 use crate::gc::{Traverser, Visitor};
 use crate::object_tracker::{CycleBreaker, ObjectTracker, Tracked};
-use crate::value::{SourceValue, Value};
+use crate::value::{DisplayPath, SourceValue, Value};
 
 #[derive(Debug)]
 pub enum VecPair {
@@ -13,14 +12,18 @@ pub enum VecPair {
     ImproperList(Rc<Vec<SourceValue>>),
 }
 
-impl Display for VecPair {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl VecPair {
+    pub fn fmt_with_path(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        path: &mut DisplayPath,
+    ) -> std::fmt::Result {
         match self {
             VecPair::List(items) => {
                 write!(f, "(")?;
                 let len = items.len();
                 for (i, item) in items.iter().enumerate() {
-                    item.fmt(f)?;
+                    item.0.fmt_with_path(f, path)?;
                     if i < len - 1 {
                         write!(f, " ")?;
                     }
@@ -31,7 +34,7 @@ impl Display for VecPair {
                 write!(f, "(")?;
                 let len = items.len();
                 for (i, item) in items.iter().enumerate() {
-                    item.fmt(f)?;
+                    item.0.fmt_with_path(f, path)?;
                     if i == len - 2 {
                         write!(f, " . ")?;
                     } else if i < len - 1 {
@@ -44,7 +47,7 @@ impl Display for VecPair {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PairType {
     List,
     ImproperList,
@@ -130,13 +133,36 @@ impl Pair {
         }
     }
 
-    fn get_type_recursive(&self, visited: &mut HashSet<*const PairInner>) -> PairType {
-        let mut latest = self.as_ptr();
-        loop {
-            if visited.contains(&latest) {
-                return PairType::Cyclic;
-            }
-            visited.insert(latest);
+    /// Returns the type of the list starting at this pair, looking for
+    /// cycles through the cars of its items as well as its cdrs.
+    ///
+    /// The `path` set contains only the pairs along the path from the
+    /// outermost list to the one we're currently walking, so a sublist that
+    /// merely appears more than once isn't mistaken for a cycle. The
+    /// `acyclic` set contains pairs from which no cycle can be reached, so
+    /// shared sublists are only explored once.
+    pub fn get_type(&self) -> PairType {
+        let mut path = PairVisitedSet::default();
+        let mut acyclic = PairVisitedSet::default();
+        // We keep our own stack of the lists we're partway through walking,
+        // rather than recursing into sublists, so deeply nested lists don't
+        // overflow the call stack.
+        let mut spines = vec![Spine::new(self.as_ptr())];
+        while let Some(spine) = spines.last_mut() {
+            let latest = match spine.next {
+                SpineNext::Pair(latest) => latest,
+                SpineNext::End(pair_type) => {
+                    for ptr in spine.pairs.drain(..) {
+                        path.remove_ptr(ptr);
+                        acyclic.add_ptr(ptr);
+                    }
+                    spines.pop();
+                    if spines.is_empty() {
+                        return pair_type;
+                    }
+                    continue;
+                }
+            };
 
             // It's unfortunate we have to resort to unsafe code just
             // to iterate through the chain of pairs. The only alternative
@@ -146,24 +172,38 @@ impl Pair {
             let cdr = unsafe { &(*latest).cdr.0 };
             let car = unsafe { &(*latest).car.0 };
 
+            spine.next = match cdr {
+                Value::EmptyList => SpineNext::End(PairType::List),
+                Value::Pair(pair) => SpineNext::Pair(pair.as_ptr()),
+                _ => SpineNext::End(PairType::ImproperList),
+            };
+
+            // Once a pair is known to be acyclic, all we still need to
+            // know is how its chain of cdrs ends.
+            if acyclic.contains_ptr(latest) {
+                continue;
+            }
+            if !path.add_ptr(latest) {
+                return PairType::Cyclic;
+            }
+            spine.pairs.push(latest);
+
             if let Value::Pair(child) = car {
-                if child.get_type_recursive(visited) == PairType::Cyclic {
-                    return PairType::Cyclic;
+                if !acyclic.contains(child) {
+                    spines.push(Spine::new(child.as_ptr()));
                 }
             }
-
-            let new_latest = match cdr {
-                Value::EmptyList => return PairType::List,
-                Value::Pair(pair) => pair.as_ptr(),
-                _ => return PairType::ImproperList,
-            };
-            latest = new_latest;
         }
+        unreachable!("the outermost spine always returns once it ends")
     }
 
-    pub fn get_type(&self) -> PairType {
-        let mut visited: HashSet<*const PairInner> = HashSet::new();
-        self.get_type_recursive(&mut visited)
+    /// If the pair represents an improper list, returns the final non-list value
+    /// that terminates it (e.g. `3` in `(1 2 . 3)`), otherwise returns None.
+    pub fn try_get_improper_tail(&self) -> Option<SourceValue> {
+        match self.get_type() {
+            PairType::ImproperList => self.iter().last(),
+            _ => None,
+        }
     }
 
     /// If the pair represents a list, returns it, otherwise returns None.
@@ -184,16 +224,70 @@ impl Traverser for Pair {
     }
 }
 
+/// Either the next pair of a spine to look at, or how the spine ended.
+enum SpineNext {
+    Pair(*const PairInner),
+    End(PairType),
+}
+
+/// A chain of cdrs that `Pair::get_type()` is partway through walking.
+struct Spine {
+    next: SpineNext,
+    /// The pairs of the chain that have been added to the current path.
+    pairs: Vec<*const PairInner>,
+}
+
+impl Spine {
+    fn new(first: *const PairInner) -> Self {
+        Spine {
+            next: SpineNext::Pair(first),
+            pairs: vec![],
+        }
+    }
+}
+
+/// Keeps track of pairs we've already visited, e.g. to detect cycles. This is
+/// what everything that walks through pairs (including `Pair::get_type()`)
+/// should use, so there's only one place that deals with pair pointers.
 #[derive(Default)]
 pub struct PairVisitedSet(HashSet<*const PairInner>);
 
 impl PairVisitedSet {
     pub fn contains(&self, pair: &Pair) -> bool {
-        self.0.contains(&pair.as_ptr())
+        self.contains_ptr(pair.as_ptr())
     }
 
-    pub fn add(&mut self, pair: &Pair) {
-        self.0.insert(pair.as_ptr());
+    fn contains_ptr(&self, ptr: *const PairInner) -> bool {
+        self.0.contains(&ptr)
+    }
+
+    /// Returns whether the set did not previously contain the given pair.
+    pub fn add(&mut self, pair: &Pair) -> bool {
+        self.add_ptr(pair.as_ptr())
+    }
+
+    pub fn remove(&mut self, pair: &Pair) {
+        self.remove_ptr(pair.as_ptr())
+    }
+
+    fn add_ptr(&mut self, ptr: *const PairInner) -> bool {
+        self.0.insert(ptr)
+    }
+
+    fn remove_ptr(&mut self, ptr: *const PairInner) {
+        self.0.remove(&ptr);
+    }
+}
+
+/// Keeps track of pairs of pairs, e.g. to avoid infinitely comparing
+/// cyclic structures to each other.
+#[derive(Default)]
+pub struct PairComparisonSet(HashSet<(*const PairInner, *const PairInner)>);
+
+impl PairComparisonSet {
+    /// Returns whether the set did not previously contain the given pairs.
+    pub fn add(&mut self, a: &Pair, b: &Pair) -> bool {
+        self.0.insert((a.as_ptr(), b.as_ptr()))
     }
 }
 
@@ -288,7 +382,7 @@ impl Iterator for PairIterator {
 #[cfg(test)]
 mod tests {
     use crate::{
-        pair::{PairManager, PairType},
+        pair::{PairManager, PairType, PairVisitedSet},
         value::Value,
     };
 
@@ -324,4 +418,47 @@ mod tests {
         cyclic_list.0.borrow_mut().cdr = Value::Pair(cyclic_list.clone()).into();
         assert_eq!(cyclic_list.get_type(), PairType::Cyclic);
     }
+
+    #[test]
+    fn cycles_through_cars_are_detected() {
+        let mut manager = PairManager::default();
+        let inner = manager.pair(1.0.into(), Value::EmptyList.into());
+        let outer = manager.pair(Value::Pair(inner.clone()).into(), Value::EmptyList.into());
+        assert_eq!(outer.get_type(), PairType::List);
+        inner.0.borrow_mut().car = Value::Pair(outer.clone()).into();
+        assert_eq!(outer.get_type(), PairType::Cyclic);
+        assert_eq!(inner.get_type(), PairType::Cyclic);
+    }
+
+    #[test]
+    fn shared_sublists_are_not_cycles() {
+        let mut manager = PairManager::default();
+        let shared = manager.pair(1.0.into(), Value::EmptyList.into());
+        let second = manager.pair(Value::Pair(shared.clone()).into(), Value::EmptyList.into());
+        let outer = manager.pair(
+            Value::Pair(shared.clone()).into(),
+            Value::Pair(second).into(),
+        );
+        assert_eq!(outer.get_type(), PairType::List);
+        let nested = manager.pair(Value::Pair(outer.clone()).into(), Value::EmptyList.into());
+        let doubly_shared = manager.pair(
+            Value::Pair(outer.clone()).into(),
+            Value::Pair(nested).into(),
+        );
+        assert_eq!(doubly_shared.get_type(), PairType::List);
+    }
+
+    #[test]
+    fn visited_set_works() {
+        let mut manager = PairManager::default();
+        let a = manager.pair(1.0.into(), Value::EmptyList.into());
+        let b = manager.pair(1.0.into(), Value::EmptyList.into());
+        let mut visited = PairVisitedSet::default();
+        assert!(!visited.contains(&a));
+        assert!(visited.add(&a));
+        assert!(visited.contains(&a));
+        assert!(!visited.add(&a));
+        assert!(!visited.contains(&b));
+        assert!(visited.add(&b));
+    }
 }
@@ -12,10 +12,39 @@ use crate::{
 pub enum ParseErrorType {
     Tokenize(TokenizeErrorType),
     InvalidNumber,
+    InvalidCharacter,
     MissingRightParen,
     UnexpectedEndOfFile,
     Expected(TokenType),
     Unexpected(TokenType),
+    TooDeeplyNested,
+}
+
+/// The default maximum number of lists and quotes that can be nested inside
+/// each other. The parser itself can handle any depth, but other things that
+/// walk the resulting values (e.g. printing and freeing them) are recursive.
+pub const DEFAULT_MAX_PARSE_DEPTH: usize = 1_000;
+
+/// A list or quotation whose parsing has begun but not yet finished.
+enum PartialExpression {
+    List {
+        left_paren: Token,
+        expressions: Vec<SourceValue>,
+        tail: DottedTail,
+    },
+    Quote {
+        apostrophe: Token,
+    },
+}
+
+/// The state of the part of a list that comes after its dot, if any.
+enum DottedTail {
+    /// No dot has been encountered.
+    None,
+    /// A dot has been encountered, but not the expression after it.
+    Pending,
+    /// The expression after the dot, which must be followed by a right paren.
+    Parsed(SourceValue),
 }
 
 pub type ParseError = SourceMapped<ParseErrorType>;
@@ -31,6 +60,9 @@ pub struct Parser<'a> {
     tokenizer: Tokenizer<'a>,
     interner: &'a mut StringInterner,
     pair_manager: &'a mut PairManager,
+    strict_r5rs: bool,
+    fold_case: bool,
+    max_depth: usize,
 }
 
 impl<'a> Parser<'a> {
@@ -45,87 +77,183 @@ impl<'a> Parser<'a> {
             tokenizer,
             interner,
             pair_manager,
+            strict_r5rs: false,
+            fold_case: false,
+            max_depth: DEFAULT_MAX_PARSE_DEPTH,
         }
     }
+
+    /// Rejects syntax that isn't part of R5RS, e.g. `#!void`.
+    pub fn with_strict_r5rs(mut self, strict_r5rs: bool) -> Self {
+        self.strict_r5rs = strict_r5rs;
+        self
+    }
+
+    /// Lowercases identifiers before interning them, so that e.g. `Foo` and
+    /// `foo` are the same symbol. Strings and characters are unaffected.
+    pub fn with_fold_case(mut self, fold_case: bool) -> Self {
+        self.fold_case = fold_case;
+        self
+    }
+
+    /// Sets the maximum number of lists and quotes that can be nested inside
+    /// each other before parsing fails.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
 }
 
 impl<'a> Parser<'a> {
-    fn expect_token(&mut self) -> Result<Token, ParseError> {
+    /// Returns the next token of an expression that's still being parsed.
+    fn expect_token(&mut self, stack: &[PartialExpression]) -> Result<Token, ParseError> {
         match self.tokenizer.next() {
             Some(Ok(token)) => Ok(token),
             Some(Err(tokenize_error)) => Err(tokenize_error.into()),
-            None => Err(ParseErrorType::UnexpectedEndOfFile
-                .source_mapped(self.tokenizer.curr_pos_as_source_range())),
+            None => match stack.last() {
+                Some(PartialExpression::List {
+                    left_paren,
+                    tail: DottedTail::None,
+                    ..
+                }) => Err(ParseErrorType::MissingRightParen.source_mapped(left_paren.1)),
+                _ => Err(ParseErrorType::UnexpectedEndOfFile
+                    .source_mapped(self.tokenizer.curr_pos_as_source_range())),
+            },
         }
     }
 
-    fn expect_expression(&mut self) -> Result<SourceValue, ParseError> {
-        let token = self.expect_token()?;
-        self.parse_token(token)
-    }
+    /// Parses the expression that starts with the given token.
+    ///
+    /// Rather than recursing for each nested list, this keeps the lists and
+    /// quotations that are still being parsed on an explicit stack, so that
+    /// deeply nested input can't overflow the Rust stack.
+    fn parse_token(&mut self, mut token: Token) -> Result<SourceValue, ParseError> {
+        let mut stack: Vec<PartialExpression> = vec![];
+        loop {
+            if let Some(PartialExpression::List {
+                tail: DottedTail::Parsed(_),
+                ..
+            }) = stack.last()
+            {
+                if token.0 != TokenType::RightParen {
+                    return Err(
+                        ParseErrorType::Expected(TokenType::RightParen).source_mapped(token.1)
+                    );
+                }
+            }
+            let mut completed = match token.0 {
+                TokenType::LeftParen | TokenType::Apostrophe => {
+                    if stack.len() >= self.max_depth {
+                        return Err(ParseErrorType::TooDeeplyNested.source_mapped(token.1));
+                    }
+                    stack.push(if token.0 == TokenType::LeftParen {
+                        PartialExpression::List {
+                            left_paren: token,
+                            expressions: vec![],
+                            tail: DottedTail::None,
+                        }
+                    } else {
+                        PartialExpression::Quote { apostrophe: token }
+                    });
+                    None
+                }
+                TokenType::RightParen => {
+                    let Some(PartialExpression::List {
+                        left_paren,
+                        expressions,
+                        tail,
+                    }) = stack.pop()
+                    else {
+                        return Err(ParseErrorType::Unexpected(TokenType::RightParen)
+                            .source_mapped(token.1));
+                    };
+                    match tail {
+                        DottedTail::None => Some(
+                            self.pair_manager
+                                .vec_to_list(expressions)
+                                .source_mapped(left_paren.extend_range(&token.1)),
+                        ),
+                        DottedTail::Pending => {
+                            return Err(ParseErrorType::Unexpected(TokenType::RightParen)
+                                .source_mapped(token.1));
+                        }
+                        DottedTail::Parsed(final_value) => Some(
+                            self.pair_manager
+                                .vec_to_pair(expressions, final_value)
+                                .source_mapped(token.1),
+                        ),
+                    }
+                }
+                TokenType::Dot => match stack.last_mut() {
+                    Some(PartialExpression::List {
+                        expressions,
+                        tail: tail @ DottedTail::None,
+                        ..
+                    }) if !expressions.is_empty() => {
+                        *tail = DottedTail::Pending;
+                        None
+                    }
+                    _ => {
+                        return Err(
+                            ParseErrorType::Unexpected(TokenType::Dot).source_mapped(token.1)
+                        );
+                    }
+                },
+                _ => Some(self.parse_atom(token)?),
+            };
 
-    fn expect_token_type(&mut self, token_type: TokenType) -> Result<Token, ParseError> {
-        let token = self.expect_token()?;
-        if token.0 != token_type {
-            return Err(ParseErrorType::Expected(token_type).source_mapped(token.1));
+            // Add the completed expression to the one enclosing it, which may
+            // complete that one too.
+            while let Some(value) = completed.take() {
+                match stack.last_mut() {
+                    None => return Ok(value),
+                    Some(PartialExpression::Quote { apostrophe }) => {
+                        let range = apostrophe.extend_range(&value.1);
+                        let expressions = vec![
+                            Value::Symbol(self.interner.intern("quote"))
+                                .source_mapped(apostrophe.1),
+                            value,
+                        ];
+                        stack.pop();
+                        completed = Some(
+                            self.pair_manager
+                                .vec_to_list(expressions)
+                                .source_mapped(range),
+                        );
+                    }
+                    Some(PartialExpression::List {
+                        expressions,
+                        tail: DottedTail::None,
+                        ..
+                    }) => expressions.push(value),
+                    Some(PartialExpression::List { tail, .. }) => {
+                        *tail = DottedTail::Parsed(value);
+                    }
+                }
+            }
+            token = self.expect_token(&stack)?;
         }
-        Ok(token)
     }
 
-    fn parse_token(&mut self, token: Token) -> Result<SourceValue, ParseError> {
+    /// Parses a token that is an expression on its own, rather than part of
+    /// a list or quotation.
+    fn parse_atom(&mut self, token: Token) -> Result<SourceValue, ParseError> {
         match token.0 {
-            TokenType::LeftParen => {
-                let mut expressions = vec![];
-                loop {
-                    match self.tokenizer.next() {
-                        Some(Ok(nested_token)) => {
-                            if nested_token.0 == TokenType::RightParen {
-                                return Ok(self
-                                    .pair_manager
-                                    .vec_to_list(expressions)
-                                    .source_mapped(token.extend_range(&nested_token.1)));
-                            } else if nested_token.0 == TokenType::Dot {
-                                if expressions.is_empty() {
-                                    return Err(ParseErrorType::Unexpected(TokenType::Dot)
-                                        .source_mapped(nested_token.1));
-                                }
-                                let final_value = self.expect_expression()?;
-                                let right_paren = self.expect_token_type(TokenType::RightParen)?;
-                                return Ok(self
-                                    .pair_manager
-                                    .vec_to_pair(expressions, final_value)
-                                    .source_mapped(right_paren.1));
-                            } else {
-                                expressions.push(self.parse_token(nested_token)?);
-                            }
-                        }
-                        Some(Err(tokenize_error)) => return Err(tokenize_error.into()),
-                        None => {
-                            return Err(ParseErrorType::MissingRightParen.source_mapped(token.1));
-                        }
-                    }
+            TokenType::LeftParen
+            | TokenType::RightParen
+            | TokenType::Apostrophe
+            | TokenType::Dot => unreachable!("{:?} is not an atom", token.0),
+            TokenType::Boolean(boolean) => Ok(Value::Boolean(boolean).source_mapped(token.1)),
+            TokenType::Undefined => {
+                if self.strict_r5rs {
+                    Err(ParseErrorType::Unexpected(TokenType::Undefined).source_mapped(token.1))
+                } else {
+                    Ok(Value::Undefined.source_mapped(token.1))
                 }
             }
-            TokenType::RightParen => {
-                Err(ParseErrorType::Unexpected(TokenType::RightParen).source_mapped(token.1))
-            }
-            TokenType::Apostrophe => {
-                let quoted_expression = self.expect_expression()?;
-                let end_range = quoted_expression.1;
-                let expressions = vec![
-                    Value::Symbol(self.interner.intern("quote")).source_mapped(token.1),
-                    quoted_expression,
-                ];
-                Ok(self
-                    .pair_manager
-                    .vec_to_list(expressions)
-                    .source_mapped(token.extend_range(&end_range)))
-            }
-            TokenType::Dot => {
-                Err(ParseErrorType::Unexpected(TokenType::Dot).source_mapped(token.1))
-            }
-            TokenType::Boolean(boolean) => Ok(Value::Boolean(boolean).source_mapped(token.1)),
-            TokenType::Undefined => Ok(Value::Undefined.source_mapped(token.1)),
+            // TODO: All our numbers are currently floats. Once we have a separate exact
+            // integer type, literals without a decimal point or exponent should produce it,
+            // as should `string->number`.
             TokenType::Number => match token.source(&self.string).parse::<f64>() {
                 Ok(number) => Ok(Value::Number(number).source_mapped(token.1)),
                 Err(_) => Err(ParseErrorType::InvalidNumber.source_mapped(token.1)),
@@ -134,8 +262,21 @@ impl<'a> Parser<'a> {
                 token.source(&self.string),
             ))
             .source_mapped(token.1)),
+            TokenType::Character => {
+                // The `[2..]` skips the `#\` prefix.
+                let name = &token.source(self.string)[2..];
+                match parse_character_name(name) {
+                    Some(char) => Ok(Value::Character(char).source_mapped(token.1)),
+                    None => Err(ParseErrorType::InvalidCharacter.source_mapped(token.1)),
+                }
+            }
             TokenType::Identifier => {
-                let string = self.interner.intern(token.source(&self.string));
+                let source = token.source(&self.string);
+                let string = if self.fold_case {
+                    self.interner.intern(source.to_lowercase())
+                } else {
+                    self.interner.intern(source)
+                };
                 Ok(Value::Symbol(string).source_mapped(token.1))
             }
         }
@@ -158,6 +299,20 @@ impl<'a> Iterator for Parser<'a> {
     }
 }
 
+fn parse_character_name(name: &str) -> Option<char> {
+    let mut chars = name.chars();
+    let first_char = chars.next()?;
+    if chars.next().is_none() {
+        return Some(first_char);
+    }
+    match name {
+        "space" => Some(' '),
+        "newline" => Some('\n'),
+        "tab" => Some('\t'),
+        _ => None,
+    }
+}
+
 pub fn parse(
     code: &str,
     interner: &mut StringInterner,
@@ -167,3 +322,105 @@ pub fn parse(
     let parser = Parser::new(code, Tokenizer::new(&code, source), interner, pair_manager);
     parser.parse_all()
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{pair::PairManager, string_interner::StringInterner, tokenizer::Tokenizer};
+
+    use super::{parse, ParseErrorType, Parser, DEFAULT_MAX_PARSE_DEPTH};
+    use crate::tokenizer::TokenType;
+
+    fn test_parse_success(code: &str, expected: &str) {
+        let mut interner = StringInterner::default();
+        let mut pair_manager = PairManager::default();
+        let values = parse(code, &mut interner, &mut pair_manager, None).unwrap();
+        let actual = values
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        assert_eq!(actual, expected, "Parsing {code:?}");
+    }
+
+    fn test_parse_err(code: &str, expected: ParseErrorType) {
+        let mut interner = StringInterner::default();
+        let mut pair_manager = PairManager::default();
+        let err = parse(code, &mut interner, &mut pair_manager, None).unwrap_err();
+        assert_eq!(err.0, expected, "Parsing {code:?}");
+    }
+
+    #[test]
+    fn lists_and_quotes_work() {
+        test_parse_success("(1 (2 'x) () . 3) 'y", "(1 (2 (quote x)) () . 3) (quote y)");
+        test_parse_success("''(a . (b))", "(quote (quote (a b)))");
+        test_parse_success("(((1)) 2)", "(((1)) 2)");
+    }
+
+    #[test]
+    fn malformed_lists_and_quotes_raise_errors() {
+        test_parse_err("(1 2", ParseErrorType::MissingRightParen);
+        test_parse_err("(1 '(2", ParseErrorType::MissingRightParen);
+        test_parse_err("'", ParseErrorType::UnexpectedEndOfFile);
+        test_parse_err("(1 .", ParseErrorType::UnexpectedEndOfFile);
+        test_parse_err("(1 . 2", ParseErrorType::UnexpectedEndOfFile);
+        test_parse_err("(1 . 2 3)", ParseErrorType::Expected(TokenType::RightParen));
+        test_parse_err(
+            "(1 . 2 (3))",
+            ParseErrorType::Expected(TokenType::RightParen),
+        );
+        test_parse_err("(1 . )", ParseErrorType::Unexpected(TokenType::RightParen));
+        test_parse_err("( . 2)", ParseErrorType::Unexpected(TokenType::Dot));
+        test_parse_err("(1 . . 2)", ParseErrorType::Unexpected(TokenType::Dot));
+        test_parse_err("'.", ParseErrorType::Unexpected(TokenType::Dot));
+        test_parse_err(")", ParseErrorType::Unexpected(TokenType::RightParen));
+        test_parse_err("(')", ParseErrorType::Unexpected(TokenType::RightParen));
+    }
+
+    #[test]
+    fn source_ranges_are_correct() {
+        let mut interner = StringInterner::default();
+        let mut pair_manager = PairManager::default();
+        let values = parse("(1 2) '(a) (1 . 2)", &mut interner, &mut pair_manager, None).unwrap();
+        let ranges: Vec<_> = values.iter().map(|value| value.1).collect();
+        assert_eq!(ranges, vec![(0, 5, None), (6, 10, None), (17, 18, None)]);
+    }
+
+    #[test]
+    fn deeply_nested_input_does_not_overflow_the_stack() {
+        let depth = 100_000;
+        let code = format!("{}{}", "(".repeat(depth), ")".repeat(depth));
+        test_parse_err(&code, ParseErrorType::TooDeeplyNested);
+        test_parse_err(&"'".repeat(depth), ParseErrorType::TooDeeplyNested);
+
+        let depth = DEFAULT_MAX_PARSE_DEPTH;
+        let code = format!("{}{}", "(".repeat(depth), ")".repeat(depth));
+        test_parse_success(&code, &code);
+    }
+
+    #[test]
+    fn max_depth_is_configurable() {
+        let parse_with_max_depth = |code: &str, max_depth: usize| {
+            let mut interner = StringInterner::default();
+            let mut pair_manager = PairManager::default();
+            Parser::new(
+                code,
+                Tokenizer::new(&code, None),
+                &mut interner,
+                &mut pair_manager,
+            )
+            .with_max_depth(max_depth)
+            .parse_all()
+            .map(|values| values.len())
+            .map_err(|err| err.0)
+        };
+        assert_eq!(parse_with_max_depth("((1)) '(2)", 2), Ok(2));
+        assert_eq!(
+            parse_with_max_depth("(((1)))", 2),
+            Err(ParseErrorType::TooDeeplyNested)
+        );
+        assert_eq!(
+            parse_with_max_depth("''1", 1),
+            Err(ParseErrorType::TooDeeplyNested)
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use std::{cell::RefCell, fmt::Display, rc::Rc};
+
+#[derive(Debug)]
+enum PortInner {
+    StringInput { chars: Vec<char>, position: usize },
+    StringOutput { string: String },
+}
+
+/// A port, which is a source or destination of characters.
+///
+/// Since ports never contain other values, they can't be involved in cycles,
+/// so they don't need to be tracked by the garbage collector.
+#[derive(Debug, Clone)]
+pub struct Port(Rc<RefCell<PortInner>>);
+
+impl Port {
+    /// Creates an input port that reads from the given string.
+    pub fn string_input(string: &str) -> Self {
+        Port(Rc::new(RefCell::new(PortInner::StringInput {
+            chars: string.chars().collect(),
+            position: 0,
+        })))
+    }
+
+    /// Creates an output port that accumulates everything written to it
+    /// into a string.
+    pub fn string_output() -> Self {
+        Port(Rc::new(RefCell::new(PortInner::StringOutput {
+            string: String::new(),
+        })))
+    }
+
+    pub fn points_at_same_memory_as(&self, other: &Port) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    pub fn is_input(&self) -> bool {
+        matches!(&*self.0.borrow(), PortInner::StringInput { .. })
+    }
+
+    pub fn is_output(&self) -> bool {
+        matches!(&*self.0.borrow(), PortInner::StringOutput { .. })
+    }
+
+    /// Returns the next character without consuming it, or `None` if there
+    /// are no more characters available.
+    pub fn peek_char(&self) -> Option<char> {
+        match &*self.0.borrow() {
+            PortInner::StringInput { chars, position } => chars.get(*position).copied(),
+            PortInner::StringOutput { .. } => None,
+        }
+    }
+
+    /// Consumes and returns the next character, or `None` if there are no more
+    /// characters available.
+    pub fn read_char(&self) -> Option<char> {
+        self.read_string(1).and_then(|string| string.chars().next())
+    }
+
+    /// Consumes and returns up to `k` characters, or `None` if there are no more
+    /// characters available.
+    pub fn read_string(&self, k: usize) -> Option<String> {
+        match &mut *self.0.borrow_mut() {
+            PortInner::StringInput { chars, position } => {
+                if k > 0 && *position >= chars.len() {
+                    return None;
+                }
+                let end = position.saturating_add(k).min(chars.len());
+                let string = chars[*position..end].iter().collect();
+                *position = end;
+                Some(string)
+            }
+            PortInner::StringOutput { .. } => None,
+        }
+    }
+
+    /// Consumes and returns all remaining characters, which may be none.
+    pub fn read_remaining(&self) -> String {
+        self.read_string(usize::MAX).unwrap_or_default()
+    }
+
+    /// Writes the given string to the port, which must be an output port.
+    pub fn write_string(&self, value: &str) {
+        match &mut *self.0.borrow_mut() {
+            PortInner::StringOutput { string } => string.push_str(value),
+            PortInner::StringInput { .. } => panic!("Can't write to an input port!"),
+        }
+    }
+
+    /// Returns everything written to the port so far, which must be a string
+    /// output port.
+    pub fn output_string(&self) -> String {
+        match &*self.0.borrow() {
+            PortInner::StringOutput { string } => string.clone(),
+            PortInner::StringInput { .. } => panic!("Input ports don't have output!"),
+        }
+    }
+}
+
+impl Display for Port {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &*self.0.borrow() {
+            PortInner::StringInput { .. } => write!(f, "#<string-input-port>"),
+            PortInner::StringOutput { .. } => write!(f, "#<string-output-port>"),
+        }
+    }
+}
@@ -2,16 +2,21 @@ use crate::{
     bound_procedure::BoundProcedure,
     builtin_procedure::BuiltinProcedure,
     compound_procedure::CompoundProcedure,
+    derived_procedure::DerivedProcedure,
+    gc::{Traverser, Visitor},
     interpreter::{Interpreter, RuntimeError, RuntimeErrorType},
+    record::RecordProcedure,
     source_mapped::{SourceMappable, SourceRange},
     string_interner::InternedString,
-    value::SourceValue,
+    value::{SourceValue, Value},
 };
 
 #[derive(Debug, Clone)]
 pub enum Procedure {
     Compound(CompoundProcedure),
     Builtin(BuiltinProcedure),
+    Record(RecordProcedure),
+    Derived(DerivedProcedure),
 }
 
 impl Procedure {
@@ -19,6 +24,8 @@ impl Procedure {
         match self {
             Procedure::Builtin(builtin) => Some(&builtin.name),
             Procedure::Compound(compound) => compound.name.as_ref(),
+            Procedure::Record(record) => Some(&record.name),
+            Procedure::Derived(derived) => Some(&derived.name),
         }
     }
 
@@ -26,6 +33,8 @@ impl Procedure {
         match self {
             Procedure::Compound(compound) => compound.signature.is_valid_arity(operands_len),
             Procedure::Builtin(builtin) => builtin.is_valid_arity(operands_len),
+            Procedure::Record(record) => record.is_valid_arity(operands_len),
+            Procedure::Derived(derived) => derived.is_valid_arity(operands_len),
         }
     }
 
@@ -60,6 +69,11 @@ impl Procedure {
         let mut evaluated_operands = Vec::with_capacity(operands.len());
         for expr in operands.iter() {
             let value = interpreter.eval_expression(expr)?;
+            // A single value from `values` is already just that value, but
+            // there's no sensible way to pass anything else as one operand.
+            if let Value::MultipleValues(_) = value.0 {
+                return Err(RuntimeErrorType::MultipleValuesAsOperand.source_mapped(expr.1));
+            }
             evaluated_operands.push(value);
         }
         Ok(BoundProcedure {
@@ -69,3 +83,13 @@ impl Procedure {
         })
     }
 }
+
+impl Traverser for Procedure {
+    fn traverse(&self, visitor: &Visitor) {
+        match self {
+            Procedure::Compound(compound) => visitor.traverse(compound),
+            Procedure::Derived(derived) => visitor.traverse(derived),
+            Procedure::Builtin(_) | Procedure::Record(_) => {}
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    callable::CallableResult,
+    gc::{Traverser, Visitor},
+    interpreter::{Interpreter, RuntimeErrorType},
+    object_tracker::{CycleBreaker, ObjectTracker, Tracked},
+    source_mapped::{SourceMappable, SourceRange},
+    string_interner::InternedString,
+    value::{SourceValue, Value},
+};
+
+/// Describes a record type created via `define-record-type`.
+#[derive(Debug)]
+pub struct RecordType {
+    pub name: InternedString,
+    pub fields: Vec<InternedString>,
+}
+
+impl RecordType {
+    /// Returns the name of the record type without any angle brackets around it,
+    /// e.g. `<point>` becomes `point`.
+    pub fn display_name(&self) -> &str {
+        let name = self.name.as_ref();
+        name.strip_prefix('<')
+            .and_then(|name| name.strip_suffix('>'))
+            .unwrap_or(name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordInner {
+    record_type: Rc<RecordType>,
+    fields: RefCell<Vec<SourceValue>>,
+}
+
+impl CycleBreaker for RecordInner {
+    fn break_cycles(&self) {
+        self.fields.borrow_mut().clear();
+    }
+
+    fn debug_name(&self) -> &'static str {
+        "Record"
+    }
+}
+
+impl Traverser for RecordInner {
+    fn traverse(&self, visitor: &Visitor) {
+        visitor.traverse(&self.fields);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Record(Tracked<RecordInner>);
+
+impl Record {
+    fn as_ptr(&self) -> *const RecordInner {
+        &*self.0 as *const RecordInner
+    }
+
+    pub fn points_at_same_memory_as(&self, other: &Record) -> bool {
+        self.as_ptr() == other.as_ptr()
+    }
+
+    pub fn record_type(&self) -> &Rc<RecordType> {
+        &self.0.record_type
+    }
+
+    pub fn is_instance_of(&self, record_type: &Rc<RecordType>) -> bool {
+        Rc::ptr_eq(&self.0.record_type, record_type)
+    }
+
+    pub fn get(&self, index: usize) -> SourceValue {
+        self.0.fields.borrow()[index].clone()
+    }
+
+    pub fn set(&self, index: usize, value: SourceValue) {
+        self.0.fields.borrow_mut()[index] = value;
+    }
+}
+
+impl Traverser for Record {
+    fn traverse(&self, visitor: &Visitor) {
+        visitor.traverse(&self.0);
+    }
+}
+
+#[derive(Default)]
+pub struct RecordManager(ObjectTracker<RecordInner>);
+
+impl RecordManager {
+    pub fn record(&mut self, record_type: Rc<RecordType>, fields: Vec<SourceValue>) -> Record {
+        Record(self.0.track(RecordInner {
+            record_type,
+            fields: RefCell::new(fields),
+        }))
+    }
+
+    pub fn get_stats_as_string(&self) -> String {
+        format!("Records: {}", self.0.stats())
+    }
+
+    pub fn begin_mark(&mut self) {
+        self.0.begin_mark();
+    }
+
+    pub fn sweep(&mut self) -> usize {
+        self.0.sweep()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordProcedureKind {
+    /// Creates a new record. The vec contains the index of the field that each
+    /// argument initializes; any other fields are left undefined.
+    Constructor(Rc<Vec<usize>>),
+    Predicate,
+    /// Accessors and modifiers contain the index of their field, which is
+    /// resolved when the record type is defined, so using them never involves
+    /// looking up fields by name.
+    Accessor(usize),
+    Modifier(usize),
+}
+
+/// A procedure generated by `define-record-type`.
+#[derive(Debug, Clone)]
+pub struct RecordProcedure {
+    pub name: InternedString,
+    pub record_type: Rc<RecordType>,
+    pub kind: RecordProcedureKind,
+}
+
+impl RecordProcedure {
+    pub fn is_valid_arity(&self, operands_len: usize) -> bool {
+        match &self.kind {
+            RecordProcedureKind::Constructor(indices) => operands_len == indices.len(),
+            RecordProcedureKind::Predicate => operands_len == 1,
+            RecordProcedureKind::Accessor(_) => operands_len == 1,
+            RecordProcedureKind::Modifier(_) => operands_len == 2,
+        }
+    }
+
+    pub fn is_same_procedure_as(&self, other: &RecordProcedure) -> bool {
+        Rc::ptr_eq(&self.record_type, &other.record_type) && self.kind == other.kind
+    }
+
+    fn expect_record(
+        &self,
+        value: &SourceValue,
+    ) -> Result<Record, crate::interpreter::RuntimeError> {
+        match &value.0 {
+            Value::Record(record) if record.is_instance_of(&self.record_type) => Ok(record.clone()),
+            _ => Err(
+                RuntimeErrorType::ExpectedRecord(self.record_type.name.clone())
+                    .source_mapped(value.1),
+            ),
+        }
+    }
+
+    pub fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        range: SourceRange,
+        operands: Vec<SourceValue>,
+    ) -> CallableResult {
+        match &self.kind {
+            RecordProcedureKind::Constructor(indices) => {
+                let mut fields: Vec<SourceValue> =
+                    vec![Value::Undefined.into(); self.record_type.fields.len()];
+                for (&index, value) in indices.iter().zip(operands) {
+                    fields[index] = value;
+                }
+                let record = interpreter
+                    .record_manager
+                    .record(self.record_type.clone(), fields);
+                Ok(Value::Record(record).source_mapped(range).into())
+            }
+            RecordProcedureKind::Predicate => Ok(matches!(
+                &operands[0].0,
+                Value::Record(record) if record.is_instance_of(&self.record_type)
+            )
+            .into()),
+            RecordProcedureKind::Accessor(index) => {
+                Ok(self.expect_record(&operands[0])?.get(*index).into())
+            }
+            RecordProcedureKind::Modifier(index) => {
+                self.expect_record(&operands[0])?
+                    .set(*index, operands[1].clone());
+                Ok(Value::Undefined.source_mapped(range).into())
+            }
+        }
+    }
+}
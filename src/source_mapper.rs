@@ -1,5 +1,7 @@
 use std::{cmp::min, collections::HashMap};
 
+use colored::Colorize;
+
 use crate::source_mapped::SourceRange;
 
 #[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
@@ -43,11 +45,31 @@ impl<'a> MappedLine<'a> {
         self.end - self.start
     }
 
-    fn trace(&self) -> Vec<String> {
+    /// Returns a human-readable description of the file and line number.
+    pub fn location(&self) -> String {
+        format!("\"{}\", line {}", self.filename, self.line_number + 1)
+    }
+
+    /// If `colorize` is true, the offending part of the line is shown in bold
+    /// and the carets underneath it are yellow.
+    fn trace(&self, colorize: bool) -> Vec<String> {
+        let carets = "^".repeat(self.len());
+        let (line, carets) = match (
+            colorize,
+            self.line.get(..self.start),
+            self.line.get(self.start..self.end),
+            self.line.get(self.end..),
+        ) {
+            (true, Some(before), Some(highlighted), Some(after)) => (
+                format!("{before}{}{after}", highlighted.bold()),
+                carets.yellow().to_string(),
+            ),
+            _ => (self.line.to_string(), carets),
+        };
         vec![
-            format!("\"{}\", line {}:", self.filename, self.line_number + 1),
-            format!("| {}", self.line),
-            format!("| {}{}", " ".repeat(self.start), "^".repeat(self.len())),
+            format!("{}:", self.location()),
+            format!("| {}", line),
+            format!("| {}{}", " ".repeat(self.start), carets),
         ]
     }
 }
@@ -91,9 +113,23 @@ impl SourceMapper {
         MappedLine::from_source(filename, contents, start, end)
     }
 
+    /// Returns the source code that the given range covers, if it's known.
+    pub fn get_source_text(&self, source_range: &SourceRange) -> Option<&str> {
+        let &(start, end, Some(source_id)) = source_range else {
+            return None;
+        };
+        self.get_contents(source_id).get(start..end)
+    }
+
     pub fn trace(&self, source_range: &SourceRange) -> Vec<String> {
+        self.trace_with_color(source_range, false)
+    }
+
+    /// Like `trace()`, but optionally colorizes the output, as long as the
+    /// environment allows it (e.g. `NO_COLOR` isn't set).
+    pub fn trace_with_color(&self, source_range: &SourceRange, colorize: bool) -> Vec<String> {
         if let Some(first_line) = self.get_first_line(source_range) {
-            first_line.trace()
+            first_line.trace(colorize)
         } else {
             vec!["<Unknown>".to_string()]
         }
@@ -130,11 +166,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_source_text_works() {
+        let (mapper, id) = make_mapper_with_source("hi\nthere");
+        assert_eq!(mapper.get_source_text(&(3, 6, Some(id))), Some("the"));
+        assert_eq!(mapper.get_source_text(&(3, 60, Some(id))), None);
+        assert_eq!(mapper.get_source_text(&(0, 1, None)), None);
+    }
+
     #[test]
     fn trace_works() {
         let (mapper, id) = make_mapper_with_source("hi\nthere");
         assert_eq!(
-            mapper.get_first_line(&(4, 6, Some(id))).unwrap().trace(),
+            mapper
+                .get_first_line(&(4, 6, Some(id)))
+                .unwrap()
+                .trace(false),
             vec![
                 "\"boop.txt\", line 2:".to_string(), //
                 "| there".to_string(),               //
@@ -12,6 +12,7 @@ const MAX_BUFFER_SIZE: usize = 255;
 pub struct StdioPrinter {
     pub disable_autoflush: bool,
     line_buffer: RefCell<String>,
+    captures: RefCell<Vec<String>>,
 }
 
 impl StdioPrinter {
@@ -19,9 +20,25 @@ impl StdioPrinter {
         StdioPrinter {
             disable_autoflush: false,
             line_buffer: String::with_capacity(MAX_BUFFER_SIZE).into(),
+            captures: vec![].into(),
         }
     }
 
+    /// Start capturing everything that's printed, rather than sending it to
+    /// stdout, until `end_capture()` is called. Captures can be nested.
+    pub fn begin_capture(&self) {
+        self.captures.borrow_mut().push(String::new());
+    }
+
+    /// Stop the most recent capture started via `begin_capture()`, returning
+    /// everything that was printed since it began.
+    pub fn end_capture(&self) -> String {
+        self.captures
+            .borrow_mut()
+            .pop()
+            .expect("end_capture() must be preceded by begin_capture()!")
+    }
+
     #[cfg(test)]
     pub fn take_buffered_output(&self) -> String {
         self.line_buffer.take()
@@ -42,6 +59,10 @@ impl StdioPrinter {
 
     /// Print the given string to stdout in a line-buffered way.
     pub fn print<T: AsRef<str>>(&self, value: T) {
+        if let Some(capture) = self.captures.borrow_mut().last_mut() {
+            capture.push_str(value.as_ref());
+            return;
+        }
         for ch in value.as_ref().chars() {
             self.line_buffer.borrow_mut().push(ch);
 
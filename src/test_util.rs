@@ -2,15 +2,56 @@ use std::fs::read_to_string;
 
 use crate::{
     interpreter::{Interpreter, RuntimeErrorType},
+    string_interner::InternedString,
     value::{SourceValue, Value},
 };
 
 pub struct TestInterpreter(Interpreter);
 
 impl TestInterpreter {
+    pub fn new(mut interpreter: Interpreter) -> Self {
+        interpreter.printer.disable_autoflush = true;
+        TestInterpreter(interpreter)
+    }
+
     pub fn get(&mut self, name: &'static str) -> Option<SourceValue> {
         self.0.environment.get(&self.0.string_interner.intern(name))
     }
+
+    pub fn intern(&mut self, name: &'static str) -> InternedString {
+        self.0.string_interner.intern(name)
+    }
+
+    pub fn eval_success(&mut self, code: &'static str, expected_value: &'static str) {
+        let source_id = self.0.source_mapper.add("<code>".into(), code.into());
+        match self.0.evaluate(source_id) {
+            Ok(value) => {
+                let value = match value.0 {
+                    Value::Undefined => "".to_string(),
+                    _ => value.to_string(),
+                };
+                let output = self.0.printer.take_buffered_output();
+                let final_value = format!("{output}{value}");
+                assert_eq!(final_value, expected_value, "Evaluating code '{code}'");
+            }
+            Err(err) => {
+                self.0.show_err_and_traceback(err);
+                panic!("Evaluating code '{code}' raised error");
+            }
+        }
+    }
+
+    pub fn eval_err(&mut self, code: &'static str, expected_err: RuntimeErrorType) {
+        let source_id = self.0.source_mapper.add("<code>".into(), code.into());
+        match self.0.evaluate(source_id) {
+            Ok(value) => {
+                panic!("Evaluating code '{code}' did not raise error and returned {value}");
+            }
+            Err(err) => {
+                assert_eq!(err.0, expected_err);
+            }
+        }
+    }
 }
 
 pub fn eval_test_file(filename: &str) {
@@ -21,16 +21,24 @@ pub enum TokenType {
     Dot,
     Apostrophe,
     String,
+    Character,
     Undefined,
 }
 
 pub type Token = SourceMapped<TokenType>;
 
+/// Returns the character named by the hex digits of a `\xHH;` escape, if
+/// they're valid.
+pub fn parse_hex_escape(digits: &str) -> Option<char> {
+    char::from_u32(u32::from_str_radix(digits, 16).ok()?)
+}
+
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum TokenizeErrorType {
     UnexpectedCharacter,
     UnterminatedString,
     UnsupportedEscapeSequence,
+    InvalidHexEscape,
 }
 
 pub type TokenizeError = SourceMapped<TokenizeErrorType>;
@@ -96,6 +104,9 @@ impl<'a> Tokenizer<'a> {
 
     fn try_accept_sharp(&mut self) -> Option<Result<TokenType, TokenizeErrorType>> {
         if self.accept_char('#') {
+            if self.accept_char('\\') {
+                return Some(self.accept_character_rest());
+            }
             let mut chars = vec![];
             loop {
                 if let Some(&(pos, next_char)) = self.chars.peek() {
@@ -124,11 +135,48 @@ impl<'a> Tokenizer<'a> {
         }
     }
 
+    /// Accepts the rest of a character literal, after its `#\` prefix. The
+    /// character immediately after the prefix is always consumed, even if it's
+    /// a delimiter like `(`; if it's alphabetic, any alphabetic characters that
+    /// follow are consumed too, so that named characters like `#\space` work.
+    fn accept_character_rest(&mut self) -> Result<TokenType, TokenizeErrorType> {
+        let Some(&(_pos, first_char)) = self.chars.peek() else {
+            return Err(TokenizeErrorType::UnexpectedCharacter);
+        };
+        self.chomp();
+        if first_char.is_alphabetic() {
+            self.chomp_while(|char| char.is_alphabetic());
+        }
+        Ok(TokenType::Character)
+    }
+
+    /// Accepts the rest of an R7RS hex escape in a string, after its `\x`
+    /// prefix, e.g. the `41;` in `\x41;`. The digits must be terminated by a
+    /// semicolon and name a valid Unicode scalar value.
+    fn accept_hex_escape_rest(&mut self) -> Result<(), TokenizeErrorType> {
+        let mut digits = String::new();
+        while let Some(&(_pos, next_char)) = self.chars.peek() {
+            if !next_char.is_ascii_hexdigit() {
+                break;
+            }
+            self.chomp();
+            digits.push(next_char);
+        }
+        if !self.accept_char(';') || parse_hex_escape(&digits).is_none() {
+            return Err(TokenizeErrorType::InvalidHexEscape);
+        }
+        Ok(())
+    }
+
     fn try_accept_string(&mut self) -> Option<Result<TokenType, TokenizeErrorType>> {
         if self.accept_char('"') {
             loop {
                 if self.accept_char('\\') {
-                    if !self.accept(|c| matches!(c, '\\' | '"' | 'n')) {
+                    if self.accept_char('x') {
+                        if let Err(err) = self.accept_hex_escape_rest() {
+                            return Some(Err(err));
+                        }
+                    } else if !self.accept(|c| matches!(c, '\\' | '"' | 'n' | 't' | 'r' | '0')) {
                         return Some(Err(TokenizeErrorType::UnsupportedEscapeSequence));
                     }
                 } else if self.accept_char('"') {
@@ -326,6 +374,49 @@ mod tests {
         )
     }
 
+    #[test]
+    fn characters_work() {
+        test_tokenize(
+            r"#\a #\space #\( #\)",
+            &[
+                (Ok(Character), r"#\a"),
+                (Ok(Character), r"#\space"),
+                (Ok(Character), r"#\("),
+                (Ok(Character), r"#\)"),
+            ],
+        );
+        test_tokenize(
+            r"(#\a)",
+            &[
+                (Ok(LeftParen), "("),
+                (Ok(Character), r"#\a"),
+                (Ok(RightParen), ")"),
+            ],
+        );
+        test_tokenize(
+            r#"#\; #\" #\' #\#"#,
+            &[
+                (Ok(Character), r"#\;"),
+                (Ok(Character), r#"#\""#),
+                (Ok(Character), r"#\'"),
+                (Ok(Character), r"#\#"),
+            ],
+        );
+        test_tokenize(
+            r"#\(( #\a1",
+            &[
+                (Ok(Character), r"#\("),
+                (Ok(LeftParen), "("),
+                (Ok(Character), r"#\a"),
+                (Ok(Number), "1"),
+            ],
+        );
+        test_tokenize(
+            r"#\",
+            &[(Err(TokenizeErrorType::UnexpectedCharacter), r"#\")],
+        );
+    }
+
     #[test]
     fn comment_works() {
         test_tokenize(
@@ -352,4 +443,47 @@ mod tests {
             &[(Err(TokenizeErrorType::UnterminatedString), r#""hi "#)],
         );
     }
+
+    #[test]
+    fn string_escapes_work() {
+        test_tokenize(r#""a\tb""#, &[(Ok(String), r#""a\tb""#)]);
+        test_tokenize(r#""a\rb""#, &[(Ok(String), r#""a\rb""#)]);
+        test_tokenize(r#""a\0b""#, &[(Ok(String), r#""a\0b""#)]);
+        test_tokenize(r#""a\x41;b""#, &[(Ok(String), r#""a\x41;b""#)]);
+        test_tokenize(r#""\x1F600;""#, &[(Ok(String), r#""\x1F600;""#)]);
+        test_tokenize(
+            r#""a\q"#,
+            &[
+                (Err(TokenizeErrorType::UnsupportedEscapeSequence), r#""a\"#),
+                (Ok(Identifier), "q"),
+            ],
+        );
+    }
+
+    #[test]
+    fn malformed_hex_escapes_raise_errors() {
+        test_tokenize(
+            r#""\x41"#,
+            &[(Err(TokenizeErrorType::InvalidHexEscape), r#""\x41"#)],
+        );
+        test_tokenize(
+            r#""\x;"#,
+            &[(Err(TokenizeErrorType::InvalidHexEscape), r#""\x;"#)],
+        );
+        test_tokenize(
+            r#""\xZZ"#,
+            &[
+                (Err(TokenizeErrorType::InvalidHexEscape), r#""\x"#),
+                (Ok(Identifier), "ZZ"),
+            ],
+        );
+        test_tokenize(
+            r#""\xD800;"#,
+            &[(Err(TokenizeErrorType::InvalidHexEscape), r#""\xD800;"#)],
+        );
+        test_tokenize(
+            r#""\x110000;"#,
+            &[(Err(TokenizeErrorType::InvalidHexEscape), r#""\x110000;"#)],
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use crate::string_interner::InternedString;
 
@@ -6,15 +6,63 @@ use crate::string_interner::InternedString;
 pub struct TrackedCallableStats {
     calls: usize,
     tail_calls: usize,
+    /// Total time spent in the callable, if profiling.
+    time: Duration,
+    /// The number of calls to the callable that are currently being timed.
+    active_timings: usize,
 }
 
 #[derive(Default)]
 pub struct TrackedStats {
     max_call_stack_depth: usize,
     callable_calls: HashMap<InternedString, TrackedCallableStats>,
+    profiling: bool,
 }
 
 impl TrackedStats {
+    /// Creates stats that also record how much time is spent in each callable.
+    pub fn with_profiling() -> Self {
+        TrackedStats {
+            profiling: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_profiling(&self) -> bool {
+        self.profiling
+    }
+
+    /// Called when we start timing a call to the given callable.
+    pub fn begin_timing(&mut self, name: Option<&InternedString>) {
+        if let Some(name) = name {
+            let stats = self.callable_calls.entry(name.clone()).or_default();
+            stats.active_timings += 1;
+        }
+    }
+
+    /// Called when a call that we started timing has finished.
+    ///
+    /// Only the outermost call to a callable contributes to its total time,
+    /// so recursive calls aren't counted more than once.
+    pub fn end_timing(&mut self, name: Option<&InternedString>, elapsed: Duration) {
+        if let Some(name) = name {
+            let stats = self.callable_calls.entry(name.clone()).or_default();
+            stats.active_timings -= 1;
+            if stats.active_timings == 0 {
+                stats.time += elapsed;
+            }
+        }
+    }
+
+    /// Returns the total time spent in the given callable, if profiling.
+    #[cfg(test)]
+    pub fn time_spent(&self, name: &str) -> Option<Duration> {
+        self.callable_calls
+            .iter()
+            .find(|(callable_name, _)| callable_name.as_ref() == name)
+            .map(|(_, stats)| stats.time)
+    }
+
     pub fn update_call_stack_depth(&mut self, new_depth: usize) {
         if new_depth > self.max_call_stack_depth {
             self.max_call_stack_depth = new_depth;
@@ -35,20 +83,40 @@ impl TrackedStats {
         }
     }
 
+    /// Returns the name, number of calls, and number of tail calls of every
+    /// callable that was called, sorted by name.
+    pub fn call_counts(&self) -> Vec<(InternedString, usize, usize)> {
+        let mut counts = self
+            .callable_calls
+            .iter()
+            .map(|(name, stats)| (name.clone(), stats.calls, stats.tail_calls))
+            .collect::<Vec<_>>();
+        counts.sort_by(|a, b| a.0.as_ref().cmp(b.0.as_ref()));
+        counts
+    }
+
     pub fn as_table(&self) -> String {
         let mut lines = vec![];
-        lines.push(format!("{:40} {:8} {:12}", "Name", "Calls", "Tail calls"));
-        lines.push("-".repeat(60));
+        let mut header = format!("{:40} {:8} {:12}", "Name", "Calls", "Tail calls");
+        if self.profiling {
+            header.push_str(&format!(" {:>10}", "Time (ms)"));
+        }
+        lines.push(header);
+        lines.push("-".repeat(if self.profiling { 71 } else { 60 }));
         let mut table_lines = self
             .callable_calls
             .iter()
             .map(|(name, stats)| {
-                format!(
+                let mut line = format!(
                     "{:40} {:8} {:12}",
                     name.to_string(),
                     stats.calls.to_string(),
                     stats.tail_calls.to_string()
-                )
+                );
+                if self.profiling {
+                    line.push_str(&format!(" {:>10.3}", stats.time.as_secs_f64() * 1000.0));
+                }
+                line
             })
             .collect::<Vec<String>>();
         table_lines.sort();
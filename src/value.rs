@@ -2,15 +2,24 @@ use std::{fmt::Display, rc::Rc};
 
 use crate::{
     callable::Callable,
+    environment::CapturedLexicalScope,
     gc::{Traverser, Visitor},
+    hash_table::HashTable,
     interpreter::{RuntimeError, RuntimeErrorType},
     mutable_string::MutableString,
-    pair::Pair,
+    pair::{Pair, PairVisitedSet},
+    port::Port,
     procedure::Procedure,
+    record::Record,
     source_mapped::{SourceMappable, SourceMapped},
     string_interner::InternedString,
+    vector::{Vector, VectorVisitedSet},
 };
 
+/// The largest magnitude below which every integer can be represented
+/// exactly as a float, i.e. 2^53.
+pub const MAX_EXACT_INTEGER: f64 = 9_007_199_254_740_992.0;
+
 impl SourceMapped<Value> {
     pub fn expect_identifier(&self) -> Result<InternedString, RuntimeError> {
         if let Value::Symbol(symbol) = &self.0 {
@@ -28,6 +37,134 @@ impl SourceMapped<Value> {
         }
     }
 
+    pub fn expect_boolean(&self) -> Result<bool, RuntimeError> {
+        if let Value::Boolean(boolean) = self.0 {
+            Ok(boolean)
+        } else {
+            Err(RuntimeErrorType::ExpectedBoolean.source_mapped(self.1))
+        }
+    }
+
+    pub fn expect_string(&self) -> Result<MutableString, RuntimeError> {
+        if let Value::String(string) = &self.0 {
+            Ok(string.clone())
+        } else {
+            Err(RuntimeErrorType::ExpectedString.source_mapped(self.1))
+        }
+    }
+
+    pub fn expect_character(&self) -> Result<char, RuntimeError> {
+        if let Value::Character(char) = self.0 {
+            Ok(char)
+        } else {
+            Err(RuntimeErrorType::ExpectedCharacter.source_mapped(self.1))
+        }
+    }
+
+    pub fn expect_vector(&self) -> Result<Vector, RuntimeError> {
+        if let Value::Vector(vector) = &self.0 {
+            Ok(vector.clone())
+        } else {
+            Err(RuntimeErrorType::ExpectedVector.source_mapped(self.1))
+        }
+    }
+
+    /// Since all our numbers are floats, this accepts any finite, whole-valued
+    /// number.
+    pub fn expect_integer(&self) -> Result<f64, RuntimeError> {
+        match self.0 {
+            Value::Number(number) if number.is_finite() && number.fract() == 0.0 => Ok(number),
+            _ => Err(RuntimeErrorType::ExpectedInteger.source_mapped(self.1)),
+        }
+    }
+
+    /// Accepts an integer whose magnitude is at most `MAX_EXACT_INTEGER`, for
+    /// operations that would give wrong answers for larger ones.
+    pub fn expect_exact_integer(&self) -> Result<f64, RuntimeError> {
+        let number = self.expect_integer()?;
+        if number.abs() > MAX_EXACT_INTEGER {
+            return Err(RuntimeErrorType::IntegerTooLarge {
+                integer: number,
+                max: MAX_EXACT_INTEGER,
+            }
+            .source_mapped(self.1));
+        }
+        Ok(number)
+    }
+
+    /// Accepts an integer that can be used as a count or size.
+    pub fn expect_non_negative_integer(&self) -> Result<usize, RuntimeError> {
+        match self.expect_integer() {
+            Ok(number) if number >= 0.0 => Ok(number as usize),
+            _ => Err(RuntimeErrorType::ExpectedNonNegativeInteger.source_mapped(self.1)),
+        }
+    }
+
+    /// Accepts a non-negative integer no larger than `max`, e.g. the size of
+    /// something that's about to be allocated.
+    pub fn expect_size(&self, max: usize) -> Result<usize, RuntimeError> {
+        let size = self.expect_non_negative_integer()?;
+        if size > max {
+            return Err(RuntimeErrorType::SizeTooLarge {
+                size: self.expect_number()?,
+                max,
+            }
+            .source_mapped(self.1));
+        }
+        Ok(size)
+    }
+
+    /// Accepts an integer that is a valid index into a sequence of the given
+    /// length.
+    pub fn expect_index(&self, length: usize) -> Result<usize, RuntimeError> {
+        self.expect_index_below(length, length)
+    }
+
+    /// Like `expect_index()`, but also accepts the length of the sequence
+    /// itself, e.g. to refer to the (empty) end of a list.
+    pub fn expect_index_or_end(&self, length: usize) -> Result<usize, RuntimeError> {
+        self.expect_index_below(length + 1, length)
+    }
+
+    fn expect_index_below(&self, limit: usize, length: usize) -> Result<usize, RuntimeError> {
+        let index = self.expect_integer()?;
+        if index < 0.0 || index >= limit as f64 {
+            Err(RuntimeErrorType::IndexOutOfRange { index, length }.source_mapped(self.1))
+        } else {
+            Ok(index as usize)
+        }
+    }
+
+    pub fn expect_input_port(&self) -> Result<Port, RuntimeError> {
+        match &self.0 {
+            Value::Port(port) if port.is_input() => Ok(port.clone()),
+            _ => Err(RuntimeErrorType::ExpectedInputPort.source_mapped(self.1)),
+        }
+    }
+
+    pub fn expect_output_port(&self) -> Result<Port, RuntimeError> {
+        match &self.0 {
+            Value::Port(port) if port.is_output() => Ok(port.clone()),
+            _ => Err(RuntimeErrorType::ExpectedOutputPort.source_mapped(self.1)),
+        }
+    }
+
+    pub fn expect_hash_table(&self) -> Result<HashTable, RuntimeError> {
+        if let Value::HashTable(hash_table) = &self.0 {
+            Ok(hash_table.clone())
+        } else {
+            Err(RuntimeErrorType::ExpectedHashTable.source_mapped(self.1))
+        }
+    }
+
+    pub fn expect_environment(&self) -> Result<CapturedLexicalScope, RuntimeError> {
+        if let Value::Environment(environment) = &self.0 {
+            Ok(environment.clone())
+        } else {
+            Err(RuntimeErrorType::ExpectedEnvironment.source_mapped(self.1))
+        }
+    }
+
     pub fn expect_pair(&self) -> Result<Pair, RuntimeError> {
         if let Value::Pair(pair) = &self.0 {
             Ok(pair.clone())
@@ -36,11 +173,16 @@ impl SourceMapped<Value> {
         }
     }
 
+    /// Special forms can't be passed around like procedures, since they need
+    /// their operands unevaluated, so we raise a more specific error for them.
     pub fn expect_procedure(&self) -> Result<Procedure, RuntimeError> {
-        if let Value::Callable(Callable::Procedure(procedure)) = &self.0 {
-            Ok(procedure.clone())
-        } else {
-            Err(RuntimeErrorType::ExpectedProcedure.source_mapped(self.1))
+        match &self.0 {
+            Value::Callable(Callable::Procedure(procedure)) => Ok(procedure.clone()),
+            Value::Callable(Callable::SpecialForm(special_form)) => Err(
+                RuntimeErrorType::SpecialFormIsNotProcedure(special_form.name.clone())
+                    .source_mapped(self.1),
+            ),
+            _ => Err(RuntimeErrorType::ExpectedProcedure.source_mapped(self.1)),
         }
     }
 
@@ -68,8 +210,19 @@ pub enum Value {
     Symbol(InternedString),
     Boolean(bool),
     String(MutableString),
+    Character(char),
     Callable(Callable),
     Pair(Pair),
+    Record(Record),
+    Vector(Vector),
+    HashTable(HashTable),
+    /// The result of calling `values` with anything other than a single value.
+    MultipleValues(Rc<Vec<SourceValue>>),
+    Port(Port),
+    /// The value returned when a port has no more data.
+    EofObject,
+    /// A first-class environment, e.g. from `interaction-environment`.
+    Environment(CapturedLexicalScope),
 }
 
 impl Value {
@@ -85,6 +238,30 @@ impl Value {
             _ => true,
         }
     }
+
+    /// Returns a human-readable name for the type of the value, for use in
+    /// error messages.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Undefined => "undefined",
+            Value::EmptyList => "empty list",
+            Value::Number(_) => "number",
+            Value::Symbol(_) => "symbol",
+            Value::Boolean(_) => "boolean",
+            Value::String(_) => "string",
+            Value::Character(_) => "character",
+            Value::Callable(Callable::SpecialForm(_)) => "special form",
+            Value::Callable(Callable::Procedure(_)) => "procedure",
+            Value::Pair(_) => "pair",
+            Value::Record(_) => "record",
+            Value::Vector(_) => "vector",
+            Value::HashTable(_) => "hash table",
+            Value::MultipleValues(_) => "multiple values",
+            Value::Port(_) => "port",
+            Value::EofObject => "eof object",
+            Value::Environment(_) => "environment",
+        }
+    }
 }
 
 impl SourceMapped<Value> {
@@ -108,14 +285,37 @@ impl Traverser for Value {
             Value::Pair(pair) => {
                 visitor.traverse(pair);
             }
-            Value::Callable(Callable::Procedure(Procedure::Compound(compound))) => {
-                visitor.traverse(compound);
+            Value::Record(record) => {
+                visitor.traverse(record);
+            }
+            Value::Vector(vector) => {
+                visitor.traverse(vector);
+            }
+            Value::HashTable(hash_table) => {
+                visitor.traverse(hash_table);
+            }
+            Value::MultipleValues(values) => {
+                visitor.traverse(values);
+            }
+            Value::Callable(Callable::Procedure(procedure)) => {
+                visitor.traverse(procedure);
+            }
+            Value::Environment(environment) => {
+                visitor.traverse(environment);
             }
             _ => {}
         }
     }
 }
 
+/// The lists and vectors that are currently being displayed, so that a value
+/// which contains itself is shown with a marker instead of infinitely.
+#[derive(Default)]
+pub struct DisplayPath {
+    pairs: PairVisitedSet,
+    vectors: VectorVisitedSet,
+}
+
 impl Display for Value {
     /// This displays a representation of the value as it would
     /// ordinarily be shown in a REPL.
@@ -124,9 +324,24 @@ impl Display for Value {
     /// a representation that would be shown via the `display` function (e.g.,
     /// strings are not shown with quotes around them).
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_with_path(f, &mut DisplayPath::default())
+    }
+}
+
+impl Value {
+    /// Like `fmt()`, but the given lists and vectors are known to be in the
+    /// middle of being displayed.
+    pub fn fmt_with_path(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        path: &mut DisplayPath,
+    ) -> std::fmt::Result {
         match self {
             Value::Undefined => write!(f, "#!void"),
             Value::EmptyList => write!(f, "()"),
+            // Rust's formatting is already the shortest string that parses
+            // back to the same float. Until we distinguish exact from inexact
+            // numbers, whole numbers print without a decimal point.
             Value::Number(value) => write!(f, "{}", value),
             Value::Symbol(name) => write!(f, "{}", name),
             Value::String(string) => {
@@ -137,13 +352,47 @@ impl Display for Value {
                 }
             }
             Value::Pair(pair) => {
-                match pair.try_get_vec_pair() {
-                    Some(vec_pair) => vec_pair.fmt(f),
-                    None => {
-                        // TODO: Implement display for cyclic lists.
-                        write!(f, "<CYCLIC LIST>")
+                // TODO: Implement display for cyclic lists.
+                let Some(vec_pair) = pair.try_get_vec_pair() else {
+                    return write!(f, "<CYCLIC LIST>");
+                };
+                if !path.pairs.add(pair) {
+                    return write!(f, "<CYCLIC LIST>");
+                }
+                let result = vec_pair.fmt_with_path(f, path);
+                path.pairs.remove(pair);
+                result
+            }
+            Value::Character(char) => {
+                if f.alternate() {
+                    write!(f, "{}", char)
+                } else {
+                    write!(f, "{}", character_repr(*char))
+                }
+            }
+            Value::Vector(vector) => {
+                if !path.vectors.add(vector) {
+                    return write!(f, "<CYCLIC VECTOR>");
+                }
+                let result = vector.fmt_with_path(f, path);
+                path.vectors.remove(vector);
+                result
+            }
+            Value::HashTable(hash_table) => write!(f, "#<hash-table {}>", hash_table.len()),
+            Value::Port(port) => port.fmt(f),
+            Value::EofObject => write!(f, "#<eof>"),
+            Value::Environment(_) => write!(f, "#<environment>"),
+            Value::MultipleValues(values) => {
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
                     }
+                    value.0.fmt_with_path(f, path)?;
                 }
+                Ok(())
+            }
+            Value::Record(record) => {
+                write!(f, "#<record {}>", record.record_type().display_name())
             }
             Value::Boolean(boolean) => write!(f, "{}", if *boolean { "#t" } else { "#f" }),
             Value::Callable(Callable::SpecialForm(special_form)) => {
@@ -161,10 +410,32 @@ impl Display for Value {
                 },
                 compound.id()
             ),
+            Value::Callable(Callable::Procedure(Procedure::Record(record))) => {
+                write!(f, "#<procedure {}>", record.name.as_ref())
+            }
+            Value::Callable(Callable::Procedure(Procedure::Derived(derived))) => {
+                write!(
+                    f,
+                    "#<procedure {} #{}>",
+                    derived.name.as_ref(),
+                    derived.id()
+                )
+            }
         }
     }
 }
 
+/// Returns the representation of the given character as it would be
+/// written in source code, e.g. `#\a` or `#\space`.
+fn character_repr(char: char) -> String {
+    match char {
+        ' ' => "#\\space".to_string(),
+        '\n' => "#\\newline".to_string(),
+        '\t' => "#\\tab".to_string(),
+        _ => format!("#\\{}", char),
+    }
+}
+
 impl From<bool> for Value {
     fn from(value: bool) -> Self {
         Value::Boolean(value)
@@ -0,0 +1,124 @@
+use std::{
+    cell::{Ref, RefCell, RefMut},
+    collections::HashSet,
+};
+
+use crate::{
+    gc::{Traverser, Visitor},
+    object_tracker::{CycleBreaker, ObjectTracker, Tracked},
+    value::{DisplayPath, SourceValue},
+};
+
+type VectorPtr = *const RefCell<Vec<SourceValue>>;
+
+impl CycleBreaker for RefCell<Vec<SourceValue>> {
+    fn break_cycles(&self) {
+        self.borrow_mut().clear();
+    }
+
+    fn debug_name(&self) -> &'static str {
+        "Vector"
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Vector(Tracked<RefCell<Vec<SourceValue>>>);
+
+impl Vector {
+    fn as_ptr(&self) -> VectorPtr {
+        &*self.0 as VectorPtr
+    }
+
+    pub fn points_at_same_memory_as(&self, other: &Vector) -> bool {
+        self.as_ptr() == other.as_ptr()
+    }
+
+    pub fn items(&self) -> Ref<'_, Vec<SourceValue>> {
+        self.0.borrow()
+    }
+
+    pub fn items_mut(&self) -> RefMut<'_, Vec<SourceValue>> {
+        self.0.borrow_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    pub fn set_items(&self, items: Vec<SourceValue>) {
+        *self.0.borrow_mut() = items;
+    }
+}
+
+impl Traverser for Vector {
+    fn traverse(&self, visitor: &Visitor) {
+        visitor.traverse(&self.0);
+    }
+}
+
+impl Vector {
+    pub fn fmt_with_path(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        path: &mut DisplayPath,
+    ) -> std::fmt::Result {
+        write!(f, "#(")?;
+        let items = self.items();
+        let len = items.len();
+        for (i, item) in items.iter().enumerate() {
+            item.0.fmt_with_path(f, path)?;
+            if i < len - 1 {
+                write!(f, " ")?;
+            }
+        }
+        write!(f, ")")
+    }
+}
+
+#[derive(Default)]
+pub struct VectorManager(ObjectTracker<RefCell<Vec<SourceValue>>>);
+
+impl VectorManager {
+    pub fn vector(&mut self, items: Vec<SourceValue>) -> Vector {
+        Vector(self.0.track(RefCell::new(items)))
+    }
+
+    pub fn get_stats_as_string(&self) -> String {
+        format!("Vectors: {}", self.0.stats())
+    }
+
+    pub fn begin_mark(&mut self) {
+        self.0.begin_mark();
+    }
+
+    pub fn sweep(&mut self) -> usize {
+        self.0.sweep()
+    }
+}
+
+/// Keeps track of vectors we've already visited, e.g. to detect cycles.
+#[derive(Default)]
+pub struct VectorVisitedSet(HashSet<VectorPtr>);
+
+impl VectorVisitedSet {
+    /// Returns whether the set did not previously contain the given vector.
+    pub fn add(&mut self, vector: &Vector) -> bool {
+        self.0.insert(vector.as_ptr())
+    }
+
+    pub fn remove(&mut self, vector: &Vector) {
+        self.0.remove(&vector.as_ptr());
+    }
+}
+
+/// Keeps track of pairs of vectors, e.g. to avoid infinitely comparing
+/// cyclic structures to each other.
+#[derive(Default)]
+pub struct VectorComparisonSet(HashSet<(VectorPtr, VectorPtr)>);
+
+impl VectorComparisonSet {
+    /// Returns whether the set did not previously contain the given vectors.
+    pub fn add(&mut self, a: &Vector, b: &Vector) -> bool {
+        self.0.insert((a.as_ptr(), b.as_ptr()))
+    }
+}